@@ -1,17 +1,69 @@
-use crate::GatewayState;
-use reqwest::header::{HeaderMap, HeaderValue};
-use serde::{Deserialize, Serialize};
+use crate::daemon_install::{self, DaemonInstall, InstallAction};
+use crate::daemon_logs::{self, DaemonLogLine};
+use crate::error::CommandError;
+use crate::gateway_client::{idempotency_key, GatewayClient, GatewayError, GatewayTls};
+use crate::gateway_endpoint::GatewayEndpoint;
+use crate::gateway_launch::{workspace_root, GatewayLaunch};
+use crate::gateway_logs::GatewayLogLine;
+use crate::gateway_profiles::{GatewayProfile, GatewayProfiles, GatewayTarget, DEFAULT_PROFILE};
+use crate::gateway_remote::RemoteGateway;
+use crate::host_telemetry::{merge_events, HostTelemetry};
+use crate::inflight::InflightRequests;
+use crate::linux_daemon::{self, LinuxBackend, PidfileRunner};
+use crate::models::{
+    AgentActionResult, AgentBudget, AgentConfigPatch, AgentConnectionTestResult, AgentCreatePayload, AgentFullConfig,
+    AgentProfile, AnalyticsExportResult, AnalyticsSnapshot, AuditLogEntry, BudgetSnapshot, ChatStreamEvent,
+    CheckpointRecord, ClawHubInstallResult, ClawHubLiveSkillsResult, ClawHubSkill, ConnectedModelProvider,
+    GatewayHealth, HealthSnapshot, HealthTelemetryEvent, IntegrationEntry, IntegrationsListResult, McpInvokeResult,
+    McpServerEntry, McpToolEntry, PermissionGrant, SwarmChatMessage, SwarmChatThread, SwarmSummary, TaskCreatePayload,
+    TaskPatch, TaskRecord, TelemetryExportResult, VaultDepositPayload, VaultEntry, VaultEntryPatch, VaultStorageInfo,
+    VaultStorageStats, VaultSummary, VaultVersion,
+};
+use crate::preflight::{self, PreflightInput, PreflightReport};
+use crate::read_cache::{Cached, ReadCache};
+use crate::secret_store::SecretStore;
+use crate::systemd_unit::{self, ResourceLimits, SystemdUnit, UnitVerification};
+use crate::session_token::{generate_session_token, persist_session_token, profile_session_token, stored_session_token};
+use crate::supervisor::{Supervisor, SupervisorStatus};
+use crate::write_queue::{self, QueuedWrite, WriteConflict, WriteOp, WriteOutcome, WriteQueue};
+use crate::{GatewayState, ProfileRuntime};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+use tauri::ipc::Channel;
 use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
 
-const GATEWAY_PORT: u16 = 4455;
+/// Who runs the gateway the host is talking to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayOwner {
+    /// A child process this host spawned and supervises.
+    App,
+    /// The always-on launchd/systemd/Windows service; the host only attaches.
+    Daemon,
+    /// Started some other way (by hand, or by another desktop host); the host only attaches.
+    External,
+    /// A gateway on another machine.
+    Remote,
+    /// Nothing is running.
+    None,
+}
 
 #[derive(Serialize)]
 pub struct GatewayStatus {
     running: bool,
+    host: String,
     port: u16,
+    /// `local` when this host spawns the gateway, `remote` when it only connects to one.
+    mode: &'static str,
+    owner: GatewayOwner,
+    /// Name of the active gateway profile.
+    profile: String,
+    #[serde(rename = "baseUrl")]
+    base_url: String,
 }
 
 #[derive(Serialize)]
@@ -29,15 +81,17 @@ pub struct GatewayDaemonStatus {
     #[serde(rename = "lastError")]
     last_error: Option<String>,
     message: String,
-}
-
-#[derive(Serialize)]
-pub struct GatewayHealth {
-    status: String,
-    #[serde(rename = "startedAt")]
-    started_at: Option<String>,
-    #[serde(rename = "instanceCount")]
-    instance_count: Option<u64>,
+    /// Result of `systemd-analyze verify` on the unit just written (Linux installs only).
+    #[serde(rename = "unitVerification", skip_serializing_if = "Option::is_none")]
+    unit_verification: Option<UnitVerification>,
+    /// How the rendered definition compared with the installed one (installs only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    install: Option<DaemonInstall>,
+    /// What keeps the gateway running: `launchd`, `systemd`, `xdg_autostart`, `pidfile` or `windows_service`.
+    backend: Option<&'static str>,
+    /// Why no backend can be used on this host, when `supported` is false.
+    #[serde(rename = "unsupportedReason")]
+    unsupported_reason: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -50,577 +104,60 @@ pub struct RedPhoneResult {
     audit_log: Option<AuditLogEntry>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AgentProfile {
-    id: String,
-    name: String,
-    role: String,
-    #[serde(rename = "modelProfileId")]
-    model_profile_id: String,
-    status: String,
-    #[serde(rename = "parentId")]
-    parent_id: Option<String>,
-    #[serde(rename = "modelProvider")]
-    model_provider: Option<String>,
-    #[serde(rename = "modelName")]
-    model_name: Option<String>,
-    temperature: Option<f64>,
-    #[serde(rename = "maxTokens")]
-    max_tokens: Option<u32>,
-    #[serde(rename = "lastHeartbeat")]
-    last_heartbeat: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AgentStats {
-    #[serde(rename = "agentId")]
-    agent_id: String,
-    #[serde(rename = "tokensToday")]
-    tokens_today: u64,
-    #[serde(rename = "tasksCompleted")]
-    tasks_completed: u64,
-    #[serde(rename = "lastUpdated")]
-    last_updated: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AgentFullConfig {
-    id: String,
-    name: String,
-    role: String,
-    #[serde(rename = "modelProfileId")]
-    model_profile_id: String,
-    status: String,
-    #[serde(rename = "parentId")]
-    parent_id: Option<String>,
-    #[serde(rename = "modelProvider")]
-    model_provider: Option<String>,
-    #[serde(rename = "modelName")]
-    model_name: Option<String>,
-    temperature: Option<f64>,
-    #[serde(rename = "maxTokens")]
-    max_tokens: Option<u32>,
-    #[serde(rename = "lastHeartbeat")]
-    last_heartbeat: Option<String>,
-    #[serde(rename = "apiKeyMasked")]
-    api_key_masked: Option<String>,
-    #[serde(rename = "installedSkills")]
-    installed_skills: Vec<String>,
-    stats: AgentStats,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AgentCreatePayload {
-    name: String,
-    role: String,
-    #[serde(rename = "parentId")]
-    parent_id: Option<String>,
-    #[serde(rename = "modelProvider")]
-    model_provider: String,
-    #[serde(rename = "modelName")]
-    model_name: String,
-    #[serde(rename = "apiKey")]
-    api_key: Option<String>,
-    temperature: Option<f64>,
-    #[serde(rename = "maxTokens")]
-    max_tokens: Option<u32>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AgentConfigPatch {
-    #[serde(rename = "modelProvider")]
-    model_provider: Option<String>,
-    #[serde(rename = "modelName")]
-    model_name: Option<String>,
-    #[serde(rename = "apiKey")]
-    api_key: Option<String>,
-    temperature: Option<f64>,
-    #[serde(rename = "maxTokens")]
-    max_tokens: Option<u32>,
-    status: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AgentActionResult {
-    success: bool,
-    message: String,
-    action: String,
-    #[serde(rename = "agentId")]
-    agent_id: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AgentConnectionTestResult {
-    ok: bool,
-    message: String,
-    status: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct TaskRecord {
-    id: String,
-    title: String,
-    description: Option<String>,
-    status: String,
-    priority: String,
-    #[serde(rename = "assigneeAgentId")]
-    assignee_agent_id: Option<String>,
-    #[serde(rename = "parentTaskId")]
-    parent_task_id: Option<String>,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-    #[serde(rename = "updatedAt")]
-    updated_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct TaskCreatePayload {
-    title: String,
-    description: Option<String>,
-    status: Option<String>,
-    priority: Option<String>,
-    #[serde(rename = "assigneeAgentId")]
-    assignee_agent_id: Option<String>,
-    #[serde(rename = "parentTaskId")]
-    parent_task_id: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct TaskPatch {
-    title: Option<String>,
-    description: Option<String>,
-    status: Option<String>,
-    priority: Option<String>,
-    #[serde(rename = "assigneeAgentId")]
-    assignee_agent_id: Option<Option<String>>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct IntegrationEntry {
-    slug: String,
-    name: String,
-    category: String,
-    description: String,
-    setup: Vec<String>,
-    permissions: Vec<String>,
-    status: String,
-    #[serde(rename = "assignedAgentIds")]
-    assigned_agent_ids: Vec<String>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct IntegrationCategoryStat {
-    name: String,
-    #[serde(rename = "connectedCount")]
-    connected_count: u64,
-    #[serde(rename = "totalCount")]
-    total_count: u64,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct IntegrationsListResult {
-    items: Vec<IntegrationEntry>,
-    categories: Vec<IntegrationCategoryStat>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct McpServerEntry {
-    id: String,
-    url: String,
-    name: String,
-    capabilities: Vec<String>,
-    connected: bool,
-    #[serde(rename = "approvedScopes")]
-    approved_scopes: Vec<String>,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-    #[serde(rename = "lastConnectedAt")]
-    last_connected_at: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct McpToolEntry {
-    id: String,
-    #[serde(rename = "serverId")]
-    server_id: String,
-    name: String,
-    description: String,
-    scopes: Vec<String>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct McpInvokeResult {
-    output: String,
-    #[serde(rename = "latencyMs")]
-    latency_ms: u64,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct VaultEntry {
-    id: String,
-    #[serde(rename = "type")]
-    entry_type: String,
-    title: String,
-    #[serde(rename = "markdownSummary")]
-    markdown_summary: String,
-    #[serde(rename = "importanceScore")]
-    importance_score: u64,
-    tags: Vec<String>,
-    #[serde(rename = "agentId")]
-    agent_id: String,
-    #[serde(rename = "taskId")]
-    task_id: Option<String>,
-    version: u64,
-    #[serde(rename = "blobPath")]
-    blob_path: Option<String>,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-    encrypted: bool,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct VaultSummary {
-    #[serde(rename = "usedGb")]
-    used_gb: f64,
-    #[serde(rename = "capacityGb")]
-    capacity_gb: f64,
-    #[serde(rename = "archivedItems")]
-    archived_items: u64,
-    #[serde(rename = "fileItems")]
-    file_items: u64,
-    #[serde(rename = "knowledgeItems")]
-    knowledge_items: u64,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct VaultStorageStats {
-    #[serde(rename = "snapshotTime")]
-    snapshot_time: String,
-    #[serde(rename = "archiveGb")]
-    archive_gb: f64,
-    #[serde(rename = "filesGb")]
-    files_gb: f64,
-    #[serde(rename = "totalGb")]
-    total_gb: f64,
-    #[serde(rename = "freeGb")]
-    free_gb: f64,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct VaultStorageInfo {
-    #[serde(rename = "rootPath")]
-    root_path: String,
-    #[serde(rename = "volumeName")]
-    volume_name: String,
-    #[serde(rename = "totalGb")]
-    total_gb: f64,
-    #[serde(rename = "freeGb")]
-    free_gb: f64,
-    #[serde(rename = "vaultUsedGb")]
-    vault_used_gb: f64,
-    #[serde(rename = "isExternal")]
-    is_external: bool,
-    #[serde(rename = "isNetwork")]
-    is_network: bool,
-    #[serde(rename = "warningLevel")]
-    warning_level: String,
-    #[serde(rename = "isOfflineFallback")]
-    is_offline_fallback: bool,
-    #[serde(rename = "tempCachePath")]
-    temp_cache_path: Option<String>,
-    #[serde(rename = "updatedAt")]
-    updated_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct VaultVersion {
-    #[serde(rename = "entryId")]
-    entry_id: String,
-    #[serde(rename = "versionNum")]
-    version_num: u64,
-    #[serde(rename = "blobPath")]
-    blob_path: Option<String>,
-    diff: Option<String>,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct ConnectedModelProvider {
-    provider: String,
-    label: String,
-    #[serde(rename = "modelHint")]
-    model_hint: String,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct ClawHubSkill {
-    slug: String,
-    name: String,
-    author: String,
-    description: String,
-    downloads: u64,
-    stars: Option<u64>,
-    version: String,
-    categories: Vec<String>,
-    permissions: Vec<String>,
-    installed: bool,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct ClawHubLiveSkillsResult {
-    skills: Vec<ClawHubSkill>,
-    #[serde(rename = "nextCursor")]
-    next_cursor: Option<String>,
-    source: String,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct ClawHubInstallResult {
-    slug: String,
-    installed: bool,
-    #[serde(rename = "assignedAgentId")]
-    assigned_agent_id: Option<String>,
-    message: String,
-    #[serde(rename = "requestedPermissionIds")]
-    requested_permission_ids: Option<Vec<String>>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct PermissionGrant {
-    id: String,
-    #[serde(rename = "agentId")]
-    agent_id: String,
-    capability: String,
-    granted: bool,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AuditLogEntry {
-    id: String,
-    category: String,
-    action: String,
-    actor: String,
-    metadata: serde_json::Value,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct SwarmChatThread {
-    id: String,
-    title: String,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-    #[serde(rename = "updatedAt")]
-    updated_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct SwarmChatMessage {
-    id: String,
-    #[serde(rename = "threadId")]
-    thread_id: String,
-    #[serde(rename = "type")]
-    message_type: String,
-    author: String,
-    content: String,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-    #[serde(rename = "parentMessageId")]
-    parent_message_id: Option<String>,
-    metadata: Option<serde_json::Value>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct SwarmSummary {
-    #[serde(rename = "onlineAgents")]
-    online_agents: u64,
-    #[serde(rename = "activeTasks")]
-    active_tasks: u64,
-    #[serde(rename = "spendTodayUsd")]
-    spend_today_usd: f64,
-    heartbeat: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct CheckpointRecord {
-    id: String,
-    #[serde(rename = "swarmId")]
-    swarm_id: String,
-    step: u64,
-    #[serde(rename = "stateJson")]
-    state_json: String,
-    #[serde(rename = "promptSnapshot")]
-    prompt_snapshot: Option<String>,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AgentBudget {
-    #[serde(rename = "agentId")]
-    agent_id: String,
-    #[serde(rename = "tokenLimit")]
-    token_limit: u64,
-    #[serde(rename = "costLimitUsd")]
-    cost_limit_usd: f64,
-    #[serde(rename = "currentTokens")]
-    current_tokens: u64,
-    #[serde(rename = "currentCostUsd")]
-    current_cost_usd: f64,
-    #[serde(rename = "hardKill")]
-    hard_kill: bool,
-    #[serde(rename = "updatedAt")]
-    updated_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct BudgetSnapshot {
-    global: AgentBudget,
-    agents: Vec<AgentBudget>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct HealthSnapshot {
-    heartbeat: String,
-    #[serde(rename = "uptimeSeconds")]
-    uptime_seconds: u64,
-    #[serde(rename = "gatewayStatus")]
-    gateway_status: String,
-    #[serde(rename = "activeAgents")]
-    active_agents: u64,
-    #[serde(rename = "totalAgents")]
-    total_agents: u64,
-    #[serde(rename = "errorAgents")]
-    error_agents: u64,
-    #[serde(rename = "pendingApprovals")]
-    pending_approvals: u64,
-    #[serde(rename = "spendTodayUsd")]
-    spend_today_usd: f64,
-    #[serde(rename = "vaultUsedGb")]
-    vault_used_gb: f64,
-    #[serde(rename = "vaultCapacityGb")]
-    vault_capacity_gb: f64,
-    #[serde(rename = "vaultUsagePct")]
-    vault_usage_pct: f64,
-    alerts: Vec<String>,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct HealthTelemetryEvent {
-    id: String,
-    category: String,
-    severity: String,
-    source: String,
-    message: String,
-    metadata: serde_json::Value,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct TelemetryExportResult {
-    format: String,
-    #[serde(rename = "generatedAt")]
-    generated_at: String,
-    payload: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AnalyticsSnapshot {
-    range: String,
-    kpis: serde_json::Value,
-    trends: serde_json::Value,
-    #[serde(rename = "perAgent")]
-    per_agent: serde_json::Value,
-    forecasts: serde_json::Value,
-    recommendations: Vec<String>,
-    #[serde(rename = "generatedAt")]
-    generated_at: String,
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct AnalyticsExportResult {
-    range: String,
-    format: String,
-    #[serde(rename = "generatedAt")]
-    generated_at: String,
-    payload: String,
-}
-
-fn gateway_base_url() -> String {
-    format!("http://127.0.0.1:{GATEWAY_PORT}")
-}
-
-fn gateway_session_token_value() -> String {
-    std::env::var("MAJORCLAW_GATEWAY_SESSION_TOKEN").unwrap_or_else(|_| "majorclaw-dev-session-token".to_string())
-}
-
-fn gateway_client() -> reqwest::Client {
-    let mut headers = HeaderMap::new();
-    if let Ok(token_value) = HeaderValue::from_str(&gateway_session_token_value()) {
-        headers.insert("x-session-token", token_value);
-    }
-    reqwest::Client::builder()
-        .default_headers(headers)
-        .build()
-        .unwrap_or_else(|_| gateway_client())
-}
-
-async fn gateway_get(url: String) -> Result<reqwest::Response, reqwest::Error> {
-    gateway_client().get(url).send().await
-}
-
-fn workspace_root() -> PathBuf {
-    if let Ok(explicit_root) = std::env::var("MAJORCLAW_WORKSPACE_ROOT") {
-        return PathBuf::from(explicit_root);
-    }
-    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    manifest_dir
-        .parent()
-        .and_then(|p| p.parent())
-        .map(|p| p.to_path_buf())
-        .unwrap_or(manifest_dir)
-}
-
-fn resolve_pnpm_path() -> PathBuf {
-    let root = workspace_root();
-    let local = root
-        .join("node_modules")
-        .join(".bin")
-        .join(if cfg!(windows) { "pnpm.cmd" } else { "pnpm" });
-    if local.exists() {
-        return local;
-    }
-    for candidate in ["/opt/homebrew/bin/pnpm", "/usr/local/bin/pnpm", "/usr/bin/pnpm"] {
-        let path = PathBuf::from(candidate);
-        if path.exists() {
-            return path;
-        }
-    }
-    PathBuf::from("pnpm")
-}
-
 fn macos_launch_agent_label() -> &'static str {
     "com.jonnycatx.major-claw.gateway"
 }
 
-fn macos_launch_agent_path() -> Result<PathBuf, String> {
-    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+fn macos_launch_agent_path() -> Result<PathBuf, CommandError> {
+    let home = std::env::var("HOME").map_err(|_| CommandError::platform("HOME is not set"))?;
     Ok(PathBuf::from(home)
         .join("Library")
         .join("LaunchAgents")
         .join(format!("{}.plist", macos_launch_agent_label())))
 }
 
-fn render_macos_launch_agent_plist() -> String {
-    let root = workspace_root();
-    let root_display = root.to_string_lossy();
-    let pnpm_display = resolve_pnpm_path().to_string_lossy().to_string();
-    format!(
+/// Escapes text for a plist `<string>` or `<key>` element.
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// The launch agent and the contents of its environment file. Like the systemd unit,
+/// the plist holds no secret: it points the gateway at the owner-only environment
+/// file through `MAJORCLAW_GATEWAY_ENV_FILE`, and the gateway loads the token from it.
+fn render_macos_launch_agent_plist(
+    launch: &GatewayLaunch,
+    endpoint: &GatewayEndpoint,
+    session_token: &str,
+    environment_file: &Path,
+) -> (String, String) {
+    let mut environment = daemon_environment(endpoint, session_token);
+    let secrets = split_daemon_secrets(&mut environment);
+    environment.insert(
+        "MAJORCLAW_GATEWAY_ENV_FILE".to_string(),
+        environment_file.to_string_lossy().to_string(),
+    );
+    let environment = environment
+        .iter()
+        .map(|(key, value)| format!("    <key>{}</key>\n    <string>{}</string>", xml_escape(key), xml_escape(value)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let program_arguments = std::iter::once(launch.program.to_string_lossy().to_string())
+        .chain(launch.args.iter().cloned())
+        .map(|arg| format!("    <string>{}</string>", xml_escape(&arg)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let plist = format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
 <plist version="1.0">
@@ -629,13 +166,14 @@ fn render_macos_launch_agent_plist() -> String {
   <string>{label}</string>
   <key>ProgramArguments</key>
   <array>
-    <string>{pnpm}</string>
-    <string>--filter</string>
-    <string>@majorclaw/gateway</string>
-    <string>start</string>
+{program_arguments}
   </array>
   <key>WorkingDirectory</key>
   <string>{cwd}</string>
+  <key>EnvironmentVariables</key>
+  <dict>
+{environment}
+  </dict>
   <key>RunAtLoad</key>
   <true/>
   <key>KeepAlive</key>
@@ -647,20 +185,20 @@ fn render_macos_launch_agent_plist() -> String {
 </dict>
 </plist>
 "#,
-        label = macos_launch_agent_label(),
-        cwd = root_display,
-        pnpm = pnpm_display,
-        home = std::env::var("HOME").unwrap_or_else(|_| "~".to_string())
-    )
+        label = xml_escape(macos_launch_agent_label()),
+        cwd = xml_escape(&launch.working_dir.to_string_lossy()),
+        home = xml_escape(&std::env::var("HOME").unwrap_or_else(|_| "~".to_string()))
+    );
+    (plist, systemd_unit::render_environment_file(&secrets))
 }
 
-fn launchctl_target() -> Result<String, String> {
+fn launchctl_target() -> Result<String, CommandError> {
     let output = Command::new("id")
         .arg("-u")
         .output()
-        .map_err(|err| format!("failed to read uid: {err}"))?;
+        .map_err(|err| CommandError::platform(format!("failed to read uid: {err}")))?;
     if !output.status.success() {
-        return Err("failed to resolve uid for launchctl target".to_string());
+        return Err(CommandError::platform("failed to resolve uid for launchctl target"));
     }
     let uid = String::from_utf8_lossy(&output.stdout).trim().to_string();
     Ok(format!("gui/{uid}"))
@@ -670,8 +208,8 @@ fn linux_systemd_label() -> &'static str {
     "major-claw-gateway"
 }
 
-fn linux_systemd_service_path() -> Result<PathBuf, String> {
-    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+fn linux_systemd_service_path() -> Result<PathBuf, CommandError> {
+    let home = std::env::var("HOME").map_err(|_| CommandError::platform("HOME is not set"))?;
     Ok(PathBuf::from(home)
         .join(".config")
         .join("systemd")
@@ -679,26 +217,152 @@ fn linux_systemd_service_path() -> Result<PathBuf, String> {
         .join(format!("{}.service", linux_systemd_label())))
 }
 
-fn render_linux_systemd_service() -> String {
-    let root = workspace_root().to_string_lossy().to_string();
-    format!(
-        "[Unit]\nDescription=Major Claw Gateway\nAfter=network.target\n\n[Service]\nType=simple\nWorkingDirectory={root}\nExecStart=/usr/bin/env pnpm --filter @majorclaw/gateway start\nRestart=always\nRestartSec=3\nEnvironment=NODE_ENV=production\n\n[Install]\nWantedBy=default.target\n",
-        root = root
-    )
+/// Environment for a gateway run by a daemon unit. It serves the default profile,
+/// and `MAJORCLAW_GATEWAY_OWNER` tells it apart from one the app spawned (there is
+/// no owner PID to pass: the service manager owns it).
+fn daemon_environment(endpoint: &GatewayEndpoint, session_token: &str) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("NODE_ENV".to_string(), "production".to_string()),
+        ("MAJORCLAW_GATEWAY_HOST".to_string(), endpoint.host.clone()),
+        ("MAJORCLAW_GATEWAY_PORT".to_string(), endpoint.port.to_string()),
+        ("MAJORCLAW_GATEWAY_SESSION_TOKEN".to_string(), session_token.to_string()),
+        ("MAJORCLAW_GATEWAY_OWNER".to_string(), "daemon".to_string()),
+    ])
+}
+
+/// Where the daemon's environment file (the session token) is kept: the systemd unit's
+/// `EnvironmentFile=` or the launch agent's `MAJORCLAW_GATEWAY_ENV_FILE`, outside the
+/// directories the service managers scan.
+fn daemon_environment_path() -> Result<PathBuf, CommandError> {
+    let home = std::env::var("HOME").map_err(|_| CommandError::platform("HOME is not set"))?;
+    Ok(PathBuf::from(home).join(".major-claw").join("daemon").join("gateway-daemon.env"))
+}
+
+/// Moves the session token out of `environment`, for the owner-only environment file.
+fn split_daemon_secrets(environment: &mut BTreeMap<String, String>) -> BTreeMap<String, String> {
+    environment
+        .remove_entry("MAJORCLAW_GATEWAY_SESSION_TOKEN")
+        .into_iter()
+        .collect()
+}
+
+/// The unit and the contents of its environment file. The session token goes only in
+/// the owner-only environment file, so the unit itself holds no secret.
+fn linux_systemd_unit(
+    launch: &GatewayLaunch,
+    endpoint: &GatewayEndpoint,
+    session_token: &str,
+    environment_file: &Path,
+) -> (SystemdUnit, String) {
+    let mut environment = daemon_environment(endpoint, session_token);
+    let secrets = split_daemon_secrets(&mut environment);
+    let unit = SystemdUnit {
+        description: "Major Claw Gateway".to_string(),
+        program: launch.program.clone(),
+        args: launch.args.clone(),
+        working_dir: launch.working_dir.clone(),
+        environment,
+        environment_file: Some(environment_file.to_path_buf()),
+        limits: ResourceLimits::from_env(),
+    };
+    (unit, systemd_unit::render_environment_file(&secrets))
+}
+
+/// Where a replaced launch agent or unit is kept, outside the directories the
+/// service managers scan.
+fn daemon_backup_dir() -> Result<PathBuf, CommandError> {
+    let home = std::env::var("HOME").map_err(|_| CommandError::platform("HOME is not set"))?;
+    Ok(PathBuf::from(home).join(".major-claw").join("backups"))
+}
+
+/// A daemon definition this build would install, where it goes, and the secrets
+/// file installed with it, if any.
+struct RenderedDaemon {
+    path: PathBuf,
+    contents: String,
+    secrets_file: Option<(PathBuf, String)>,
+}
+
+/// The daemon definition this build would install, or `None` where the service is
+/// installed by script (Windows) or not at all.
+fn rendered_daemon_definition(
+    state: &GatewayState,
+    session_token: &str,
+) -> Result<Option<RenderedDaemon>, CommandError> {
+    match std::env::consts::OS {
+        "macos" => {
+            let launch = gateway_launch(state)?.for_daemon();
+            let environment_path = daemon_environment_path()?;
+            let (plist, environment_file) =
+                render_macos_launch_agent_plist(&launch, &state.daemon_endpoint(), session_token, &environment_path);
+            Ok(Some(RenderedDaemon {
+                path: macos_launch_agent_path()?,
+                contents: plist,
+                secrets_file: Some((environment_path, environment_file)),
+            }))
+        }
+        "linux" => {
+            let launch = gateway_launch(state)?.for_daemon();
+            let endpoint = state.daemon_endpoint();
+            let runner = pidfile_runner()?;
+            match linux_backend(&runner)? {
+                LinuxBackend::Systemd => {
+                    let environment_path = daemon_environment_path()?;
+                    let (unit, environment_file) =
+                        linux_systemd_unit(&launch, &endpoint, session_token, &environment_path);
+                    Ok(Some(RenderedDaemon {
+                        path: linux_systemd_service_path()?,
+                        contents: unit.render(),
+                        secrets_file: Some((environment_path, environment_file)),
+                    }))
+                }
+                _ => {
+                    let environment = daemon_environment(&endpoint, session_token);
+                    let script = runner.render_script(&launch.program, &launch.args, &launch.working_dir, &environment);
+                    Ok(Some(RenderedDaemon {
+                        path: runner.script.clone(),
+                        contents: script,
+                        secrets_file: None,
+                    }))
+                }
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Plans the install of the rendered definition and, unless `dry_run`, writes it.
+fn install_daemon_definition(
+    state: &GatewayState,
+    session_token: &str,
+    dry_run: bool,
+) -> Result<Option<DaemonInstall>, CommandError> {
+    let Some(rendered) = rendered_daemon_definition(state, session_token)? else {
+        return Ok(None);
+    };
+    let mut plan = daemon_install::plan(&rendered.path, &rendered.contents).map_err(CommandError::platform)?;
+    if let Some((path, contents)) = &rendered.secrets_file {
+        plan = plan.with_secrets_file(path, contents).map_err(CommandError::platform)?;
+    }
+    if dry_run {
+        return Ok(Some(plan));
+    }
+    gateway_launch(state)?.stage_for_daemon().map_err(CommandError::platform)?;
+    daemon_install::apply(plan, &rendered.contents, &daemon_backup_dir()?)
+        .map(Some)
+        .map_err(CommandError::platform)
 }
 
 fn windows_service_label() -> &'static str {
     "MajorClawGateway"
 }
 
-fn windows_install_script_path() -> PathBuf {
-    workspace_root()
-        .join("ops")
-        .join("windows")
-        .join("install-gateway-service.ps1")
+/// The NSSM helper script ships with the source checkout, not the app bundle.
+fn windows_install_script_path() -> Option<PathBuf> {
+    workspace_root().map(|root| root.join("ops").join("windows").join("install-gateway-service.ps1"))
 }
 
-fn daemon_status_unsupported(os: String, message: String) -> GatewayDaemonStatus {
+fn daemon_status_unsupported(os: String, reason: String) -> GatewayDaemonStatus {
     GatewayDaemonStatus {
         platform: os,
         supported: false,
@@ -708,10 +372,24 @@ fn daemon_status_unsupported(os: String, message: String) -> GatewayDaemonStatus
         service_path: String::new(),
         log_hint: String::new(),
         last_error: None,
-        message,
+        message: format!("Always-on service is unsupported because {reason}."),
+        unit_verification: None,
+        install: None,
+        backend: None,
+        unsupported_reason: Some(reason),
     }
 }
 
+fn pidfile_runner() -> Result<PidfileRunner, CommandError> {
+    PidfileRunner::locate().map_err(CommandError::platform)
+}
+
+/// The Linux backend in use, or why the always-on service cannot run on this host.
+fn linux_backend(runner: &PidfileRunner) -> Result<LinuxBackend, CommandError> {
+    linux_daemon::detect(runner)
+        .map_err(|reason| CommandError::platform(format!("the always-on service is unsupported because {reason}")))
+}
+
 fn parse_sc_running(output: &str) -> bool {
     let lower = output.to_lowercase();
     lower.contains("state") && lower.contains("running")
@@ -722,7 +400,7 @@ fn parse_sc_enabled(output: &str) -> bool {
     lower.contains("start_type") && (lower.contains("auto_start") || lower.contains("auto start"))
 }
 
-fn daemon_status_for_macos() -> Result<GatewayDaemonStatus, String> {
+fn daemon_status_for_macos() -> Result<GatewayDaemonStatus, CommandError> {
     let path = macos_launch_agent_path()?;
     let enabled = path.exists();
     let target = launchctl_target()?;
@@ -774,10 +452,55 @@ fn daemon_status_for_macos() -> Result<GatewayDaemonStatus, String> {
         log_hint: "$HOME/Library/Logs/MajorClaw/stdout.log".to_string(),
         last_error,
         message,
+        unit_verification: None,
+        install: None,
+        backend: Some("launchd"),
+        unsupported_reason: None,
     })
 }
 
-fn daemon_status_for_linux() -> Result<GatewayDaemonStatus, String> {
+fn daemon_status_for_linux() -> Result<GatewayDaemonStatus, CommandError> {
+    let runner = pidfile_runner()?;
+    match linux_daemon::detect(&runner) {
+        Ok(LinuxBackend::Systemd) => daemon_status_for_systemd(),
+        Ok(backend) => Ok(daemon_status_for_runner(&runner, backend)),
+        Err(reason) => Ok(daemon_status_unsupported("linux".to_string(), reason)),
+    }
+}
+
+fn daemon_status_for_runner(runner: &PidfileRunner, backend: LinuxBackend) -> GatewayDaemonStatus {
+    let (service_path, enabled) = match backend {
+        LinuxBackend::XdgAutostart => {
+            (&runner.autostart_entry, runner.autostart_entry.exists() && runner.script.exists())
+        }
+        _ => (&runner.script, runner.script.exists()),
+    };
+    let pid = runner.pid();
+    GatewayDaemonStatus {
+        platform: "linux".to_string(),
+        supported: true,
+        enabled,
+        running: pid.is_some(),
+        service_label: "gateway-daemon.sh".to_string(),
+        service_path: service_path.to_string_lossy().to_string(),
+        log_hint: format!("tail -f {}", runner.log.display()),
+        last_error: None,
+        message: match (enabled, pid, backend) {
+            (false, _, _) => "Background gateway runner is disabled.".to_string(),
+            (true, Some(pid), _) => format!("Background gateway runner is running (pid {pid})."),
+            (true, None, LinuxBackend::XdgAutostart) => {
+                "Background gateway runner is enabled and starts at the next login.".to_string()
+            }
+            (true, None, _) => "Background gateway runner is enabled but not running.".to_string(),
+        },
+        unit_verification: None,
+        install: None,
+        backend: Some(backend.as_str()),
+        unsupported_reason: None,
+    }
+}
+
+fn daemon_status_for_systemd() -> Result<GatewayDaemonStatus, CommandError> {
     let path = linux_systemd_service_path()?;
     let label = linux_systemd_label().to_string();
     let enabled_output = Command::new("systemctl")
@@ -806,10 +529,14 @@ fn daemon_status_for_linux() -> Result<GatewayDaemonStatus, String> {
         } else {
             "Systemd user service is disabled.".to_string()
         },
+        unit_verification: None,
+        install: None,
+        backend: Some(LinuxBackend::Systemd.as_str()),
+        unsupported_reason: None,
     })
 }
 
-fn daemon_status_for_windows() -> Result<GatewayDaemonStatus, String> {
+fn daemon_status_for_windows() -> Result<GatewayDaemonStatus, CommandError> {
     let script = windows_install_script_path();
     let query_output = Command::new("sc")
         .args(["query", windows_service_label()])
@@ -839,7 +566,7 @@ fn daemon_status_for_windows() -> Result<GatewayDaemonStatus, String> {
         enabled,
         running,
         service_label: windows_service_label().to_string(),
-        service_path: script.to_string_lossy().to_string(),
+        service_path: script.map(|path| path.to_string_lossy().to_string()).unwrap_or_default(),
         log_hint: format!(
             "PowerShell: Get-WinEvent -LogName Application | Where-Object {{$_.ProviderName -like '*{}*'}} -MaxEvents 50",
             windows_service_label()
@@ -850,29 +577,15 @@ fn daemon_status_for_windows() -> Result<GatewayDaemonStatus, String> {
         } else {
             "Windows service is not installed.".to_string()
         },
+        unit_verification: None,
+        install: None,
+        backend: Some("windows_service"),
+        unsupported_reason: None,
     })
 }
 
-fn ensure_gateway_session_token() -> String {
-    if let Ok(existing) = std::env::var("MAJORCLAW_GATEWAY_SESSION_TOKEN") {
-        if !existing.trim().is_empty() {
-            return existing;
-        }
-    }
-    let generated = format!(
-        "mc-{}-{}",
-        std::process::id(),
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|value| value.as_nanos())
-            .unwrap_or_default()
-    );
-    std::env::set_var("MAJORCLAW_GATEWAY_SESSION_TOKEN", generated.clone());
-    generated
-}
-
 #[tauri::command]
-pub fn open_official_integrations(app: AppHandle, fragment: Option<String>) -> Result<(), String> {
+pub fn open_official_integrations(app: AppHandle, fragment: Option<String>) -> Result<(), CommandError> {
     let label = "official-integrations";
     let url = match fragment {
         Some(value) if !value.trim().is_empty() => {
@@ -881,23 +594,23 @@ pub fn open_official_integrations(app: AppHandle, fragment: Option<String>) -> R
         _ => "https://openclaw.ai/integrations".to_string(),
     };
     if let Some(window) = app.get_webview_window(label) {
-        let escaped_url = serde_json::to_string(&url).map_err(|err| format!("url encode failed: {err}"))?;
+        let escaped_url = serde_json::to_string(&url).map_err(|err| CommandError::platform(format!("url encode failed: {err}")))?;
         window
-            .eval(&format!("window.location.href = {escaped_url};"))
-            .map_err(|err| format!("failed to update official integrations url: {err}"))?;
-        window.show().map_err(|err| format!("failed to show official integrations window: {err}"))?;
-        window.set_focus().map_err(|err| format!("failed to focus official integrations window: {err}"))?;
+            .eval(format!("window.location.href = {escaped_url};"))
+            .map_err(|err| CommandError::platform(format!("failed to update official integrations url: {err}")))?;
+        window.show().map_err(|err| CommandError::platform(format!("failed to show official integrations window: {err}")))?;
+        window.set_focus().map_err(|err| CommandError::platform(format!("failed to focus official integrations window: {err}")))?;
         // Re-inject navigation handler when reusing existing window
         inject_navigation_handler(&window)?;
         return Ok(());
     }
-    WebviewWindowBuilder::new(&app, label, WebviewUrl::External(url.parse().map_err(|err| format!("invalid integrations url: {err}"))?))
+    WebviewWindowBuilder::new(&app, label, WebviewUrl::External(url.parse().map_err(|err| CommandError::platform(format!("invalid integrations url: {err}")))?))
         .title("Official OpenClaw Integrations")
         .inner_size(1240.0, 920.0)
         .center()
         .decorations(true)
         .build()
-        .map_err(|err| format!("failed to open official integrations window: {err}"))?;
+        .map_err(|err| CommandError::platform(format!("failed to open official integrations window: {err}")))?;
     
     // Inject JavaScript to handle link clicks and keep navigation within the same window
     // The script will wait for DOM ready, so we can inject after a short delay
@@ -914,36 +627,36 @@ pub fn open_official_integrations(app: AppHandle, fragment: Option<String>) -> R
 }
 
 #[tauri::command]
-pub fn close_official_integrations(app: AppHandle) -> Result<bool, String> {
+pub fn close_official_integrations(app: AppHandle) -> Result<bool, CommandError> {
     if let Some(window) = app.get_webview_window("official-integrations") {
         window
             .close()
-            .map_err(|err| format!("failed to close official integrations window: {err}"))?;
+            .map_err(|err| CommandError::platform(format!("failed to close official integrations window: {err}")))?;
         return Ok(true);
     }
     Ok(false)
 }
 
 #[tauri::command]
-pub fn back_official_integrations(app: AppHandle) -> Result<bool, String> {
+pub fn back_official_integrations(app: AppHandle) -> Result<bool, CommandError> {
     if let Some(window) = app.get_webview_window("official-integrations") {
         window
             .eval(
                 "if (window.history.length > 1) { window.history.back(); } else { window.location.href = 'https://openclaw.ai/integrations'; }",
             )
-            .map_err(|err| format!("failed to navigate back in official integrations window: {err}"))?;
+            .map_err(|err| CommandError::platform(format!("failed to navigate back in official integrations window: {err}")))?;
         window
             .show()
-            .map_err(|err| format!("failed to show official integrations window: {err}"))?;
+            .map_err(|err| CommandError::platform(format!("failed to show official integrations window: {err}")))?;
         window
             .set_focus()
-            .map_err(|err| format!("failed to focus official integrations window: {err}"))?;
+            .map_err(|err| CommandError::platform(format!("failed to focus official integrations window: {err}")))?;
         return Ok(true);
     }
     Ok(false)
 }
 
-fn inject_navigation_handler(window: &tauri::WebviewWindow) -> Result<(), String> {
+fn inject_navigation_handler(window: &tauri::WebviewWindow) -> Result<(), CommandError> {
     // JavaScript to intercept all link clicks and ensure they navigate within the same window
     let script = r#"
         (function() {
@@ -1035,50 +748,265 @@ fn inject_navigation_handler(window: &tauri::WebviewWindow) -> Result<(), String
     
     window
         .eval(script)
-        .map_err(|err| format!("failed to inject navigation handler: {err}"))?;
+        .map_err(|err| CommandError::platform(format!("failed to inject navigation handler: {err}")))?;
     
     Ok(())
 }
 
-fn start_gateway_inner(state: &GatewayState) -> Result<GatewayStatus, String> {
-    let mut guard = state.child.lock().map_err(|_| "gateway state lock poisoned".to_string())?;
+fn gateway_status_for(state: &GatewayState, running: bool) -> Result<GatewayStatus, CommandError> {
+    let runtime = state.active();
+    if let Some(remote) = runtime.remote.as_ref() {
+        let (host, port) = remote.host_port();
+        // Not ours to start or stop, so it counts as running; `gateway_health` shows reachability.
+        return Ok(GatewayStatus {
+            running: true,
+            host,
+            port,
+            mode: "remote",
+            owner: GatewayOwner::Remote,
+            profile: runtime.name.clone(),
+            base_url: remote.base_url.clone(),
+        });
+    }
+    let attached = *runtime.attached.lock().map_err(|_| CommandError::lock_poisoned("gateway owner"))?;
+    let owner = match attached {
+        _ if running => GatewayOwner::App,
+        Some(owner) => owner,
+        None => GatewayOwner::None,
+    };
+    let endpoint = runtime
+        .endpoint
+        .lock()
+        .map_err(|_| CommandError::lock_poisoned("gateway endpoint"))?;
+    Ok(GatewayStatus {
+        running: owner != GatewayOwner::None,
+        host: endpoint.host.clone(),
+        port: endpoint.port,
+        mode: "local",
+        owner,
+        profile: runtime.name.clone(),
+        base_url: endpoint.base_url(),
+    })
+}
+
+/// Picks where a newly spawned gateway should listen: the configured endpoint if its
+/// port is free, otherwise a free port on the same host. The choice is saved in the
+/// active profile so the next launch prefers it, and the shared client is pointed at it.
+fn claim_spawn_endpoint(
+    state: &GatewayState,
+    runtime: &ProfileRuntime,
+    client: &GatewayClient,
+) -> Result<GatewayEndpoint, CommandError> {
+    let mut endpoint = runtime
+        .endpoint
+        .lock()
+        .map_err(|_| CommandError::lock_poisoned("gateway endpoint"))?;
+    if !endpoint.port_is_free() {
+        let fallback = endpoint.with_free_port().ok_or_else(|| {
+            CommandError::platform(format!(
+                "gateway port {} is in use and no free port is available on {}",
+                endpoint.port, endpoint.host
+            ))
+        })?;
+        eprintln!("gateway port {} is in use; falling back to {}", endpoint.port, fallback.port);
+        *endpoint = fallback;
+    }
+    if let Err(error) = state.remember_endpoint(&runtime.name, &endpoint) {
+        eprintln!("failed to persist gateway endpoint: {error}");
+    }
+    client.set_base_url(endpoint.base_url());
+    Ok(endpoint.clone())
+}
+
+/// How gateways are launched, or why they cannot be in this build.
+fn gateway_launch(state: &GatewayState) -> Result<&GatewayLaunch, CommandError> {
+    state
+        .launch
+        .as_ref()
+        .map_err(|err| CommandError::platform(format!("cannot launch the gateway: {err}")))
+}
+
+fn start_gateway_inner(state: &GatewayState, client: &GatewayClient) -> Result<GatewayStatus, CommandError> {
+    let runtime = state.active();
+    if runtime.remote.is_some() {
+        return Err(CommandError::validation(
+            "connected to a remote gateway; switch to a local profile to start one",
+        ));
+    }
+    let mut guard = runtime.child.lock().map_err(|_| CommandError::lock_poisoned("gateway state"))?;
     if let Some(existing) = guard.as_mut() {
         match existing.try_wait() {
             Ok(None) => {
-                return Ok(GatewayStatus {
-                    running: true,
-                    port: GATEWAY_PORT,
-                })
+                drop(guard);
+                return gateway_status_for(state, true);
             }
             Ok(Some(_)) => {
                 *guard = None;
             }
-            Err(err) => return Err(format!("failed to inspect gateway process: {err}")),
+            Err(err) => return Err(CommandError::platform(format!("failed to inspect gateway process: {err}"))),
         }
     }
 
-    let token = ensure_gateway_session_token();
-    let child = Command::new(resolve_pnpm_path())
-        .args(["--filter", "@majorclaw/gateway", "dev:server"])
+    let token = client.session_token()?;
+    let endpoint = claim_spawn_endpoint(state, &runtime, client)?;
+    let mut child = gateway_launch(state)?
+        .command()
         .env("MAJORCLAW_GATEWAY_SESSION_TOKEN", token)
+        .env("MAJORCLAW_GATEWAY_HOST", &endpoint.host)
+        .env("MAJORCLAW_GATEWAY_PORT", endpoint.port.to_string())
         .env("MAJORCLAW_GATEWAY_OWNER_PID", std::process::id().to_string())
-        .current_dir(workspace_root())
+        .env("MAJORCLAW_GATEWAY_OWNER", "app")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()
-        .map_err(|err| format!("failed to start gateway: {err}"))?;
+        .map_err(|err| CommandError::platform(format!("failed to start gateway: {err}")))?;
+    if let Some(stdout) = child.stdout.take() {
+        state.logs.capture("stdout", stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        state.logs.capture("stderr", stderr);
+    }
 
     *guard = Some(child);
+    *runtime.spawned_at.lock().map_err(|_| CommandError::lock_poisoned("gateway state"))? = Some(Instant::now());
+    *runtime.attached.lock().map_err(|_| CommandError::lock_poisoned("gateway owner"))? = None;
     Ok(GatewayStatus {
         running: true,
-        port: GATEWAY_PORT,
+        host: endpoint.host.clone(),
+        port: endpoint.port,
+        mode: "local",
+        owner: GatewayOwner::App,
+        profile: runtime.name.clone(),
+        base_url: endpoint.base_url(),
     })
 }
 
-pub fn auto_start_gateway(state: &GatewayState) -> Result<GatewayStatus, String> {
-    start_gateway_inner(state)
+/// Whether a child this host spawned is alive. Unlike `poll_gateway_process` this
+/// never reaps an exit, which is left for the supervisor to report.
+fn owns_running_child(runtime: &ProfileRuntime) -> Result<bool, CommandError> {
+    Ok(runtime
+        .child
+        .lock()
+        .map_err(|_| CommandError::lock_poisoned("gateway state"))?
+        .as_mut()
+        .is_some_and(|child| matches!(child.try_wait(), Ok(None))))
+}
+
+/// Attaches to a gateway already answering on the active local profile's endpoint,
+/// such as the always-on service or one started by hand, instead of spawning a
+/// second one that would fight it for the port. The first attach also checks that
+/// it is a Major Claw gateway accepting this host's session token. Returns who owns
+/// it, or `None` (and forgets any earlier attach) when nothing answers.
+pub async fn attach_existing_gateway(
+    state: &GatewayState,
+    client: &GatewayClient,
+) -> Result<Option<GatewayOwner>, CommandError> {
+    let runtime = state.active();
+    if runtime.remote.is_some() || owns_running_child(&runtime)? {
+        return Ok(None);
+    }
+    let base_url = runtime
+        .endpoint
+        .lock()
+        .map_err(|_| CommandError::lock_poisoned("gateway endpoint"))?
+        .base_url();
+    client.set_base_url(base_url.clone());
+    let health = match client.health().await {
+        Ok(health) if health.status == "ok" => health,
+        _ => {
+            *runtime.attached.lock().map_err(|_| CommandError::lock_poisoned("gateway owner"))? = None;
+            return Ok(None);
+        }
+    };
+    let known = *runtime.attached.lock().map_err(|_| CommandError::lock_poisoned("gateway owner"))?;
+    if let Some(owner) = known {
+        return Ok(Some(owner));
+    }
+    client.list_agents().await.map_err(|error| {
+        CommandError::platform(format!(
+            "another gateway is already listening on {base_url} and did not accept this app's session token: {error}"
+        ))
+    })?;
+    let owner = match health.owner.as_deref() {
+        Some("daemon") => GatewayOwner::Daemon,
+        _ => GatewayOwner::External,
+    };
+    *runtime.attached.lock().map_err(|_| CommandError::lock_poisoned("gateway owner"))? = Some(owner);
+    Ok(Some(owner))
+}
+
+/// The always-on service serves the default profile; while it is installed, its
+/// service manager owns the gateway and the host never spawns a competing one.
+pub fn daemon_owns_gateway(state: &GatewayState) -> bool {
+    state.active().name == DEFAULT_PROFILE && daemon_enabled(state)
+}
+
+/// Whether the always-on service is enabled, from `GatewayState::daemon_enabled`.
+/// Asking the service manager shells out, so that only happens while it is unknown.
+fn daemon_enabled(state: &GatewayState) -> bool {
+    let cached = *state.daemon_enabled.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    cached.unwrap_or_else(|| refresh_daemon_enabled(state))
+}
+
+/// Asks the service manager whether the always-on service is enabled and caches the
+/// answer. Blocks on the service manager's CLI.
+pub fn refresh_daemon_enabled(state: &GatewayState) -> bool {
+    let enabled = gateway_daemon_status().is_ok_and(|status| status.enabled);
+    *state.daemon_enabled.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(enabled);
+    enabled
+}
+
+/// Makes sure the active profile has a gateway: the one this host already runs, an
+/// existing one it can attach to, or a newly spawned child. Remote profiles are
+/// reported as they are.
+pub async fn ensure_gateway(state: &GatewayState, client: &GatewayClient) -> Result<GatewayStatus, CommandError> {
+    let runtime = state.active();
+    if runtime.remote.is_some() {
+        return gateway_status_for(state, true);
+    }
+    if owns_running_child(&runtime)? {
+        return gateway_status_for(state, true);
+    }
+    if attach_existing_gateway(state, client).await?.is_some() {
+        return gateway_status_for(state, false);
+    }
+    if daemon_owns_gateway(state) {
+        return Err(CommandError::platform(
+            "the always-on gateway service is enabled but not answering; check gateway_daemon_logs",
+        ));
+    }
+    start_gateway_inner(state, client)
+}
+
+pub enum GatewayProcess {
+    /// Alive, with when it was spawned.
+    Running(Option<Instant>),
+    Exited(Option<i32>),
+    Missing,
+}
+
+/// Liveness of the gateway child owned by this host. An exited child is reaped
+/// and reported once with its exit code; after that it reads as `Missing`.
+pub fn poll_gateway_process(state: &GatewayState) -> Result<GatewayProcess, CommandError> {
+    let runtime = state.active();
+    let mut guard = runtime.child.lock().map_err(|_| CommandError::lock_poisoned("gateway state"))?;
+    let Some(child) = guard.as_mut() else {
+        return Ok(GatewayProcess::Missing);
+    };
+    match child.try_wait() {
+        Ok(None) => Ok(GatewayProcess::Running(
+            *runtime.spawned_at.lock().map_err(|_| CommandError::lock_poisoned("gateway state"))?,
+        )),
+        Ok(Some(status)) => {
+            *guard = None;
+            Ok(GatewayProcess::Exited(status.code()))
+        }
+        Err(err) => Err(CommandError::platform(format!("failed to inspect gateway process: {err}"))),
+    }
 }
 
 #[tauri::command]
-pub fn gateway_daemon_status() -> Result<GatewayDaemonStatus, String> {
+pub fn gateway_daemon_status() -> Result<GatewayDaemonStatus, CommandError> {
     let os = std::env::consts::OS.to_string();
     match os.as_str() {
         "macos" => daemon_status_for_macos(),
@@ -1086,94 +1014,146 @@ pub fn gateway_daemon_status() -> Result<GatewayDaemonStatus, String> {
         "windows" => daemon_status_for_windows(),
         _ => Ok(daemon_status_unsupported(
             os,
-            "Always-on service toggle is not supported on this platform.".to_string(),
+            "there is no supported service manager on this platform".to_string(),
         )),
     }
 }
 
 #[tauri::command]
-pub fn gateway_daemon_set_enabled(enabled: bool) -> Result<GatewayDaemonStatus, String> {
+pub fn gateway_daemon_set_enabled(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    enabled: bool,
+) -> Result<GatewayDaemonStatus, CommandError> {
+    // Removing the service needs no token, so it works even without a usable client.
+    let token = if enabled { client.session_token()? } else { String::new() };
+    apply_gateway_daemon_enabled(state.inner(), enabled, &token)
+}
+
+/// Installs the always-on service, or brings an installed one up to date with this
+/// build (launch paths, endpoint, token, limits). The installed definition is only
+/// replaced, backed up and reloaded when it differs; `dry_run` just reports the drift.
+#[tauri::command]
+pub fn gateway_daemon_install_or_upgrade(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    dry_run: Option<bool>,
+) -> Result<GatewayDaemonStatus, CommandError> {
+    if dry_run.unwrap_or(false) {
+        let mut status = gateway_daemon_status()?;
+        status.install = install_daemon_definition(state.inner(), &client.session_token()?, true)?;
+        return Ok(status);
+    }
+    apply_gateway_daemon_enabled(state.inner(), true, &client.session_token()?)
+}
+
+/// Run on launch: an enabled always-on service left behind by an older build is
+/// upgraded so it does not keep running stale paths. Only done while the default
+/// profile (whose token the service uses) is active, and only when the installed
+/// definition differs. A running service is restarted on the new definition; one the
+/// user stopped is only rewritten and stays stopped. Windows services are left alone,
+/// since reinstalling them needs the install script and admin rights. Blocks on the
+/// service manager's CLI.
+pub fn upgrade_installed_daemon(state: &GatewayState, session_token: &str) -> Result<(), CommandError> {
+    if !daemon_owns_gateway(state) {
+        return Ok(());
+    }
+    let Some(plan) = install_daemon_definition(state, session_token, true)?.filter(DaemonInstall::changed) else {
+        return Ok(());
+    };
+    if gateway_daemon_status()?.running {
+        apply_gateway_daemon_enabled(state, true, session_token)?;
+    } else {
+        install_daemon_definition(state, session_token, false)?;
+        if std::env::consts::OS == "linux" && linux_systemd_service_path()?.exists() {
+            let _ = Command::new("systemctl").args(["--user", "daemon-reload"]).status();
+        }
+        gateway_launch(state)?.prune_daemon_runtimes();
+    }
+    eprintln!("upgraded the always-on gateway service: {}", serde_json::to_string(&plan).unwrap_or_default());
+    Ok(())
+}
+
+/// Enables or disables the always-on service and updates the cached enabled state;
+/// after a failure it is unknown again and is asked for on next use.
+fn apply_gateway_daemon_enabled(
+    state: &GatewayState,
+    enabled: bool,
+    session_token: &str,
+) -> Result<GatewayDaemonStatus, CommandError> {
+    let result = set_daemon_enabled_for_os(state, enabled, session_token);
+    *state.daemon_enabled.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) =
+        result.as_ref().ok().map(|status| status.enabled);
+    result
+}
+
+fn set_daemon_enabled_for_os(
+    state: &GatewayState,
+    enabled: bool,
+    session_token: &str,
+) -> Result<GatewayDaemonStatus, CommandError> {
     match std::env::consts::OS {
         "macos" => {
             let path = macos_launch_agent_path()?;
+            let mut install = None;
             if enabled {
-                if let Some(parent) = path.parent() {
-                    fs::create_dir_all(parent)
-                        .map_err(|err| format!("failed to create launch agents directory: {err}"))?;
-                }
                 let logs_dir = PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| "~".to_string()))
                     .join("Library")
                     .join("Logs")
                     .join("MajorClaw");
-                fs::create_dir_all(logs_dir).map_err(|err| format!("failed to create logs directory: {err}"))?;
-                fs::write(&path, render_macos_launch_agent_plist())
-                    .map_err(|err| format!("failed to write launch agent plist: {err}"))?;
+                fs::create_dir_all(logs_dir).map_err(|err| CommandError::platform(format!("failed to create logs directory: {err}")))?;
+                let outcome = install_daemon_definition(state, session_token, false)?;
+                let changed = outcome.as_ref().is_some_and(DaemonInstall::changed);
+                install = outcome;
                 let target = launchctl_target()?;
-                let _ = Command::new("launchctl")
-                    .args(["bootout", &target, &path.to_string_lossy()])
-                    .status();
-                let bootstrap = Command::new("launchctl")
-                    .args(["bootstrap", &target, &path.to_string_lossy()])
-                    .status()
-                    .map_err(|err| format!("failed to bootstrap launch agent: {err}"))?;
-                if !bootstrap.success() {
-                    return Err("launchctl bootstrap failed".to_string());
+                let service = format!("{}/{}", target, macos_launch_agent_label());
+                let loaded = Command::new("launchctl")
+                    .args(["print", &service])
+                    .output()
+                    .is_ok_and(|output| output.status.success());
+                // An unchanged, loaded agent is left running; anything else is (re)loaded.
+                if changed || !loaded {
+                    let _ = Command::new("launchctl")
+                        .args(["bootout", &target, &path.to_string_lossy()])
+                        .status();
+                    let bootstrap = Command::new("launchctl")
+                        .args(["bootstrap", &target, &path.to_string_lossy()])
+                        .status()
+                        .map_err(|err| CommandError::platform(format!("failed to bootstrap launch agent: {err}")))?;
+                    if !bootstrap.success() {
+                        return Err(CommandError::platform("launchctl bootstrap failed"));
+                    }
+                    let _ = Command::new("launchctl").args(["kickstart", "-k", &service]).status();
                 }
-                let _ = Command::new("launchctl")
-                    .args(["kickstart", "-k", &format!("{}/{}", target, macos_launch_agent_label())])
-                    .status();
             } else if path.exists() {
                 let target = launchctl_target()?;
                 let _ = Command::new("launchctl")
                     .args(["bootout", &target, &path.to_string_lossy()])
                     .status();
                 let _ = fs::remove_file(&path);
+                let _ = fs::remove_file(daemon_environment_path()?);
             }
-            gateway_daemon_status()
-        }
-        "linux" => {
-            let path = linux_systemd_service_path()?;
-            if enabled {
-                if let Some(parent) = path.parent() {
-                    fs::create_dir_all(parent).map_err(|err| format!("failed to create systemd user directory: {err}"))?;
-                }
-                fs::write(&path, render_linux_systemd_service())
-                    .map_err(|err| format!("failed to write systemd unit file: {err}"))?;
-                let _ = Command::new("systemctl").args(["--user", "daemon-reload"]).status();
-                let result = Command::new("systemctl")
-                    .args(["--user", "enable", "--now", linux_systemd_label()])
-                    .status()
-                    .map_err(|err| format!("failed to enable systemd service: {err}"))?;
-                if !result.success() {
-                    return Err(
-                        "systemd enable --now failed (ensure user services are available; loginctl enable-linger may be required)"
-                            .to_string(),
-                    );
-                }
-            } else {
-                let _ = Command::new("systemctl")
-                    .args(["--user", "disable", "--now", linux_systemd_label()])
-                    .status();
-                if path.exists() {
-                    let _ = fs::remove_file(&path);
-                }
-                let _ = Command::new("systemctl").args(["--user", "daemon-reload"]).status();
-            }
-            gateway_daemon_status()
+            let mut status = gateway_daemon_status()?;
+            status.install = install;
+            Ok(status)
         }
+        "linux" => apply_linux_daemon_enabled(state, enabled, session_token),
         "windows" => {
             if enabled {
-                let script = windows_install_script_path();
+                let script = windows_install_script_path().ok_or_else(|| {
+                    CommandError::platform("the Windows service installer needs a source checkout; set MAJORCLAW_WORKSPACE_ROOT")
+                })?;
                 if !script.exists() {
-                    return Err(format!("Windows install script not found at {}", script.to_string_lossy()));
+                    return Err(CommandError::platform(format!("Windows install script not found at {}", script.to_string_lossy())));
                 }
                 let script_path = script.to_string_lossy().to_string();
                 let result = Command::new("powershell")
                     .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File", script_path.as_str()])
+                    .env("MAJORCLAW_GATEWAY_SESSION_TOKEN", session_token)
                     .status()
-                    .map_err(|err| format!("failed to execute Windows service install script: {err}"))?;
+                    .map_err(|err| CommandError::platform(format!("failed to execute Windows service install script: {err}")))?;
                 if !result.success() {
-                    return Err("Windows service install script failed (admin rights may be required)".to_string());
+                    return Err(CommandError::platform("Windows service install script failed (admin rights may be required)"));
                 }
             } else {
                 let _ = Command::new("sc").args(["stop", windows_service_label()]).status();
@@ -1185,27 +1165,119 @@ pub fn gateway_daemon_set_enabled(enabled: bool) -> Result<GatewayDaemonStatus,
     }
 }
 
-#[tauri::command]
-pub fn gateway_daemon_start() -> Result<GatewayDaemonStatus, String> {
-    match std::env::consts::OS {
+/// Stops and removes the systemd unit and its environment file, if installed.
+fn remove_linux_systemd_unit() -> Result<(), CommandError> {
+    let path = linux_systemd_service_path()?;
+    if path.exists() {
+        let _ = Command::new("systemctl")
+            .args(["--user", "disable", "--now", linux_systemd_label()])
+            .status();
+        let _ = fs::remove_file(&path);
+        let _ = Command::new("systemctl").args(["--user", "daemon-reload"]).status();
+    }
+    let _ = fs::remove_file(daemon_environment_path()?);
+    Ok(())
+}
+
+/// Enables the always-on gateway with the detected backend, first removing any
+/// other backend left installed so two of them never run the gateway, or disables
+/// it by removing every backend that is installed.
+fn apply_linux_daemon_enabled(
+    state: &GatewayState,
+    enabled: bool,
+    session_token: &str,
+) -> Result<GatewayDaemonStatus, CommandError> {
+    let runner = pidfile_runner()?;
+    if !enabled {
+        remove_linux_systemd_unit()?;
+        runner.uninstall().map_err(CommandError::platform)?;
+        return gateway_daemon_status();
+    }
+    let backend = linux_backend(&runner)?;
+    match backend {
+        LinuxBackend::Systemd => runner.uninstall().map_err(CommandError::platform)?,
+        LinuxBackend::XdgAutostart => remove_linux_systemd_unit()?,
+        LinuxBackend::Pidfile => {
+            remove_linux_systemd_unit()?;
+            runner.remove_autostart_entry().map_err(CommandError::platform)?;
+        }
+    }
+    let install = install_daemon_definition(state, session_token, false)?;
+    let action = install.as_ref().map(|install| install.action);
+    let mut verification = None;
+    if backend == LinuxBackend::Systemd {
+        verification = Some(systemd_unit::verify(&linux_systemd_service_path()?));
+        if action != Some(InstallAction::Unchanged) {
+            let _ = Command::new("systemctl").args(["--user", "daemon-reload"]).status();
+        }
+        // Idempotent: does nothing to a unit that is already enabled and running.
+        let result = Command::new("systemctl")
+            .args(["--user", "enable", "--now", linux_systemd_label()])
+            .status()
+            .map_err(|err| CommandError::platform(format!("failed to enable systemd service: {err}")))?;
+        if !result.success() {
+            return Err(CommandError::platform(
+                "systemd enable --now failed (ensure user services are available; loginctl enable-linger may be required)",
+            ));
+        }
+        // `enable --now` leaves a running service on its old definition.
+        if action == Some(InstallAction::Upgraded) {
+            let _ = Command::new("systemctl")
+                .args(["--user", "restart", linux_systemd_label()])
+                .status();
+        }
+    } else {
+        if backend == LinuxBackend::XdgAutostart {
+            if let Some(parent) = runner.autostart_entry.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|err| CommandError::platform(format!("failed to create autostart directory: {err}")))?;
+            }
+            daemon_install::write_private_file(&runner.autostart_entry, &runner.render_autostart_entry())
+                .map_err(|err| CommandError::platform(format!("failed to write autostart entry: {err}")))?;
+        }
+        // A running runner keeps the script it was started with until it is restarted.
+        if action == Some(InstallAction::Upgraded) {
+            runner.stop().map_err(CommandError::platform)?;
+        }
+        runner.start().map_err(CommandError::platform)?;
+    }
+    // The service now runs this version's runtime, so older copies can go.
+    gateway_launch(state)?.prune_daemon_runtimes();
+    let mut status = gateway_daemon_status()?;
+    status.unit_verification = verification;
+    status.install = install;
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn gateway_daemon_start() -> Result<GatewayDaemonStatus, CommandError> {
+    match std::env::consts::OS {
         "macos" => {
             let target = launchctl_target()?;
             let status = Command::new("launchctl")
                 .args(["kickstart", "-k", &format!("{}/{}", target, macos_launch_agent_label())])
                 .status()
-                .map_err(|err| format!("failed to start launch agent: {err}"))?;
+                .map_err(|err| CommandError::platform(format!("failed to start launch agent: {err}")))?;
             if !status.success() {
-                return Err("launchctl kickstart failed".to_string());
+                return Err(CommandError::platform("launchctl kickstart failed"));
             }
             gateway_daemon_status()
         }
         "linux" => {
+            let runner = pidfile_runner()?;
+            if linux_backend(&runner)? != LinuxBackend::Systemd {
+                if !runner.script.exists() {
+                    return Err(CommandError::validation("enable the always-on service before starting it"));
+                }
+                runner.start().map_err(CommandError::platform)?;
+                return gateway_daemon_status();
+            }
             let status = Command::new("systemctl")
                 .args(["--user", "start", linux_systemd_label()])
                 .status()
-                .map_err(|err| format!("failed to start systemd service: {err}"))?;
+                .map_err(|err| CommandError::platform(format!("failed to start systemd service: {err}")))?;
             if !status.success() {
-                return Err("systemctl --user start failed".to_string());
+                return Err(CommandError::platform("systemctl --user start failed"));
             }
             gateway_daemon_status()
         }
@@ -1213,9 +1285,9 @@ pub fn gateway_daemon_start() -> Result<GatewayDaemonStatus, String> {
             let status = Command::new("sc")
                 .args(["start", windows_service_label()])
                 .status()
-                .map_err(|err| format!("failed to start windows service: {err}"))?;
+                .map_err(|err| CommandError::platform(format!("failed to start windows service: {err}")))?;
             if !status.success() {
-                return Err("sc start failed".to_string());
+                return Err(CommandError::platform("sc start failed"));
             }
             gateway_daemon_status()
         }
@@ -1224,7 +1296,7 @@ pub fn gateway_daemon_start() -> Result<GatewayDaemonStatus, String> {
 }
 
 #[tauri::command]
-pub fn gateway_daemon_stop() -> Result<GatewayDaemonStatus, String> {
+pub fn gateway_daemon_stop() -> Result<GatewayDaemonStatus, CommandError> {
     match std::env::consts::OS {
         "macos" => {
             let target = launchctl_target()?;
@@ -1241,6 +1313,11 @@ pub fn gateway_daemon_stop() -> Result<GatewayDaemonStatus, String> {
             gateway_daemon_status()
         }
         "linux" => {
+            let runner = pidfile_runner()?;
+            if linux_backend(&runner)? != LinuxBackend::Systemd {
+                runner.stop().map_err(CommandError::platform)?;
+                return gateway_daemon_status();
+            }
             let _ = Command::new("systemctl")
                 .args(["--user", "stop", linux_systemd_label()])
                 .status();
@@ -1255,26 +1332,35 @@ pub fn gateway_daemon_stop() -> Result<GatewayDaemonStatus, String> {
 }
 
 #[tauri::command]
-pub fn gateway_daemon_restart() -> Result<GatewayDaemonStatus, String> {
+pub fn gateway_daemon_restart() -> Result<GatewayDaemonStatus, CommandError> {
     match std::env::consts::OS {
         "macos" => {
             let target = launchctl_target()?;
             let status = Command::new("launchctl")
                 .args(["kickstart", "-k", &format!("{}/{}", target, macos_launch_agent_label())])
                 .status()
-                .map_err(|err| format!("failed to restart launch agent: {err}"))?;
+                .map_err(|err| CommandError::platform(format!("failed to restart launch agent: {err}")))?;
             if !status.success() {
-                return Err("launchctl kickstart failed".to_string());
+                return Err(CommandError::platform("launchctl kickstart failed"));
             }
             gateway_daemon_status()
         }
         "linux" => {
+            let runner = pidfile_runner()?;
+            if linux_backend(&runner)? != LinuxBackend::Systemd {
+                if !runner.script.exists() {
+                    return Err(CommandError::validation("enable the always-on service before restarting it"));
+                }
+                runner.stop().map_err(CommandError::platform)?;
+                runner.start().map_err(CommandError::platform)?;
+                return gateway_daemon_status();
+            }
             let status = Command::new("systemctl")
                 .args(["--user", "restart", linux_systemd_label()])
                 .status()
-                .map_err(|err| format!("failed to restart systemd service: {err}"))?;
+                .map_err(|err| CommandError::platform(format!("failed to restart systemd service: {err}")))?;
             if !status.success() {
-                return Err("systemctl --user restart failed".to_string());
+                return Err(CommandError::platform("systemctl --user restart failed"));
             }
             gateway_daemon_status()
         }
@@ -1283,9 +1369,9 @@ pub fn gateway_daemon_restart() -> Result<GatewayDaemonStatus, String> {
             let status = Command::new("sc")
                 .args(["start", windows_service_label()])
                 .status()
-                .map_err(|err| format!("failed to restart windows service: {err}"))?;
+                .map_err(|err| CommandError::platform(format!("failed to restart windows service: {err}")))?;
             if !status.success() {
-                return Err("sc start failed".to_string());
+                return Err(CommandError::platform("sc start failed"));
             }
             gateway_daemon_status()
         }
@@ -1293,22 +1379,35 @@ pub fn gateway_daemon_restart() -> Result<GatewayDaemonStatus, String> {
     }
 }
 
-async fn request_gateway_shutdown(reason: &str, actor: &str) -> Result<(), String> {
-    let client = gateway_client();
-    client
-        .post(format!("{}/system/shutdown", gateway_base_url()))
-        .json(&serde_json::json!({
-            "reason": reason,
-            "actor": actor
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("failed to request gateway shutdown: {err}"))?;
-    Ok(())
+/// Recent output of the always-on gateway, oldest first: the user unit's journal
+/// (or the background runner's log file) on Linux, the launch agent's log files on
+/// macOS, and Application event log entries on Windows. `since` is milliseconds since the Unix epoch.
+#[tauri::command]
+pub fn gateway_daemon_logs(lines: Option<u32>, since: Option<u64>) -> Result<Vec<DaemonLogLine>, CommandError> {
+    let lines = lines.unwrap_or(200).clamp(1, 5_000) as usize;
+    let result = match std::env::consts::OS {
+        "macos" => {
+            let home = std::env::var("HOME").map_err(|_| CommandError::platform("HOME is not set"))?;
+            let dir = PathBuf::from(home).join("Library").join("Logs").join("MajorClaw");
+            daemon_logs::read_log_files(&[dir.join("stdout.log"), dir.join("stderr.log")], lines, since)
+        }
+        "linux" => {
+            let runner = pidfile_runner()?;
+            match linux_backend(&runner)? {
+                LinuxBackend::Systemd => {
+                    daemon_logs::read_journal(&format!("{}.service", linux_systemd_label()), lines, since)
+                }
+                _ => daemon_logs::read_log_files(&[runner.log.with_extension("log.1"), runner.log], lines, since),
+            }
+        }
+        "windows" => daemon_logs::read_event_log(windows_service_label(), lines, since),
+        other => return Err(CommandError::validation(format!("daemon logs are not available on {other}"))),
+    };
+    result.map_err(CommandError::platform)
 }
 
-async fn wait_for_process_exit(child: &mut std::process::Child, timeout_ms: u64) -> Result<bool, String> {
-    let started = std::time::Instant::now();
+async fn wait_for_process_exit(child: &mut std::process::Child, timeout_ms: u64) -> Result<bool, CommandError> {
+    let started = Instant::now();
     loop {
         match child.try_wait() {
             Ok(Some(_)) => return Ok(true),
@@ -1318,23 +1417,25 @@ async fn wait_for_process_exit(child: &mut std::process::Child, timeout_ms: u64)
                 }
                 tokio::time::sleep(std::time::Duration::from_millis(120)).await;
             }
-            Err(err) => return Err(format!("failed to inspect gateway process: {err}")),
+            Err(err) => return Err(CommandError::platform(format!("failed to inspect gateway process: {err}"))),
         }
     }
 }
 
-async fn stop_gateway_process(
+pub async fn stop_gateway_process(
     state: &GatewayState,
+    client: &GatewayClient,
     reason: &str,
     actor: &str,
     graceful_timeout_ms: u64,
     kill_timeout_ms: u64,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
+    let runtime = state.active();
     let maybe_child = {
-        let mut guard = state
+        let mut guard = runtime
             .child
             .lock()
-            .map_err(|_| "gateway state lock poisoned".to_string())?;
+            .map_err(|_| CommandError::lock_poisoned("gateway state"))?;
         guard.take()
     };
     let mut child = if let Some(child) = maybe_child {
@@ -1343,7 +1444,7 @@ async fn stop_gateway_process(
         return Ok(false);
     };
 
-    let _ = request_gateway_shutdown(reason, actor).await;
+    let _ = client.request_shutdown(reason, actor).await;
     let exited = wait_for_process_exit(&mut child, graceful_timeout_ms).await?;
     if !exited {
         let _ = child.kill();
@@ -1354,69 +1455,59 @@ async fn stop_gateway_process(
 }
 
 #[tauri::command]
-pub fn start_gateway(state: State<'_, GatewayState>) -> Result<GatewayStatus, String> {
+pub async fn start_gateway(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    supervisor: State<'_, Supervisor>,
+) -> Result<GatewayStatus, CommandError> {
+    supervisor.reset();
     state
         .desired_running
         .store(true, std::sync::atomic::Ordering::SeqCst);
-    start_gateway_inner(state.inner())
+    ensure_gateway(state.inner(), client.inner()).await
 }
 
 #[tauri::command]
-pub async fn stop_gateway(state: State<'_, GatewayState>) -> Result<GatewayStatus, String> {
+pub async fn stop_gateway(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+) -> Result<GatewayStatus, CommandError> {
     state
         .desired_running
         .store(false, std::sync::atomic::Ordering::SeqCst);
-    let _ = stop_gateway_process(state.inner(), "manual_stop", "user", 8_500, 1_500).await?;
-    Ok(GatewayStatus {
-        running: false,
-        port: GATEWAY_PORT,
-    })
+    let _ = stop_gateway_process(state.inner(), client.inner(), "manual_stop", "user", 8_500, 1_500).await?;
+    gateway_status_for(state.inner(), false)
 }
 
-pub async fn stop_gateway_for_exit(state: &GatewayState) -> Result<GatewayStatus, String> {
+pub async fn stop_gateway_for_exit(state: &GatewayState, client: &GatewayClient) -> Result<GatewayStatus, CommandError> {
     state
         .desired_running
         .store(false, std::sync::atomic::Ordering::SeqCst);
-    let _ = stop_gateway_process(state, "app_exit", "app", 8_500, 1_500).await?;
-    Ok(GatewayStatus {
-        running: false,
-        port: GATEWAY_PORT,
-    })
+    let _ = stop_gateway_process(state, client, "app_exit", "app", 8_500, 1_500).await?;
+    gateway_status_for(state, false)
 }
 
 #[tauri::command]
-pub async fn red_phone_shutdown(state: State<'_, GatewayState>, reason: String) -> Result<RedPhoneResult, String> {
+pub async fn red_phone_shutdown(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    reason: String,
+) -> Result<RedPhoneResult, CommandError> {
     let note = reason.trim();
     if note.is_empty() {
-        return Err("red phone requires a reason".to_string());
+        return Err(CommandError::validation("red phone requires a reason"));
     }
-    let client = gateway_client();
     let mut audited = false;
     let mut audit_log: Option<AuditLogEntry> = None;
-    if let Ok(response) = client
-        .post(format!("{}/system/red-phone", gateway_base_url()))
-        .json(&serde_json::json!({
-            "reason": note,
-            "actor": "user"
-        }))
-        .send()
-        .await
-    {
-        if let Ok(payload) = response.json::<serde_json::Value>().await {
-            let maybe_log = payload.get("log").cloned().unwrap_or(serde_json::Value::Null);
-            if !maybe_log.is_null() {
-                if let Ok(parsed) = serde_json::from_value::<AuditLogEntry>(maybe_log) {
-                    audited = true;
-                    audit_log = Some(parsed);
-                }
-            }
-        }
+    if let Ok(Some(parsed)) = client.red_phone(note, "user").await {
+        audited = true;
+        audit_log = Some(parsed);
     }
     state
         .desired_running
         .store(false, std::sync::atomic::Ordering::SeqCst);
     let shutdown_reason = format!("red_phone:{note}");
-    let _ = stop_gateway_process(state.inner(), &shutdown_reason, "user", 1_500, 1_500).await?;
+    let _ = stop_gateway_process(state.inner(), client.inner(), &shutdown_reason, "user", 1_500, 1_500).await?;
     let timestamp = format!("{:?}", std::time::SystemTime::now());
     Ok(RedPhoneResult {
         status: "stopped".to_string(),
@@ -1428,570 +1519,603 @@ pub async fn red_phone_shutdown(state: State<'_, GatewayState>, reason: String)
 }
 
 #[tauri::command]
-pub fn gateway_status(state: State<'_, GatewayState>) -> Result<GatewayStatus, String> {
-    let mut guard = state.child.lock().map_err(|_| "gateway state lock poisoned".to_string())?;
-    let running = match guard.as_mut() {
-        Some(child) => match child.try_wait() {
-            Ok(None) => true,
-            Ok(Some(_)) => {
-                *guard = None;
-                false
-            }
-            Err(err) => return Err(format!("failed to inspect gateway process: {err}")),
-        },
-        None => false,
+pub fn gateway_status(state: State<'_, GatewayState>) -> Result<GatewayStatus, CommandError> {
+    // Only looks: an exited child stays in place for the supervisor to reap, so its
+    // exit code still reaches the restart it reports.
+    let running = owns_running_child(&state.active())?;
+    gateway_status_for(state.inner(), running)
+}
+
+/// Checks what a gateway launch needs (runtime, working dir, port, vault dir and,
+/// on Linux, the systemd user session) so the UI can explain a failed start.
+#[tauri::command]
+pub fn gateway_preflight(state: State<'_, GatewayState>) -> Result<PreflightReport, CommandError> {
+    let runtime = state.active();
+    let endpoint = match runtime.remote {
+        Some(_) => None,
+        None => Some(
+            runtime
+                .endpoint
+                .lock()
+                .map_err(|_| CommandError::lock_poisoned("gateway endpoint"))?
+                .clone(),
+        ),
     };
+    let attached = runtime
+        .attached
+        .lock()
+        .map_err(|_| CommandError::lock_poisoned("gateway owner"))?
+        .is_some();
+    let owns_running_gateway = attached || owns_running_child(&runtime)?;
+    Ok(preflight::run(PreflightInput {
+        launch: state.launch.as_ref().map_err(String::as_str),
+        endpoint,
+        owns_running_gateway,
+    }))
+}
 
-    Ok(GatewayStatus {
-        running,
-        port: GATEWAY_PORT,
-    })
+#[tauri::command]
+pub fn gateway_supervisor_status(supervisor: State<'_, Supervisor>) -> Result<SupervisorStatus, CommandError> {
+    Ok(supervisor.status())
 }
 
 #[tauri::command]
-pub fn gateway_session_token() -> Result<String, String> {
-    Ok(ensure_gateway_session_token())
+pub fn gateway_process_logs(
+    state: State<'_, GatewayState>,
+    limit: Option<u32>,
+    level: Option<String>,
+) -> Result<Vec<GatewayLogLine>, CommandError> {
+    let min_level = level.filter(|value| !value.trim().is_empty());
+    Ok(state.logs.recent(limit.unwrap_or(200) as usize, min_level.as_deref()))
 }
 
 #[tauri::command]
-pub async fn gateway_health() -> Result<GatewayHealth, String> {
-    let response = gateway_get(format!("{}/health", gateway_base_url()))
-        .await
-        .map_err(|err| format!("gateway health request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("gateway health decode failed: {err}"))?;
-
-    Ok(GatewayHealth {
-        status: payload
-            .get("status")
-            .and_then(|value| value.as_str())
-            .unwrap_or("unknown")
-            .to_string(),
-        started_at: payload
-            .get("startedAt")
-            .and_then(|value| value.as_str())
-            .map(|value| value.to_string()),
-        instance_count: payload.get("instanceCount").and_then(|value| value.as_u64()),
-    })
+pub fn gateway_session_token(client: State<'_, GatewayClient>) -> Result<String, CommandError> {
+    Ok(client.session_token()?)
 }
 
+/// Replaces the session token. The gateway only reads its token at startup, so it
+/// has to be restarted with the new one, by whoever owns it: a gateway this host
+/// spawned is stopped and respawned, and one run by the always-on service is
+/// restarted by reinstalling the service with the new token. A gateway started
+/// outside the app can't be restarted from here, so rotating is refused while
+/// attached to one. The daemon unit serves the default profile, so it is only
+/// rewritten when that profile is active.
 #[tauri::command]
-pub async fn clawhub_search(query: String, sort: String) -> Result<Vec<ClawHubSkill>, String> {
-    let url = format!(
-        "{}/clawhub/search?query={}&sort={}",
-        gateway_base_url(),
-        urlencoding::encode(&query),
-        urlencoding::encode(&sort)
-    );
-    let response = gateway_get(url)
-        .await
-        .map_err(|err| format!("clawhub search request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("clawhub search decode failed: {err}"))?;
-    let skills = payload
-        .get("skills")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(skills).map_err(|err| format!("clawhub search parse failed: {err}"))
+pub async fn rotate_gateway_session_token(
+    app: AppHandle,
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    secrets: State<'_, SecretStore>,
+) -> Result<String, CommandError> {
+    if state.remote().is_some() {
+        return Err(CommandError::validation(
+            "the remote gateway's token is managed on its own host",
+        ));
+    }
+    let runtime = state.active();
+    let attached = *runtime
+        .attached
+        .lock()
+        .map_err(|_| CommandError::lock_poisoned("gateway owner"))?;
+    let daemon_owned = daemon_owns_gateway(state.inner());
+    match attached {
+        // `App` here is a gateway another app instance spawned, not this host's child.
+        Some(GatewayOwner::External | GatewayOwner::App) => {
+            return Err(CommandError::validation(
+                "the running gateway was not started by this app and keeps its own token; stop it first",
+            ));
+        }
+        Some(GatewayOwner::Daemon) if !daemon_owned => {
+            return Err(CommandError::validation(
+                "the always-on service running the gateway is not enabled for this profile; enable it or stop it first",
+            ));
+        }
+        _ => {}
+    }
+    let next = generate_session_token()?;
+    persist_session_token(secrets.inner(), &runtime.name, &next)?;
+    if daemon_owned {
+        // Rewrites the unit or its environment file, which restarts the service. That
+        // waits on the service manager or the background runner, so it runs off the
+        // async runtime.
+        let (blocking_app, token) = (app.clone(), next.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            apply_gateway_daemon_enabled(blocking_app.state::<GatewayState>().inner(), true, &token)
+        })
+        .await
+        .map_err(|err| CommandError::platform(format!("failed to update the always-on service: {err}")))??;
+        client.set_session_token(next.clone());
+        // Attaching to the restarted service, token check included, is left to the supervisor.
+        *runtime
+            .attached
+            .lock()
+            .map_err(|_| CommandError::lock_poisoned("gateway owner"))? = None;
+        return Ok(next);
+    }
+    let was_running = stop_gateway_process(
+        state.inner(),
+        client.inner(),
+        "session_token_rotation",
+        "user",
+        8_500,
+        1_500,
+    )
+    .await?;
+    client.set_session_token(next.clone());
+    if was_running || state.desired_running.load(std::sync::atomic::Ordering::SeqCst) {
+        start_gateway_inner(state.inner(), client.inner())?;
+    }
+    Ok(next)
 }
 
 #[tauri::command]
-pub async fn list_agents() -> Result<Vec<AgentProfile>, String> {
-    let response = gateway_get(format!("{}/agents", gateway_base_url()))
-        .await
-        .map_err(|err| format!("agents request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("agents decode failed: {err}"))?;
-    let agents = payload
-        .get("agents")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(agents).map_err(|err| format!("agents parse failed: {err}"))
+pub fn remote_gateway_config(state: State<'_, GatewayState>) -> Option<RemoteGateway> {
+    state.remote()
 }
 
+/// Saves a remote gateway as a profile (`remote` unless named) and switches to it.
+/// The connection is probed before anything changes.
 #[tauri::command]
-pub async fn list_tasks() -> Result<Vec<TaskRecord>, String> {
-    let response = gateway_get(format!("{}/tasks", gateway_base_url()))
-        .await
-        .map_err(|err| format!("tasks request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("tasks decode failed: {err}"))?;
-    let tasks = payload
-        .get("tasks")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(tasks).map_err(|err| format!("tasks parse failed: {err}"))
-}
-
-#[tauri::command]
-pub async fn create_task(payload: TaskCreatePayload) -> Result<TaskRecord, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/tasks/create", gateway_base_url()))
-        .json(&serde_json::json!({
-            "title": payload.title,
-            "description": payload.description,
-            "status": payload.status,
-            "priority": payload.priority,
-            "assignee_agent_id": payload.assignee_agent_id,
-            "parent_task_id": payload.parent_task_id
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("create task request failed: {err}"))?;
-    let parsed = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("create task decode failed: {err}"))?;
-    let task = parsed
-        .get("task")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(task).map_err(|err| format!("create task parse failed: {err}"))
+pub async fn connect_remote_gateway(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    secrets: State<'_, SecretStore>,
+    supervisor: State<'_, Supervisor>,
+    config: RemoteGateway,
+    token: String,
+    name: Option<String>,
+) -> Result<GatewayStatus, CommandError> {
+    let profile = GatewayProfile {
+        name: name.unwrap_or_else(|| "remote".to_string()),
+        target: GatewayTarget::Remote(config),
+    };
+    let profile = save_profile(state.inner(), secrets.inner(), profile, Some(token)).await?;
+    switch_to_profile(state.inner(), client.inner(), secrets.inner(), supervisor.inner(), &profile).await
 }
 
+/// Goes back to the default profile, a gateway spawned and supervised by this host.
+/// The remote profile stays saved for a later switch.
 #[tauri::command]
-pub async fn update_task(task_id: String, patch: TaskPatch) -> Result<TaskRecord, String> {
-    let client = gateway_client();
-    let mut patch_payload = serde_json::Map::new();
-    if let Some(title) = patch.title {
-        patch_payload.insert("title".to_string(), serde_json::Value::String(title));
-    }
-    if let Some(description) = patch.description {
-        patch_payload.insert("description".to_string(), serde_json::Value::String(description));
-    }
-    if let Some(status) = patch.status {
-        patch_payload.insert("status".to_string(), serde_json::Value::String(status));
+pub async fn disconnect_remote_gateway(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    secrets: State<'_, SecretStore>,
+    supervisor: State<'_, Supervisor>,
+) -> Result<GatewayStatus, CommandError> {
+    let profile = state.profiles().get(DEFAULT_PROFILE).cloned().unwrap_or_default();
+    switch_to_profile(state.inner(), client.inner(), secrets.inner(), supervisor.inner(), &profile).await
+}
+
+#[tauri::command]
+pub fn list_gateway_profiles(state: State<'_, GatewayState>) -> GatewayProfiles {
+    state.profiles().clone()
+}
+
+/// Adds or replaces a profile without switching to it. Remote profiles are probed
+/// with `token` first and need one the first time they are saved; a new token for
+/// the active profile applies from the next switch or launch.
+#[tauri::command]
+pub async fn save_gateway_profile(
+    state: State<'_, GatewayState>,
+    secrets: State<'_, SecretStore>,
+    profile: GatewayProfile,
+    token: Option<String>,
+) -> Result<GatewayProfiles, CommandError> {
+    save_profile(state.inner(), secrets.inner(), profile, token).await?;
+    Ok(state.profiles().clone())
+}
+
+#[tauri::command]
+pub fn delete_gateway_profile(state: State<'_, GatewayState>, name: String) -> Result<GatewayProfiles, CommandError> {
+    let mut profiles = state.profiles();
+    profiles.remove(name.trim()).map_err(CommandError::validation)?;
+    state.persist_profiles(&profiles)?;
+    Ok(profiles.clone())
+}
+
+/// Makes `name` the active profile: stops the gateway this host spawned for the
+/// previous profile, points the shared client at the new one, and spawns its
+/// gateway if it is local. Remote profiles are probed before anything is stopped.
+#[tauri::command]
+pub async fn switch_gateway_profile(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    secrets: State<'_, SecretStore>,
+    supervisor: State<'_, Supervisor>,
+    name: String,
+) -> Result<GatewayStatus, CommandError> {
+    let profile = state
+        .profiles()
+        .get(name.trim())
+        .cloned()
+        .ok_or_else(|| CommandError::validation(format!("no gateway profile named {:?}", name.trim())))?;
+    switch_to_profile(state.inner(), client.inner(), secrets.inner(), supervisor.inner(), &profile).await
+}
+
+async fn save_profile(
+    state: &GatewayState,
+    secrets: &SecretStore,
+    profile: GatewayProfile,
+    token: Option<String>,
+) -> Result<GatewayProfile, CommandError> {
+    let profile = profile.validated().map_err(CommandError::validation)?;
+    let token = token.map(|token| token.trim().to_string()).filter(|token| !token.is_empty());
+    if let GatewayTarget::Remote(remote) = &profile.target {
+        let token = token
+            .clone()
+            .or_else(|| stored_session_token(secrets, &profile.name))
+            .ok_or_else(|| CommandError::validation("remote gateway token is required"))?;
+        probe_remote(remote, &token).await?;
     }
-    if let Some(priority) = patch.priority {
-        patch_payload.insert("priority".to_string(), serde_json::Value::String(priority));
+    if let Some(token) = token.as_deref() {
+        persist_session_token(secrets, &profile.name, token)?;
     }
-    if let Some(assignee) = patch.assignee_agent_id {
-        match assignee {
-            Some(value) => {
-                patch_payload.insert(
-                    "assignee_agent_id".to_string(),
-                    serde_json::Value::String(value),
-                );
-            }
-            None => {
-                patch_payload.insert("assignee_agent_id".to_string(), serde_json::Value::Null);
-            }
+    let mut profiles = state.profiles();
+    profiles.upsert(profile.clone()).map_err(CommandError::validation)?;
+    state.persist_profiles(&profiles)?;
+    Ok(profile)
+}
+
+/// Checks that `remote` answers an authenticated call with `token`, so a wrong URL,
+/// certificate or token fails before anything is saved or switched.
+async fn probe_remote(remote: &RemoteGateway, token: &str) -> Result<GatewayTls, CommandError> {
+    let tls = remote.tls().map_err(CommandError::validation)?;
+    let probe = GatewayClient::new(&remote.base_url, token, &tls).map_err(CommandError::from)?;
+    probe.list_agents().await.map_err(CommandError::from)?;
+    Ok(tls)
+}
+
+async fn switch_to_profile(
+    state: &GatewayState,
+    client: &GatewayClient,
+    secrets: &SecretStore,
+    supervisor: &Supervisor,
+    profile: &GatewayProfile,
+) -> Result<GatewayStatus, CommandError> {
+    let (token, tls) = match &profile.target {
+        GatewayTarget::Remote(remote) => {
+            let token = stored_session_token(secrets, &profile.name)
+                .ok_or_else(|| CommandError::validation("no token saved for this remote gateway profile"))?;
+            let tls = probe_remote(remote, &token).await?;
+            (token, tls)
         }
+        GatewayTarget::Local(_) => (profile_session_token(secrets, &profile.name)?, GatewayTls::default()),
+    };
+
+    state
+        .desired_running
+        .store(false, std::sync::atomic::Ordering::SeqCst);
+    let _ = stop_gateway_process(state, client, "profile_switch", "user", 8_500, 1_500).await?;
+    state.activate(profile)?;
+    let runtime = state.active();
+    let base_url = match runtime.remote.as_ref() {
+        Some(remote) => remote.base_url.clone(),
+        None => runtime
+            .endpoint
+            .lock()
+            .map_err(|_| CommandError::lock_poisoned("gateway endpoint"))?
+            .base_url(),
+    };
+    client.reconfigure(base_url, token, &tls).map_err(CommandError::from)?;
+    state.connection_epoch.send_modify(|epoch| *epoch += 1);
+    supervisor.reset();
+    if profile.is_remote() {
+        return gateway_status_for(state, true);
     }
-    let response = client
-        .patch(format!(
-            "{}/tasks/{}",
-            gateway_base_url(),
-            urlencoding::encode(&task_id)
-        ))
-        .json(&serde_json::Value::Object(patch_payload))
-        .send()
-        .await
-        .map_err(|err| format!("update task request failed: {err}"))?;
-    let parsed = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("update task decode failed: {err}"))?;
-    let task = parsed
-        .get("task")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(task).map_err(|err| format!("update task parse failed: {err}"))
+    state
+        .desired_running
+        .store(true, std::sync::atomic::Ordering::SeqCst);
+    ensure_gateway(state, client).await
 }
 
 #[tauri::command]
-pub async fn delete_task(task_id: String) -> Result<bool, String> {
-    let client = gateway_client();
-    let response = client
-        .delete(format!(
-            "{}/tasks/{}",
-            gateway_base_url(),
-            urlencoding::encode(&task_id)
-        ))
-        .send()
-        .await
-        .map_err(|err| format!("delete task request failed: {err}"))?;
-    if response.status().is_success() {
-        return Ok(true);
-    }
-    Err(format!("delete task failed with status {}", response.status()))
-}
-
-#[tauri::command]
-pub async fn create_agent(payload: AgentCreatePayload) -> Result<AgentProfile, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/agents/create", gateway_base_url()))
-        .json(&serde_json::json!({
-            "name": payload.name,
-            "role": payload.role,
-            "parent_id": payload.parent_id,
-            "model_provider": payload.model_provider,
-            "model_name": payload.model_name,
-            "api_key": payload.api_key,
-            "temperature": payload.temperature,
-            "max_tokens": payload.max_tokens
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("create agent request failed: {err}"))?;
-    let parsed = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("create agent decode failed: {err}"))?;
-    let agent = parsed
-        .get("agent")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(agent).map_err(|err| format!("create agent parse failed: {err}"))
+pub async fn gateway_health(client: State<'_, GatewayClient>) -> Result<GatewayHealth, CommandError> {
+    client.health().await.map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn reorder_agents(order: Vec<String>) -> Result<Vec<AgentProfile>, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/agents/reorder", gateway_base_url()))
-        .json(&serde_json::json!({ "order": order }))
-        .send()
-        .await
-        .map_err(|err| format!("reorder agents request failed: {err}"))?;
-    let parsed = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("reorder agents decode failed: {err}"))?;
-    let agents = parsed
-        .get("agents")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(agents).map_err(|err| format!("reorder agents parse failed: {err}"))
-}
-
-#[tauri::command]
-pub async fn update_agent_config(agent_id: String, config: AgentConfigPatch) -> Result<AgentProfile, String> {
-    let client = gateway_client();
-    let response = client
-        .patch(format!("{}/agents/{}/config", gateway_base_url(), urlencoding::encode(&agent_id)))
-        .json(&serde_json::json!({
-            "model_provider": config.model_provider,
-            "model_name": config.model_name,
-            "api_key": config.api_key,
-            "temperature": config.temperature,
-            "max_tokens": config.max_tokens,
-            "status": config.status
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("update agent config request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn clawhub_search(
+    client: State<'_, GatewayClient>,
+    query: String,
+    sort: String,
+) -> Result<Vec<ClawHubSkill>, CommandError> {
+    client
+        .clawhub_search(&query, &sort)
         .await
-        .map_err(|err| format!("update agent config decode failed: {err}"))?;
-    let agent = payload
-        .get("agent")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(agent).map_err(|err| format!("update agent config parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_agent_config(agent_id: String) -> Result<AgentFullConfig, String> {
-    let response = gateway_get(format!(
-        "{}/agents/{}/full",
-        gateway_base_url(),
-        urlencoding::encode(&agent_id)
-    ))
-    .await
-    .map_err(|err| format!("agent config request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("agent config decode failed: {err}"))?;
-    let agent = payload
-        .get("agent")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(agent).map_err(|err| format!("agent config parse failed: {err}"))
+pub async fn list_agents(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    cache: State<'_, ReadCache>,
+) -> Result<Cached<Vec<AgentProfile>>, CommandError> {
+    cache.read(&state.active().name, "agents", client.list_agents()).await
 }
 
 #[tauri::command]
-pub async fn chat_threads() -> Result<Vec<SwarmChatThread>, String> {
-    let response = gateway_get(format!("{}/chat/threads", gateway_base_url()))
-        .await
-        .map_err(|err| format!("chat threads request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn list_tasks(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    cache: State<'_, ReadCache>,
+) -> Result<Cached<Vec<TaskRecord>>, CommandError> {
+    cache.read(&state.active().name, "tasks", client.list_tasks()).await
+}
+
+#[tauri::command]
+pub async fn create_task(
+    app: AppHandle,
+    client: State<'_, GatewayClient>,
+    queue: State<'_, WriteQueue>,
+    payload: TaskCreatePayload,
+) -> Result<WriteOutcome<TaskRecord>, CommandError> {
+    let key = idempotency_key();
+    let apply = client.create_task(&payload, &key);
+    let op = WriteOp::CreateTask {
+        payload: payload.clone(),
+    };
+    queue.submit(&app, op, key.clone(), apply).await
+}
+
+#[tauri::command]
+pub async fn update_task(
+    app: AppHandle,
+    client: State<'_, GatewayClient>,
+    queue: State<'_, WriteQueue>,
+    task_id: String,
+    patch: TaskPatch,
+) -> Result<WriteOutcome<TaskRecord>, CommandError> {
+    let apply = client.update_task(&task_id, &patch);
+    let op = WriteOp::UpdateTask {
+        task_id: task_id.clone(),
+        patch: patch.clone(),
+    };
+    queue.submit(&app, op, idempotency_key(), apply).await
+}
+
+#[tauri::command]
+pub async fn delete_task(
+    app: AppHandle,
+    client: State<'_, GatewayClient>,
+    queue: State<'_, WriteQueue>,
+    task_id: String,
+) -> Result<WriteOutcome<bool>, CommandError> {
+    let apply = async { client.delete_task(&task_id).await.map(|()| true) };
+    let op = WriteOp::DeleteTask {
+        task_id: task_id.clone(),
+    };
+    queue.submit(&app, op, idempotency_key(), apply).await
+}
+
+#[tauri::command]
+pub async fn create_agent(
+    client: State<'_, GatewayClient>,
+    payload: AgentCreatePayload,
+) -> Result<AgentProfile, CommandError> {
+    client
+        .create_agent(&payload)
         .await
-        .map_err(|err| format!("chat threads decode failed: {err}"))?;
-    let threads = payload
-        .get("threads")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(threads).map_err(|err| format!("chat threads parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn chat_summary() -> Result<SwarmSummary, String> {
-    let response = gateway_get(format!("{}/chat/summary", gateway_base_url()))
+pub async fn reorder_agents(
+    client: State<'_, GatewayClient>,
+    order: Vec<String>,
+) -> Result<Vec<AgentProfile>, CommandError> {
+    client
+        .reorder_agents(&order)
         .await
-        .map_err(|err| format!("chat summary request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn update_agent_config(
+    client: State<'_, GatewayClient>,
+    agent_id: String,
+    config: AgentConfigPatch,
+) -> Result<AgentProfile, CommandError> {
+    client
+        .update_agent_config(&agent_id, &config)
         .await
-        .map_err(|err| format!("chat summary decode failed: {err}"))?;
-    let summary = payload
-        .get("summary")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(summary).map_err(|err| format!("chat summary parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn chat_messages(thread_id: String) -> Result<Vec<SwarmChatMessage>, String> {
-    let response = gateway_get(format!(
-        "{}/chat/messages?threadId={}",
-        gateway_base_url(),
-        urlencoding::encode(&thread_id)
-    ))
-    .await
-    .map_err(|err| format!("chat messages request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn get_agent_config(
+    client: State<'_, GatewayClient>,
+    agent_id: String,
+) -> Result<AgentFullConfig, CommandError> {
+    client
+        .get_agent_config(&agent_id)
         .await
-        .map_err(|err| format!("chat messages decode failed: {err}"))?;
-    let messages = payload
-        .get("messages")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(messages).map_err(|err| format!("chat messages parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn chat_send(thread_id: String, content: String, user_id: Option<String>) -> Result<Vec<SwarmChatMessage>, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/chat/send", gateway_base_url()))
-        .json(&serde_json::json!({
-            "thread_id": thread_id,
-            "content": content,
-            "user_id": user_id.unwrap_or_else(|| "user".to_string())
-        }))
-        .send()
+pub async fn chat_threads(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    cache: State<'_, ReadCache>,
+) -> Result<Cached<Vec<SwarmChatThread>>, CommandError> {
+    cache.read(&state.active().name, "chat_threads", client.chat_threads()).await
+}
+
+#[tauri::command]
+pub async fn chat_summary(client: State<'_, GatewayClient>) -> Result<SwarmSummary, CommandError> {
+    client.chat_summary().await.map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn chat_messages(
+    client: State<'_, GatewayClient>,
+    thread_id: String,
+) -> Result<Vec<SwarmChatMessage>, CommandError> {
+    client
+        .chat_messages(&thread_id)
         .await
-        .map_err(|err| format!("chat send request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn chat_send(
+    client: State<'_, GatewayClient>,
+    thread_id: String,
+    content: String,
+    user_id: Option<String>,
+) -> Result<Vec<SwarmChatMessage>, CommandError> {
+    let user = user_id.unwrap_or_else(|| "user".to_string());
+    client
+        .chat_send(&thread_id, &content, &user)
         .await
-        .map_err(|err| format!("chat send decode failed: {err}"))?;
-    let emitted = payload
-        .get("emitted")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(emitted).map_err(|err| format!("chat send parse failed: {err}"))
+        .map_err(CommandError::from)
+}
+
+/// Like `chat_send`, but pushes the reply over `on_event` as the gateway produces it:
+/// deltas from a backend that streams, otherwise each finished message. The call
+/// resolves once the reply is complete or `cancel_request(stream_id)` stops it,
+/// and the last event on the channel is `done`. If the send fails, even midway
+/// through the reply, the last event is `error` instead and the call rejects.
+#[tauri::command]
+pub async fn chat_send_stream(
+    client: State<'_, GatewayClient>,
+    inflight: State<'_, InflightRequests>,
+    stream_id: String,
+    thread_id: String,
+    content: String,
+    user_id: Option<String>,
+    on_event: Channel<ChatStreamEvent>,
+) -> Result<(), CommandError> {
+    let user = user_id.unwrap_or_else(|| "user".to_string());
+    let send = client.chat_send_stream(&thread_id, &content, &user, Some(&stream_id), |event| {
+        let _ = on_event.send(event);
+    });
+    let outcome = match inflight.run(Some(&stream_id), send).await {
+        Ok(outcome) => outcome.map(|result| result.map_err(CommandError::from)),
+        Err(error) => Some(Err(error)),
+    };
+    let cancelled = match outcome {
+        Some(Ok(())) => false,
+        Some(Err(error)) => {
+            let _ = on_event.send(ChatStreamEvent::Error {
+                message: error.to_string(),
+            });
+            return Err(error);
+        }
+        None => true,
+    };
+    let _ = on_event.send(ChatStreamEvent::Done { cancelled });
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn chat_quick_action(
+    client: State<'_, GatewayClient>,
     thread_id: String,
     action: String,
-) -> Result<Vec<SwarmChatMessage>, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/chat/quick", gateway_base_url()))
-        .json(&serde_json::json!({
-            "thread_id": thread_id,
-            "action": action
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("chat quick request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+) -> Result<Vec<SwarmChatMessage>, CommandError> {
+    client
+        .chat_quick_action(&thread_id, &action)
         .await
-        .map_err(|err| format!("chat quick decode failed: {err}"))?;
-    let emitted = payload
-        .get("emitted")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(emitted).map_err(|err| format!("chat quick parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn chat_checkpoints(swarm_id: Option<String>, limit: Option<u32>) -> Result<Vec<CheckpointRecord>, String> {
+pub async fn chat_checkpoints(
+    client: State<'_, GatewayClient>,
+    swarm_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<CheckpointRecord>, CommandError> {
     let resolved_swarm = swarm_id.unwrap_or_else(|| "swarm_main".to_string());
     let cap = limit.unwrap_or(50);
-    let response = gateway_get(format!(
-        "{}/chat/checkpoints?swarmId={}&limit={}",
-        gateway_base_url(),
-        urlencoding::encode(&resolved_swarm),
-        cap
-    ))
-    .await
-    .map_err(|err| format!("chat checkpoints request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+    client
+        .chat_checkpoints(&resolved_swarm, cap)
         .await
-        .map_err(|err| format!("chat checkpoints decode failed: {err}"))?;
-    let checkpoints = payload
-        .get("checkpoints")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(checkpoints).map_err(|err| format!("chat checkpoints parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn chat_rewind(
+    client: State<'_, GatewayClient>,
+    inflight: State<'_, InflightRequests>,
     thread_id: String,
     checkpoint_id: String,
     edit_prompt: Option<String>,
-) -> Result<Vec<SwarmChatMessage>, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/chat/rewind", gateway_base_url()))
-        .json(&serde_json::json!({
-            "thread_id": thread_id,
-            "checkpoint_id": checkpoint_id,
-            "edit_prompt": edit_prompt
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("chat rewind request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("chat rewind decode failed: {err}"))?;
-    let emitted = payload
-        .get("emitted")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(emitted).map_err(|err| format!("chat rewind parse failed: {err}"))
+    request_id: Option<String>,
+) -> Result<Vec<SwarmChatMessage>, CommandError> {
+    let rewind = client.chat_rewind(&thread_id, &checkpoint_id, edit_prompt.as_deref(), request_id.as_deref());
+    inflight
+        .run(request_id.as_deref(), rewind)
+        .await?
+        .ok_or_else(|| CommandError::cancelled("chat rewind"))?
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_budgets() -> Result<BudgetSnapshot, String> {
-    let response = gateway_get(format!("{}/budgets", gateway_base_url()))
-        .await
-        .map_err(|err| format!("budgets request failed: {err}"))?;
-    response
-        .json::<BudgetSnapshot>()
-        .await
-        .map_err(|err| format!("budgets decode failed: {err}"))
+pub async fn get_budgets(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    cache: State<'_, ReadCache>,
+) -> Result<Cached<BudgetSnapshot>, CommandError> {
+    cache.read(&state.active().name, "budgets", client.get_budgets()).await
 }
 
 #[tauri::command]
 pub async fn update_budget(
+    client: State<'_, GatewayClient>,
     agent_id: String,
     token_limit: u64,
     cost_limit_usd: f64,
     hard_kill: bool,
-) -> Result<AgentBudget, String> {
-    let client = gateway_client();
-    let response = client
-        .patch(format!(
-            "{}/budgets/{}",
-            gateway_base_url(),
-            urlencoding::encode(&agent_id)
-        ))
-        .json(&serde_json::json!({
-            "token_limit": token_limit,
-            "cost_limit_usd": cost_limit_usd,
-            "hard_kill": hard_kill
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("update budget request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+) -> Result<AgentBudget, CommandError> {
+    client
+        .update_budget(&agent_id, token_limit, cost_limit_usd, hard_kill)
         .await
-        .map_err(|err| format!("update budget decode failed: {err}"))?;
-    let budget = payload
-        .get("budget")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(budget).map_err(|err| format!("update budget parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn vault_summary() -> Result<VaultSummary, String> {
-    let response = gateway_get(format!("{}/vault/summary", gateway_base_url()))
-        .await
-        .map_err(|err| format!("vault summary request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("vault summary decode failed: {err}"))?;
-    let summary = payload
-        .get("summary")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(summary).map_err(|err| format!("vault summary parse failed: {err}"))
+pub async fn vault_summary(client: State<'_, GatewayClient>) -> Result<VaultSummary, CommandError> {
+    client.vault_summary().await.map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn vault_capacity() -> Result<VaultStorageStats, String> {
-    let response = gateway_get(format!("{}/vault/capacity", gateway_base_url()))
-        .await
-        .map_err(|err| format!("vault capacity request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("vault capacity decode failed: {err}"))?;
-    let stats = payload
-        .get("stats")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(stats).map_err(|err| format!("vault capacity parse failed: {err}"))
+pub async fn vault_capacity(client: State<'_, GatewayClient>) -> Result<VaultStorageStats, CommandError> {
+    client.vault_capacity().await.map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn vault_recent(limit: Option<u32>) -> Result<Vec<VaultEntry>, String> {
+pub async fn vault_recent(
+    state: State<'_, GatewayState>,
+    client: State<'_, GatewayClient>,
+    cache: State<'_, ReadCache>,
+    limit: Option<u32>,
+) -> Result<Cached<Vec<VaultEntry>>, CommandError> {
     let cap = limit.unwrap_or(40);
-    let response = gateway_get(format!("{}/vault/recent?limit={}", gateway_base_url(), cap))
-        .await
-        .map_err(|err| format!("vault recent request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("vault recent decode failed: {err}"))?;
-    let items = payload
-        .get("items")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(items).map_err(|err| format!("vault recent parse failed: {err}"))
+    cache.read(&state.active().name, &format!("vault_recent_{cap}"), client.vault_recent(cap)).await
 }
 
 #[tauri::command]
-pub async fn vault_search(query: String, limit: Option<u32>) -> Result<Vec<VaultEntry>, String> {
+pub async fn vault_search(
+    client: State<'_, GatewayClient>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<VaultEntry>, CommandError> {
     let cap = limit.unwrap_or(40);
-    let response = gateway_get(format!(
-        "{}/vault/search?query={}&limit={}",
-        gateway_base_url(),
-        urlencoding::encode(&query),
-        cap
-    ))
-    .await
-    .map_err(|err| format!("vault search request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+    client
+        .vault_search(&query, cap)
         .await
-        .map_err(|err| format!("vault search decode failed: {err}"))?;
-    let items = payload
-        .get("items")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(items).map_err(|err| format!("vault search parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn vault_deposit(
+    app: AppHandle,
+    client: State<'_, GatewayClient>,
+    queue: State<'_, WriteQueue>,
     entry_type: String,
     title: String,
     markdown_summary: String,
@@ -2000,791 +2124,506 @@ pub async fn vault_deposit(
     agent_id: String,
     task_id: Option<String>,
     encrypted: Option<bool>,
-) -> Result<VaultEntry, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/vault/deposit", gateway_base_url()))
-        .json(&serde_json::json!({
-            "type": entry_type,
-            "title": title,
-            "markdown_summary": markdown_summary,
-            "importance_score": importance_score.unwrap_or(7),
-            "tags": tags.unwrap_or_default(),
-            "agent_id": agent_id,
-            "task_id": task_id,
-            "encrypted": encrypted.unwrap_or(false)
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("vault deposit request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("vault deposit decode failed: {err}"))?;
-    let entry = payload
-        .get("entry")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(entry).map_err(|err| format!("vault deposit parse failed: {err}"))
+) -> Result<WriteOutcome<VaultEntry>, CommandError> {
+    let payload = VaultDepositPayload {
+        entry_type,
+        title,
+        markdown_summary,
+        importance_score: importance_score.unwrap_or(7),
+        tags: tags.unwrap_or_default(),
+        agent_id,
+        task_id,
+        encrypted: encrypted.unwrap_or(false),
+    };
+    let key = idempotency_key();
+    let apply = client.vault_deposit(&payload, &key);
+    let op = WriteOp::VaultDeposit {
+        payload: payload.clone(),
+    };
+    queue.submit(&app, op, key.clone(), apply).await
 }
 
 #[tauri::command]
-pub async fn vault_prune(max_importance: Option<u64>) -> Result<serde_json::Value, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/vault/prune", gateway_base_url()))
-        .json(&serde_json::json!({
-            "max_importance": max_importance.unwrap_or(3)
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("vault prune request failed: {err}"))?;
-    response
-        .json::<serde_json::Value>()
+pub async fn vault_prune(
+    client: State<'_, GatewayClient>,
+    max_importance: Option<u64>,
+) -> Result<serde_json::Value, CommandError> {
+    client
+        .vault_prune(max_importance.unwrap_or(3))
         .await
-        .map_err(|err| format!("vault prune decode failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn vault_list_versions(entry_id: String) -> Result<Vec<VaultVersion>, String> {
-    let response = gateway_get(format!(
-        "{}/vault/entries/{}/versions",
-        gateway_base_url(),
-        urlencoding::encode(&entry_id)
-    ))
-    .await
-    .map_err(|err| format!("vault versions request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn vault_list_versions(
+    client: State<'_, GatewayClient>,
+    entry_id: String,
+) -> Result<Vec<VaultVersion>, CommandError> {
+    client
+        .vault_list_versions(&entry_id)
         .await
-        .map_err(|err| format!("vault versions decode failed: {err}"))?;
-    let versions = payload
-        .get("versions")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(versions).map_err(|err| format!("vault versions parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn vault_update_entry(
+    app: AppHandle,
+    client: State<'_, GatewayClient>,
+    queue: State<'_, WriteQueue>,
     entry_id: String,
     title: Option<String>,
     markdown_summary: Option<String>,
     importance_score: Option<u64>,
     tags: Option<Vec<String>>,
     encrypted: Option<bool>,
-) -> Result<VaultEntry, String> {
-    let client = gateway_client();
-    let response = client
-        .patch(format!(
-            "{}/vault/entries/{}",
-            gateway_base_url(),
-            urlencoding::encode(&entry_id)
-        ))
-        .json(&serde_json::json!({
-            "title": title,
-            "markdown_summary": markdown_summary,
-            "importance_score": importance_score,
-            "tags": tags,
-            "encrypted": encrypted
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("vault update entry request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("vault update entry decode failed: {err}"))?;
-    let entry = payload
-        .get("entry")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(entry).map_err(|err| format!("vault update entry parse failed: {err}"))
+) -> Result<WriteOutcome<VaultEntry>, CommandError> {
+    let patch = VaultEntryPatch {
+        title,
+        markdown_summary,
+        importance_score,
+        tags,
+        encrypted,
+    };
+    let apply = client.vault_update_entry(&entry_id, &patch);
+    let op = WriteOp::VaultUpdateEntry {
+        entry_id: entry_id.clone(),
+        patch: patch.clone(),
+    };
+    queue.submit(&app, op, idempotency_key(), apply).await
+}
+
+#[tauri::command]
+pub fn write_queue_pending(state: State<'_, GatewayState>, queue: State<'_, WriteQueue>) -> Vec<QueuedWrite> {
+    queue.pending(&state.active().name)
+}
+
+/// Queued writes the gateway rejected on replay, oldest first.
+#[tauri::command]
+pub fn write_queue_conflicts(state: State<'_, GatewayState>, queue: State<'_, WriteQueue>) -> Vec<WriteConflict> {
+    queue.conflicts(&state.active().name)
+}
+
+/// Dismisses one conflict, or all of them when `conflict_id` is omitted.
+#[tauri::command]
+pub fn dismiss_write_conflict(
+    app: AppHandle,
+    queue: State<'_, WriteQueue>,
+    conflict_id: Option<String>,
+) -> Result<usize, CommandError> {
+    queue.dismiss_conflict(&app, conflict_id.as_deref())
+}
+
+/// Replays queued writes now instead of waiting for the next reconnect.
+#[tauri::command]
+pub async fn replay_write_queue(app: AppHandle) -> Result<usize, CommandError> {
+    Ok(write_queue::replay(app).await)
 }
 
 #[tauri::command]
 pub async fn vault_create_version(
+    client: State<'_, GatewayClient>,
     entry_id: String,
     markdown_summary: Option<String>,
     blob_path: Option<String>,
     diff: Option<String>,
     importance_score: Option<u64>,
     tags: Option<Vec<String>>,
-) -> Result<VaultVersion, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!(
-            "{}/vault/entries/{}/versions",
-            gateway_base_url(),
-            urlencoding::encode(&entry_id)
-        ))
-        .json(&serde_json::json!({
-            "markdown_summary": markdown_summary,
-            "blob_path": blob_path,
-            "diff": diff,
-            "importance_score": importance_score,
-            "tags": tags
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("vault create version request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+) -> Result<VaultVersion, CommandError> {
+    client
+        .vault_create_version(
+            &entry_id,
+            markdown_summary.as_deref(),
+            blob_path.as_deref(),
+            diff.as_deref(),
+            importance_score,
+            tags.as_deref(),
+        )
         .await
-        .map_err(|err| format!("vault create version decode failed: {err}"))?;
-    let version = payload
-        .get("version")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(version).map_err(|err| format!("vault create version parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn vault_storage_info() -> Result<VaultStorageInfo, String> {
-    let response = gateway_get(format!("{}/vault/storage/info", gateway_base_url()))
-        .await
-        .map_err(|err| format!("vault storage info request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn vault_storage_info(client: State<'_, GatewayClient>) -> Result<VaultStorageInfo, CommandError> {
+    client
+        .vault_storage_info()
         .await
-        .map_err(|err| format!("vault storage info decode failed: {err}"))?;
-    let info = payload
-        .get("info")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(info).map_err(|err| format!("vault storage info parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn vault_relocate_storage(path: String, move_existing: Option<bool>) -> Result<VaultStorageInfo, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/vault/storage/relocate", gateway_base_url()))
-        .json(&serde_json::json!({
-            "path": path,
-            "move_existing": move_existing.unwrap_or(true)
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("vault relocate request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("vault relocate decode failed: {err}"))?;
-    let info = payload
-        .get("info")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(info).map_err(|err| format!("vault relocate parse failed: {err}"))
+pub async fn vault_relocate_storage(
+    client: State<'_, GatewayClient>,
+    inflight: State<'_, InflightRequests>,
+    path: String,
+    move_existing: Option<bool>,
+    request_id: Option<String>,
+) -> Result<VaultStorageInfo, CommandError> {
+    let relocate = client.vault_relocate_storage(&path, move_existing.unwrap_or(true), request_id.as_deref());
+    inflight
+        .run(request_id.as_deref(), relocate)
+        .await?
+        .ok_or_else(|| CommandError::cancelled("vault relocation"))?
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn run_agent_quick_action(agent_id: String, action: String) -> Result<AgentActionResult, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!(
-            "{}/agents/{}/action",
-            gateway_base_url(),
-            urlencoding::encode(&agent_id)
-        ))
-        .json(&serde_json::json!({ "action": action }))
-        .send()
-        .await
-        .map_err(|err| format!("agent quick action request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn run_agent_quick_action(
+    client: State<'_, GatewayClient>,
+    agent_id: String,
+    action: String,
+) -> Result<AgentActionResult, CommandError> {
+    client
+        .run_agent_quick_action(&agent_id, &action)
         .await
-        .map_err(|err| format!("agent quick action decode failed: {err}"))?;
-    let result = payload
-        .get("result")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(result).map_err(|err| format!("agent quick action parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_agent_logs(agent_id: String, limit: Option<u32>) -> Result<Vec<AuditLogEntry>, String> {
+pub async fn get_agent_logs(
+    client: State<'_, GatewayClient>,
+    agent_id: String,
+    limit: Option<u32>,
+) -> Result<Vec<AuditLogEntry>, CommandError> {
     let cap = limit.unwrap_or(40);
-    let response = gateway_get(format!(
-        "{}/agents/{}/logs?limit={}",
-        gateway_base_url(),
-        urlencoding::encode(&agent_id),
-        cap
-    ))
-    .await
-    .map_err(|err| format!("agent logs request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+    client
+        .get_agent_logs(&agent_id, cap)
         .await
-        .map_err(|err| format!("agent logs decode failed: {err}"))?;
-    let logs = payload
-        .get("logs")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(logs).map_err(|err| format!("agent logs parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn test_agent_connection(agent_id: String, api_key: Option<String>) -> Result<AgentConnectionTestResult, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!(
-            "{}/agents/{}/test-connection",
-            gateway_base_url(),
-            urlencoding::encode(&agent_id)
-        ))
-        .json(&serde_json::json!({
-            "api_key": api_key
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("agent connection test request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn test_agent_connection(
+    client: State<'_, GatewayClient>,
+    agent_id: String,
+    api_key: Option<String>,
+) -> Result<AgentConnectionTestResult, CommandError> {
+    client
+        .test_agent_connection(&agent_id, api_key.as_deref())
         .await
-        .map_err(|err| format!("agent connection test decode failed: {err}"))?;
-    let result = payload
-        .get("result")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(result).map_err(|err| format!("agent connection test parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_integrations(query: Option<String>, category: Option<String>) -> Result<IntegrationsListResult, String> {
+pub async fn get_integrations(
+    client: State<'_, GatewayClient>,
+    query: Option<String>,
+    category: Option<String>,
+) -> Result<IntegrationsListResult, CommandError> {
     let resolved_query = query.unwrap_or_default();
     let resolved_category = category.unwrap_or_else(|| "All Categories".to_string());
-    let url = format!(
-        "{}/integrations/all?query={}&category={}",
-        gateway_base_url(),
-        urlencoding::encode(&resolved_query),
-        urlencoding::encode(&resolved_category)
-    );
-    let response = gateway_get(url)
-        .await
-        .map_err(|err| format!("integrations request failed: {err}"))?;
-    response
-        .json::<IntegrationsListResult>()
+    client
+        .get_integrations(&resolved_query, &resolved_category)
         .await
-        .map_err(|err| format!("integrations decode failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_integration_status(slug: String) -> Result<serde_json::Value, String> {
-    let response = gateway_get(format!(
-        "{}/integrations/{}/status",
-        gateway_base_url(),
-        urlencoding::encode(&slug)
-    ))
-    .await
-    .map_err(|err| format!("integration status request failed: {err}"))?;
-    response
-        .json::<serde_json::Value>()
+pub async fn get_integration_status(
+    client: State<'_, GatewayClient>,
+    slug: String,
+) -> Result<serde_json::Value, CommandError> {
+    client
+        .get_integration_status(&slug)
         .await
-        .map_err(|err| format!("integration status decode failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn connect_integration(
+    client: State<'_, GatewayClient>,
     slug: String,
     target_agent_ids: Option<Vec<String>>,
     config: Option<serde_json::Value>,
-) -> Result<IntegrationEntry, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!(
-            "{}/integrations/{}/connect",
-            gateway_base_url(),
-            urlencoding::encode(&slug)
-        ))
-        .json(&serde_json::json!({
-            "target_agent_ids": target_agent_ids.unwrap_or_default(),
-            "config": config.unwrap_or_else(|| serde_json::json!({}))
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("connect integration request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+) -> Result<IntegrationEntry, CommandError> {
+    let resolved_targets = target_agent_ids.unwrap_or_default();
+    let resolved_config = config.unwrap_or_else(|| serde_json::json!({}));
+    client
+        .connect_integration(&slug, &resolved_targets, &resolved_config)
         .await
-        .map_err(|err| format!("connect integration decode failed: {err}"))?;
-    let integration = payload
-        .get("integration")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(integration).map_err(|err| format!("connect integration parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_connected_model_providers() -> Result<Vec<ConnectedModelProvider>, String> {
-    let response = gateway_get(format!("{}/integrations/model-providers", gateway_base_url()))
-        .await
-        .map_err(|err| format!("model providers request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn get_connected_model_providers(
+    client: State<'_, GatewayClient>,
+) -> Result<Vec<ConnectedModelProvider>, CommandError> {
+    client
+        .get_connected_model_providers()
         .await
-        .map_err(|err| format!("model providers decode failed: {err}"))?;
-    let providers = payload
-        .get("providers")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(providers).map_err(|err| format!("model providers parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn mcp_list_servers(query: Option<String>) -> Result<Vec<McpServerEntry>, String> {
-    let url = format!(
-        "{}/mcp/servers?query={}",
-        gateway_base_url(),
-        urlencoding::encode(&query.unwrap_or_default())
-    );
-    let response = gateway_get(url)
-        .await
-        .map_err(|err| format!("mcp servers request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn mcp_list_servers(
+    client: State<'_, GatewayClient>,
+    query: Option<String>,
+) -> Result<Vec<McpServerEntry>, CommandError> {
+    client
+        .mcp_list_servers(&query.unwrap_or_default())
         .await
-        .map_err(|err| format!("mcp servers decode failed: {err}"))?;
-    let servers = payload
-        .get("servers")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(servers).map_err(|err| format!("mcp servers parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn mcp_register_server(
+    client: State<'_, GatewayClient>,
     url: String,
     name: Option<String>,
     capabilities: Option<Vec<String>>,
-) -> Result<McpServerEntry, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/mcp/servers/register", gateway_base_url()))
-        .json(&serde_json::json!({
-            "url": url,
-            "name": name,
-            "capabilities": capabilities.unwrap_or_default()
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("mcp register request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+) -> Result<McpServerEntry, CommandError> {
+    client
+        .mcp_register_server(&url, name.as_deref(), &capabilities.unwrap_or_default())
         .await
-        .map_err(|err| format!("mcp register decode failed: {err}"))?;
-    let server = payload
-        .get("server")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(server).map_err(|err| format!("mcp register parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn mcp_connect_server(server_id: String, scopes: Option<Vec<String>>) -> Result<McpServerEntry, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!(
-            "{}/mcp/servers/{}/connect",
-            gateway_base_url(),
-            urlencoding::encode(&server_id)
-        ))
-        .json(&serde_json::json!({
-            "scopes": scopes.unwrap_or_default()
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("mcp connect request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn mcp_connect_server(
+    client: State<'_, GatewayClient>,
+    server_id: String,
+    scopes: Option<Vec<String>>,
+) -> Result<McpServerEntry, CommandError> {
+    client
+        .mcp_connect_server(&server_id, &scopes.unwrap_or_default())
         .await
-        .map_err(|err| format!("mcp connect decode failed: {err}"))?;
-    let server = payload
-        .get("server")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(server).map_err(|err| format!("mcp connect parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn mcp_disconnect_server(server_id: String) -> Result<McpServerEntry, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!(
-            "{}/mcp/servers/{}/disconnect",
-            gateway_base_url(),
-            urlencoding::encode(&server_id)
-        ))
-        .send()
-        .await
-        .map_err(|err| format!("mcp disconnect request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn mcp_disconnect_server(
+    client: State<'_, GatewayClient>,
+    server_id: String,
+) -> Result<McpServerEntry, CommandError> {
+    client
+        .mcp_disconnect_server(&server_id)
         .await
-        .map_err(|err| format!("mcp disconnect decode failed: {err}"))?;
-    let server = payload
-        .get("server")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(server).map_err(|err| format!("mcp disconnect parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn mcp_list_tools(server_id: String) -> Result<Vec<McpToolEntry>, String> {
-    let response = gateway_get(format!(
-        "{}/mcp/servers/{}/tools",
-        gateway_base_url(),
-        urlencoding::encode(&server_id)
-    ))
-    .await
-    .map_err(|err| format!("mcp tools request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn mcp_list_tools(
+    client: State<'_, GatewayClient>,
+    server_id: String,
+) -> Result<Vec<McpToolEntry>, CommandError> {
+    client
+        .mcp_list_tools(&server_id)
         .await
-        .map_err(|err| format!("mcp tools decode failed: {err}"))?;
-    let tools = payload
-        .get("tools")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(tools).map_err(|err| format!("mcp tools parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn mcp_invoke_tool(
+    client: State<'_, GatewayClient>,
+    inflight: State<'_, InflightRequests>,
     server_id: String,
     tool_id: String,
     agent_id: String,
     args: Option<serde_json::Value>,
-) -> Result<McpInvokeResult, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!(
-            "{}/mcp/servers/{}/invoke",
-            gateway_base_url(),
-            urlencoding::encode(&server_id)
-        ))
-        .json(&serde_json::json!({
-            "tool_id": tool_id,
-            "agent_id": agent_id,
-            "args": args.unwrap_or_else(|| serde_json::json!({}))
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("mcp invoke request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("mcp invoke decode failed: {err}"))?;
-    let result = payload
-        .get("result")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(result).map_err(|err| format!("mcp invoke parse failed: {err}"))
+    request_id: Option<String>,
+) -> Result<McpInvokeResult, CommandError> {
+    let resolved_args = args.unwrap_or_else(|| serde_json::json!({}));
+    let invoke = client.mcp_invoke_tool(&server_id, &tool_id, &agent_id, &resolved_args, request_id.as_deref());
+    inflight
+        .run(request_id.as_deref(), invoke)
+        .await?
+        .ok_or_else(|| CommandError::cancelled("mcp tool invocation"))?
+        .map_err(CommandError::from)
+}
+
+/// Cancels a command started with `request_id`: the host drops it and the gateway
+/// is told to abort, which only matters if it has not finished on its side yet.
+/// Returns whether anything was still running under that id.
+#[tauri::command]
+pub async fn cancel_request(
+    client: State<'_, GatewayClient>,
+    inflight: State<'_, InflightRequests>,
+    request_id: String,
+) -> Result<bool, CommandError> {
+    let cancelled = inflight.cancel(&request_id);
+    // Older gateways have no `/requests/cancel`; dropping the request already hung up on them.
+    let gateway_cancelled = client.cancel_request(&request_id).await.unwrap_or(false);
+    Ok(cancelled || gateway_cancelled)
 }
 
 #[tauri::command]
 pub async fn get_live_skills(
+    client: State<'_, GatewayClient>,
     sort: Option<String>,
     non_suspicious: Option<bool>,
     cursor: Option<String>,
-) -> Result<ClawHubLiveSkillsResult, String> {
+) -> Result<ClawHubLiveSkillsResult, CommandError> {
     let resolved_sort = sort.unwrap_or_else(|| "downloads".to_string());
     let resolved_non_suspicious = non_suspicious.unwrap_or(true);
-    let cursor_query = cursor
-        .map(|value| format!("&cursor={}", urlencoding::encode(&value)))
-        .unwrap_or_default();
-    let url = format!(
-        "{}/clawhub/live?sort={}&nonSuspicious={}{}",
-        gateway_base_url(),
-        urlencoding::encode(&resolved_sort),
-        resolved_non_suspicious,
-        cursor_query
-    );
-    let response = gateway_get(url)
-        .await
-        .map_err(|err| format!("live skills request failed: {err}"))?;
-    response
-        .json::<ClawHubLiveSkillsResult>()
+    client
+        .get_live_skills(&resolved_sort, resolved_non_suspicious, cursor.as_deref())
         .await
-        .map_err(|err| format!("live skills decode failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn clawhub_install(slug: String, target_agent: Option<String>) -> Result<ClawHubInstallResult, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/clawhub/install", gateway_base_url()))
-        .json(&serde_json::json!({
-            "slug": slug,
-            "target_agent": target_agent
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("clawhub install request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn clawhub_install(
+    client: State<'_, GatewayClient>,
+    slug: String,
+    target_agent: Option<String>,
+) -> Result<ClawHubInstallResult, CommandError> {
+    client
+        .clawhub_install(&slug, target_agent.as_deref())
         .await
-        .map_err(|err| format!("clawhub install decode failed: {err}"))?;
-    let result = payload
-        .get("result")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(result).map_err(|err| format!("clawhub install parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn clawhub_list_installed() -> Result<Vec<ClawHubSkill>, String> {
-    let response = gateway_get(format!("{}/clawhub/installed", gateway_base_url()))
-        .await
-        .map_err(|err| format!("clawhub installed request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn clawhub_list_installed(client: State<'_, GatewayClient>) -> Result<Vec<ClawHubSkill>, CommandError> {
+    client
+        .clawhub_installed(None)
         .await
-        .map_err(|err| format!("clawhub installed decode failed: {err}"))?;
-    let skills = payload
-        .get("skills")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(skills).map_err(|err| format!("clawhub installed parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_installed_skills(agent_id: Option<String>) -> Result<Vec<ClawHubSkill>, String> {
-    let mut url = format!("{}/clawhub/installed", gateway_base_url());
-    if let Some(agent) = agent_id {
-        url = format!("{}?agentId={}", url, urlencoding::encode(&agent));
-    }
-    let response = gateway_get(url)
-        .await
-        .map_err(|err| format!("installed skills request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn get_installed_skills(
+    client: State<'_, GatewayClient>,
+    agent_id: Option<String>,
+) -> Result<Vec<ClawHubSkill>, CommandError> {
+    client
+        .clawhub_installed(agent_id.as_deref())
         .await
-        .map_err(|err| format!("installed skills decode failed: {err}"))?;
-    let skills = payload
-        .get("skills")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(skills).map_err(|err| format!("installed skills parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn toggle_skill(agent_id: String, slug: String, enabled: bool) -> Result<bool, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/clawhub/toggle", gateway_base_url()))
-        .json(&serde_json::json!({
-            "agent_id": agent_id,
-            "slug": slug,
-            "enabled": enabled
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("toggle skill request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn toggle_skill(
+    client: State<'_, GatewayClient>,
+    agent_id: String,
+    slug: String,
+    enabled: bool,
+) -> Result<bool, CommandError> {
+    client
+        .toggle_skill(&agent_id, &slug, enabled)
         .await
-        .map_err(|err| format!("toggle skill decode failed: {err}"))?;
-    Ok(payload
-        .get("result")
-        .and_then(|value| value.get("success"))
-        .and_then(|value| value.as_bool())
-        .unwrap_or(false))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn permissions_request(
+    client: State<'_, GatewayClient>,
     agent_id: String,
     capabilities: Vec<String>,
     context: Option<serde_json::Value>,
-) -> Result<Vec<PermissionGrant>, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/permissions/request", gateway_base_url()))
-        .json(&serde_json::json!({
-            "agent_id": agent_id,
-            "capabilities": capabilities,
-            "context": context.unwrap_or_else(|| serde_json::json!({}))
-        }))
-        .send()
-        .await
-        .map_err(|err| format!("permissions request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+) -> Result<Vec<PermissionGrant>, CommandError> {
+    let resolved_context = context.unwrap_or_else(|| serde_json::json!({}));
+    client
+        .permissions_request(&agent_id, &capabilities, &resolved_context)
         .await
-        .map_err(|err| format!("permissions request decode failed: {err}"))?;
-    let grants = payload
-        .get("grants")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(grants).map_err(|err| format!("permissions request parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn permissions_approve(grant_id: String) -> Result<PermissionGrant, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/permissions/approve", gateway_base_url()))
-        .json(&serde_json::json!({ "grant_id": grant_id }))
-        .send()
-        .await
-        .map_err(|err| format!("permissions approve failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn permissions_approve(
+    client: State<'_, GatewayClient>,
+    grant_id: String,
+) -> Result<PermissionGrant, CommandError> {
+    client
+        .permissions_approve(&grant_id)
         .await
-        .map_err(|err| format!("permissions approve decode failed: {err}"))?;
-    let grant = payload
-        .get("grant")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(grant).map_err(|err| format!("permissions approve parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn permissions_deny(grant_id: String) -> Result<PermissionGrant, String> {
-    let client = gateway_client();
-    let response = client
-        .post(format!("{}/permissions/deny", gateway_base_url()))
-        .json(&serde_json::json!({ "grant_id": grant_id }))
-        .send()
-        .await
-        .map_err(|err| format!("permissions deny failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn permissions_deny(
+    client: State<'_, GatewayClient>,
+    grant_id: String,
+) -> Result<PermissionGrant, CommandError> {
+    client
+        .permissions_deny(&grant_id)
         .await
-        .map_err(|err| format!("permissions deny decode failed: {err}"))?;
-    let grant = payload
-        .get("grant")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(grant).map_err(|err| format!("permissions deny parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn permissions_pending(agent_id: Option<String>) -> Result<Vec<PermissionGrant>, String> {
-    let mut url = format!("{}/permissions/pending", gateway_base_url());
-    if let Some(agent) = agent_id {
-        url = format!("{}?agentId={}", url, urlencoding::encode(&agent));
-    }
-    let response = gateway_get(url)
-        .await
-        .map_err(|err| format!("permissions pending failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn permissions_pending(
+    client: State<'_, GatewayClient>,
+    agent_id: Option<String>,
+) -> Result<Vec<PermissionGrant>, CommandError> {
+    client
+        .permissions_pending(agent_id.as_deref())
         .await
-        .map_err(|err| format!("permissions pending decode failed: {err}"))?;
-    let pending = payload
-        .get("pending")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(pending).map_err(|err| format!("permissions pending parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn audit_logs(limit: Option<u32>) -> Result<Vec<AuditLogEntry>, String> {
+pub async fn audit_logs(
+    client: State<'_, GatewayClient>,
+    limit: Option<u32>,
+) -> Result<Vec<AuditLogEntry>, CommandError> {
     let cap = limit.unwrap_or(100);
-    let response = gateway_get(format!("{}/audit/logs?limit={}", gateway_base_url(), cap))
-        .await
-        .map_err(|err| format!("audit logs request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("audit logs decode failed: {err}"))?;
-    let logs = payload
-        .get("logs")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(logs).map_err(|err| format!("audit logs parse failed: {err}"))
+    client.audit_logs(cap).await.map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_health_snapshot() -> Result<HealthSnapshot, String> {
-    let response = gateway_get(format!("{}/telemetry/snapshot", gateway_base_url()))
-        .await
-        .map_err(|err| format!("health snapshot request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn get_health_snapshot(client: State<'_, GatewayClient>) -> Result<HealthSnapshot, CommandError> {
+    client
+        .health_snapshot()
         .await
-        .map_err(|err| format!("health snapshot decode failed: {err}"))?;
-    let snapshot = payload
-        .get("snapshot")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(snapshot).map_err(|err| format!("health snapshot parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_health_events(limit: Option<u32>, category: Option<String>) -> Result<Vec<HealthTelemetryEvent>, String> {
+pub async fn get_health_events(
+    client: State<'_, GatewayClient>,
+    host_telemetry: State<'_, HostTelemetry>,
+    limit: Option<u32>,
+    category: Option<String>,
+) -> Result<Vec<HealthTelemetryEvent>, CommandError> {
     let cap = limit.unwrap_or(150);
-    let category_q = category
-        .filter(|value| !value.trim().is_empty())
-        .map(|value| format!("&category={}", urlencoding::encode(&value)))
-        .unwrap_or_default();
-    let response = gateway_get(format!(
-        "{}/telemetry/events?limit={}{}",
-        gateway_base_url(),
-        cap,
-        category_q
-    ))
-    .await
-    .map_err(|err| format!("health events request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
-        .await
-        .map_err(|err| format!("health events decode failed: {err}"))?;
-    let events = payload
-        .get("events")
-        .cloned()
-        .unwrap_or_else(|| serde_json::Value::Array(vec![]));
-    serde_json::from_value(events).map_err(|err| format!("health events parse failed: {err}"))
+    let resolved_category = category.filter(|value| !value.trim().is_empty());
+    let host_events = host_telemetry.recent(cap as usize, resolved_category.as_deref());
+    match client.health_events(cap, resolved_category.as_deref()).await {
+        Ok(gateway_events) => Ok(merge_events(gateway_events, host_events, cap as usize)),
+        // Watchdog restarts matter most while the gateway is down, so still show them.
+        Err(GatewayError::Request { .. }) if !host_events.is_empty() => Ok(host_events),
+        Err(error) => Err(CommandError::from(error)),
+    }
 }
 
 #[tauri::command]
-pub async fn export_health_telemetry(format: Option<String>, limit: Option<u32>) -> Result<TelemetryExportResult, String> {
+pub async fn export_health_telemetry(
+    client: State<'_, GatewayClient>,
+    format: Option<String>,
+    limit: Option<u32>,
+) -> Result<TelemetryExportResult, CommandError> {
     let target_format = format.unwrap_or_else(|| "json".to_string());
     let cap = limit.unwrap_or(300);
-    let response = gateway_get(format!(
-        "{}/telemetry/export?format={}&limit={}",
-        gateway_base_url(),
-        urlencoding::encode(&target_format),
-        cap
-    ))
-    .await
-    .map_err(|err| format!("health export request failed: {err}"))?;
-    response
-        .json::<TelemetryExportResult>()
+    client
+        .export_health_telemetry(&target_format, cap)
         .await
-        .map_err(|err| format!("health export decode failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn get_analytics_snapshot(range: Option<String>) -> Result<AnalyticsSnapshot, String> {
+pub async fn get_analytics_snapshot(
+    client: State<'_, GatewayClient>,
+    range: Option<String>,
+) -> Result<AnalyticsSnapshot, CommandError> {
     let requested = range.unwrap_or_else(|| "30d".to_string());
     let normalized = if requested == "7d" || requested == "30d" || requested == "90d" {
         requested
     } else {
         "30d".to_string()
     };
-    let response = gateway_get(format!(
-        "{}/analytics/snapshot?range={}",
-        gateway_base_url(),
-        urlencoding::encode(&normalized)
-    ))
-    .await
-    .map_err(|err| format!("analytics snapshot request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+    client
+        .analytics_snapshot(&normalized)
         .await
-        .map_err(|err| format!("analytics snapshot decode failed: {err}"))?;
-    let snapshot = payload
-        .get("snapshot")
-        .cloned()
-        .unwrap_or_else(|| serde_json::json!({}));
-    serde_json::from_value(snapshot).map_err(|err| format!("analytics snapshot parse failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn export_analytics_report(range: Option<String>, format: Option<String>) -> Result<AnalyticsExportResult, String> {
+pub async fn export_analytics_report(
+    client: State<'_, GatewayClient>,
+    range: Option<String>,
+    format: Option<String>,
+) -> Result<AnalyticsExportResult, CommandError> {
     let requested_range = range.unwrap_or_else(|| "30d".to_string());
     let normalized_range = if requested_range == "7d" || requested_range == "30d" || requested_range == "90d" {
         requested_range
@@ -2797,38 +2636,19 @@ pub async fn export_analytics_report(range: Option<String>, format: Option<Strin
     } else {
         "json".to_string()
     };
-    let response = gateway_get(format!(
-        "{}/analytics/export?range={}&format={}",
-        gateway_base_url(),
-        urlencoding::encode(&normalized_range),
-        urlencoding::encode(&normalized_format)
-    ))
-    .await
-    .map_err(|err| format!("analytics export request failed: {err}"))?;
-    response
-        .json::<AnalyticsExportResult>()
+    client
+        .export_analytics_report(&normalized_range, &normalized_format)
         .await
-        .map_err(|err| format!("analytics export decode failed: {err}"))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn clawhub_get_skill_details(slug: String) -> Result<Option<ClawHubSkill>, String> {
-    let response = gateway_get(format!(
-        "{}/clawhub/details?slug={}",
-        gateway_base_url(),
-        urlencoding::encode(&slug)
-    ))
-    .await
-    .map_err(|err| format!("clawhub details request failed: {err}"))?;
-    let payload = response
-        .json::<serde_json::Value>()
+pub async fn clawhub_get_skill_details(
+    client: State<'_, GatewayClient>,
+    slug: String,
+) -> Result<Option<ClawHubSkill>, CommandError> {
+    client
+        .clawhub_skill_details(&slug)
         .await
-        .map_err(|err| format!("clawhub details decode failed: {err}"))?;
-    let maybe = payload.get("skill").cloned().unwrap_or(serde_json::Value::Null);
-    if maybe.is_null() {
-        return Ok(None);
-    }
-    let parsed = serde_json::from_value(maybe).map_err(|err| format!("clawhub details parse failed: {err}"))?;
-    Ok(Some(parsed))
+        .map_err(CommandError::from)
 }
-
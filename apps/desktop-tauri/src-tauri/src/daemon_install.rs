@@ -0,0 +1,382 @@
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Environment keys whose values are never echoed back in a drift report.
+const SECRET_KEYS: &[&str] = &["MAJORCLAW_GATEWAY_SESSION_TOKEN"];
+const REDACTED: &str = "<redacted>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallAction {
+    /// Nothing was installed before.
+    Installed,
+    /// The installed definition differed and was replaced.
+    Upgraded,
+    /// The installed definition already matches; nothing was written or reloaded.
+    Unchanged,
+}
+
+/// How a daemon definition (launch agent plist or systemd unit) compares with the
+/// one installed, and, once applied, where the replaced file was backed up.
+#[derive(Debug, Clone, Serialize)]
+pub struct DaemonInstall {
+    pub action: InstallAction,
+    path: String,
+    #[serde(rename = "backupPath")]
+    backup_path: Option<String>,
+    /// Lines only in the installed definition, secrets masked.
+    removed: Vec<String>,
+    /// Lines only in the rendered definition, secrets masked.
+    added: Vec<String>,
+    /// Secret environment keys whose values differ; their lines are identical once masked.
+    #[serde(rename = "changedSecrets")]
+    changed_secrets: Vec<String>,
+    /// Whether this was only a comparison and nothing was written.
+    #[serde(rename = "dryRun")]
+    dry_run: bool,
+    #[serde(skip)]
+    definition_changed: bool,
+    #[serde(skip)]
+    secrets_file: Option<SecretsFile>,
+    #[serde(skip)]
+    before_secrets: Vec<(&'static str, String)>,
+    #[serde(skip)]
+    after_secrets: Vec<(&'static str, String)>,
+}
+
+/// An owner-only file installed alongside the definition that holds its secrets
+/// (the systemd unit's `EnvironmentFile=`).
+#[derive(Debug, Clone)]
+struct SecretsFile {
+    path: PathBuf,
+    contents: String,
+    changed: bool,
+}
+
+impl DaemonInstall {
+    pub fn changed(&self) -> bool {
+        self.action != InstallAction::Unchanged
+    }
+
+    /// Adds a secrets file to the plan. Its contents are never diffed: a change is
+    /// reported by key in `changedSecrets` and upgrades an otherwise unchanged install.
+    pub fn with_secrets_file(mut self, path: &Path, contents: &str) -> Result<Self, String> {
+        let installed = read_installed(path)?;
+        let changed = installed.as_deref() != Some(contents);
+        if changed && self.action == InstallAction::Unchanged {
+            self.action = InstallAction::Upgraded;
+        }
+        // Values found in the file win over ones an older definition carried inline, so a
+        // token that only moved out of the definition is not reported as changed.
+        self.before_secrets.extend(secret_values(installed.as_deref().unwrap_or_default()));
+        self.after_secrets.extend(secret_values(contents));
+        self.changed_secrets = changed_secret_keys(self.action, &self.before_secrets, &self.after_secrets);
+        self.secrets_file = Some(SecretsFile {
+            path: path.to_path_buf(),
+            contents: contents.to_string(),
+            changed,
+        });
+        Ok(self)
+    }
+}
+
+fn read_installed(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(installed) => Ok(Some(installed)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(format!("failed to read {}: {err}", path.display())),
+    }
+}
+
+/// Secret keys whose last value differs between `before` and `after`; only reported
+/// for upgrades, since a fresh install has nothing to compare with.
+fn changed_secret_keys(
+    action: InstallAction,
+    before: &[(&'static str, String)],
+    after: &[(&'static str, String)],
+) -> Vec<String> {
+    if action != InstallAction::Upgraded {
+        return Vec::new();
+    }
+    let value = |values: &[(&str, String)], key: &str| {
+        values.iter().rev().find(|(k, _)| *k == key).map(|(_, v)| v.clone())
+    };
+    SECRET_KEYS
+        .iter()
+        .filter(|key| value(before, key) != value(after, key))
+        .map(|key| key.to_string())
+        .collect()
+}
+
+/// Compares `rendered` with the definition installed at `path` without touching it.
+pub fn plan(path: &Path, rendered: &str) -> Result<DaemonInstall, String> {
+    let installed = read_installed(path)?;
+    let action = match installed.as_deref() {
+        None => InstallAction::Installed,
+        Some(installed) if installed == rendered => InstallAction::Unchanged,
+        Some(_) => InstallAction::Upgraded,
+    };
+    let installed = installed.unwrap_or_default();
+    let (before, after) = (secret_values(&installed), secret_values(rendered));
+    let changed_secrets = changed_secret_keys(action, &before, &after);
+    let (removed, added) = line_diff(&mask(&installed, &before), &mask(rendered, &after));
+    Ok(DaemonInstall {
+        action,
+        path: path.to_string_lossy().to_string(),
+        backup_path: None,
+        removed,
+        added,
+        changed_secrets,
+        dry_run: true,
+        definition_changed: action != InstallAction::Unchanged,
+        secrets_file: None,
+        before_secrets: before,
+        after_secrets: after,
+    })
+}
+
+/// Writes `rendered` to the planned path unless it is unchanged, first copying an
+/// existing definition into `backup_dir` (one backup per file name, replaced on
+/// every upgrade) so a bad upgrade can be rolled back by hand. A changed secrets
+/// file is written first, so the new definition never points at a stale one.
+pub fn apply(mut plan: DaemonInstall, rendered: &str, backup_dir: &Path) -> Result<DaemonInstall, String> {
+    plan.dry_run = false;
+    if let Some(secrets) = plan.secrets_file.as_ref().filter(|secrets| secrets.changed) {
+        if let Some(parent) = secrets.path.parent() {
+            fs::create_dir_all(parent).map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+        }
+        write_private_file(&secrets.path, &secrets.contents)
+            .map_err(|err| format!("failed to write {}: {err}", secrets.path.display()))?;
+    }
+    if !plan.definition_changed {
+        return Ok(plan);
+    }
+    let path = PathBuf::from(&plan.path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+    }
+    if plan.action == InstallAction::Upgraded {
+        fs::create_dir_all(backup_dir).map_err(|err| format!("failed to create {}: {err}", backup_dir.display()))?;
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let backup = backup_dir.join(format!("{name}.bak"));
+        let previous = fs::read_to_string(&path).map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        write_private_file(&backup, &previous)
+            .map_err(|err| format!("failed to back up {} to {}: {err}", path.display(), backup.display()))?;
+        plan.backup_path = Some(backup.to_string_lossy().to_string());
+    }
+    write_private_file(&path, rendered).map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+    Ok(plan)
+}
+
+/// Writes a daemon definition that embeds the session token, readable only by the
+/// owner. A new file is created 0600, so the token is never readable by others, even
+/// briefly.
+pub fn write_private_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        // `mode` only applies on creation; tighten a file an older build left 0644
+        // before the new contents go in.
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents.as_bytes())
+}
+
+/// Values of `SECRET_KEYS` in a definition: `KEY=value` in a systemd unit or
+/// environment file, or the `<string>` after `<key>KEY</key>` in a plist.
+fn secret_values(text: &str) -> Vec<(&'static str, String)> {
+    let mut values = Vec::new();
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        for key in SECRET_KEYS {
+            if line.trim() == format!("<key>{key}</key>") {
+                if let Some(next) = lines.peek() {
+                    let value = next.trim().trim_start_matches("<string>").trim_end_matches("</string>");
+                    values.push((*key, value.to_string()));
+                }
+            } else if let Some((_, rest)) = line.split_once(&format!("{key}=")) {
+                values.push((*key, rest.trim_matches(|c| c == '"' || c == '\'').to_string()));
+            }
+        }
+    }
+    values
+}
+
+fn mask(text: &str, secrets: &[(&'static str, String)]) -> Vec<String> {
+    text.lines()
+        .map(|line| {
+            secrets
+                .iter()
+                .filter(|(_, value)| !value.is_empty())
+                .fold(line.to_string(), |line, (_, value)| line.replace(value.as_str(), REDACTED))
+        })
+        .collect()
+}
+
+/// Lines only in `before` and lines only in `after`, in file order, from a longest
+/// common subsequence. Definitions are a few dozen lines, so the table stays tiny.
+fn line_diff(before: &[String], after: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut common = vec![vec![0usize; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            common[i][j] = if before[i] == after[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() && j < after.len() {
+        if before[i] == after[j] {
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            removed.push(before[i].clone());
+            i += 1;
+        } else {
+            added.push(after[j].clone());
+            j += 1;
+        }
+    }
+    removed.extend(before[i..].iter().cloned());
+    added.extend(after[j..].iter().cloned());
+    (removed, added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIT: &str =
+        "[Service]\nExecStart=\"/opt/node\"\nEnvironment=\"MAJORCLAW_GATEWAY_SESSION_TOKEN=old-token\"\n";
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("major-claw-daemon-install-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn lines(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn line_diff_reports_lines_only_on_each_side_in_order() {
+        let (removed, added) = line_diff(&lines(&["a", "b", "c", "d"]), &lines(&["a", "c", "e", "d", "f"]));
+        assert_eq!(removed, lines(&["b"]));
+        assert_eq!(added, lines(&["e", "f"]));
+        assert_eq!(line_diff(&lines(&["x"]), &lines(&["x"])), (Vec::new(), Vec::new()));
+    }
+
+    #[test]
+    fn secret_values_reads_units_scripts_and_plists() {
+        assert_eq!(secret_values(UNIT), vec![("MAJORCLAW_GATEWAY_SESSION_TOKEN", "old-token".to_string())]);
+        let script = "export MAJORCLAW_GATEWAY_SESSION_TOKEN='abc'\n";
+        assert_eq!(secret_values(script), vec![("MAJORCLAW_GATEWAY_SESSION_TOKEN", "abc".to_string())]);
+        let plist = "<key>MAJORCLAW_GATEWAY_SESSION_TOKEN</key>\n    <string>xyz</string>\n";
+        assert_eq!(secret_values(plist), vec![("MAJORCLAW_GATEWAY_SESSION_TOKEN", "xyz".to_string())]);
+    }
+
+    #[test]
+    fn plan_classifies_installs_and_masks_secrets() {
+        let dir = scratch_dir("plan");
+        let path = dir.join("gateway.service");
+        let fresh = plan(&path, UNIT).unwrap();
+        assert_eq!(fresh.action, InstallAction::Installed);
+        assert!(fresh.changed_secrets.is_empty());
+
+        fs::write(&path, UNIT).unwrap();
+        assert_eq!(plan(&path, UNIT).unwrap().action, InstallAction::Unchanged);
+
+        let rotated = UNIT.replace("old-token", "new-token").replace("/opt/node", "/opt/node22");
+        let upgrade = plan(&path, &rotated).unwrap();
+        assert_eq!(upgrade.action, InstallAction::Upgraded);
+        assert_eq!(upgrade.changed_secrets, lines(&["MAJORCLAW_GATEWAY_SESSION_TOKEN"]));
+        assert_eq!(upgrade.removed, lines(&["ExecStart=\"/opt/node\""]));
+        assert_eq!(upgrade.added, lines(&["ExecStart=\"/opt/node22\""]));
+        let report = serde_json::to_string(&upgrade).unwrap();
+        assert!(!report.contains("old-token") && !report.contains("new-token"));
+    }
+
+    #[test]
+    fn secrets_file_changes_upgrade_an_unchanged_definition() {
+        let dir = scratch_dir("secrets");
+        let (path, env) = (dir.join("gateway.service"), dir.join("gateway.env"));
+        let unit = "[Service]\nEnvironmentFile=gateway.env\n";
+        fs::write(&path, unit).unwrap();
+        fs::write(&env, "MAJORCLAW_GATEWAY_SESSION_TOKEN=\"old\"\n").unwrap();
+
+        let same = plan(&path, unit).unwrap().with_secrets_file(&env, "MAJORCLAW_GATEWAY_SESSION_TOKEN=\"old\"\n");
+        assert_eq!(same.unwrap().action, InstallAction::Unchanged);
+
+        let rotated = plan(&path, unit)
+            .unwrap()
+            .with_secrets_file(&env, "MAJORCLAW_GATEWAY_SESSION_TOKEN=\"new\"\n")
+            .unwrap();
+        assert_eq!(rotated.action, InstallAction::Upgraded);
+        assert_eq!(rotated.changed_secrets, lines(&["MAJORCLAW_GATEWAY_SESSION_TOKEN"]));
+        assert!(rotated.removed.is_empty() && rotated.added.is_empty());
+
+        let applied = apply(rotated, unit, &dir.join("backups")).unwrap();
+        assert_eq!(fs::read_to_string(&env).unwrap(), "MAJORCLAW_GATEWAY_SESSION_TOKEN=\"new\"\n");
+        // Only the secrets changed, so the definition was neither rewritten nor backed up.
+        assert!(applied.backup_path.is_none());
+    }
+
+    #[test]
+    fn a_token_moved_into_the_secrets_file_is_not_reported_as_changed() {
+        let dir = scratch_dir("moved");
+        let (path, env) = (dir.join("gateway.service"), dir.join("gateway.env"));
+        fs::write(&path, UNIT).unwrap();
+        let unit = "[Service]\nExecStart=\"/opt/node\"\nEnvironmentFile=gateway.env\n";
+        let moved = plan(&path, unit)
+            .unwrap()
+            .with_secrets_file(&env, "MAJORCLAW_GATEWAY_SESSION_TOKEN=\"old-token\"\n")
+            .unwrap();
+        assert_eq!(moved.action, InstallAction::Upgraded);
+        assert!(moved.changed_secrets.is_empty());
+    }
+
+    #[test]
+    fn apply_backs_up_the_previous_definition_and_writes_owner_only() {
+        let dir = scratch_dir("apply");
+        let path = dir.join("gateway.service");
+        fs::write(&path, UNIT).unwrap();
+        let rendered = UNIT.replace("old-token", "new-token");
+        let applied = apply(plan(&path, &rendered).unwrap(), &rendered, &dir.join("backups")).unwrap();
+        assert!(!applied.dry_run);
+        assert_eq!(fs::read_to_string(&path).unwrap(), rendered);
+        let backup = PathBuf::from(applied.backup_path.unwrap());
+        assert_eq!(fs::read_to_string(backup).unwrap(), UNIT);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_private_file_tightens_an_existing_file() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = scratch_dir("private").join("token");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        write_private_file(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::host_telemetry::iso8601;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Only the end of a launchd log file is read; launchd never rotates them.
+const MAX_TAIL_BYTES: u64 = 2 * 1024 * 1024;
+/// Application log events scanned on Windows before filtering by provider.
+const MAX_WINDOWS_EVENTS: usize = 5_000;
+
+/// One line of output from the always-on gateway, oldest first in results.
+#[derive(Serialize)]
+pub struct DaemonLogLine {
+    /// UTC, shaped like JavaScript's `toISOString()`. `None` for launchd lines the
+    /// gateway wrote without a timestamp (plain-text startup messages).
+    timestamp: Option<String>,
+    /// `debug`, `info`, `warn` or `error`.
+    severity: String,
+    message: String,
+    /// Where the line was read from: `journald`, a log file name, or `eventlog`.
+    source: String,
+}
+
+fn since_timestamp(since_ms: Option<u64>) -> Option<String> {
+    since_ms.map(|ms| iso8601(UNIX_EPOCH + Duration::from_millis(ms)))
+}
+
+/// `level` and `timestamp` from a gateway JSON log line, if it is one.
+fn gateway_fields(message: &str) -> (Option<String>, Option<String>) {
+    let Ok(value) = serde_json::from_str::<Value>(message) else {
+        return (None, None);
+    };
+    let field = |name: &str| value.get(name).and_then(Value::as_str).map(str::to_string);
+    (field("level").map(|level| normalize_level(&level)), field("timestamp"))
+}
+
+fn normalize_level(level: &str) -> String {
+    match level.to_lowercase().as_str() {
+        "trace" | "debug" | "verbose" => "debug",
+        "warn" | "warning" => "warn",
+        "error" | "fatal" | "critical" => "error",
+        _ => "info",
+    }
+    .to_string()
+}
+
+/// The user unit's journal via `journalctl -o json`.
+pub fn read_journal(unit: &str, lines: usize, since_ms: Option<u64>) -> Result<Vec<DaemonLogLine>, String> {
+    let mut command = Command::new("journalctl");
+    command
+        .args(["--user", "-u", unit, "-o", "json", "--no-pager", "-n"])
+        .arg(lines.to_string());
+    if let Some(ms) = since_ms {
+        command.arg("--since").arg(format!("@{}", ms / 1_000));
+    }
+    let output = command
+        .output()
+        .map_err(|err| format!("failed to run journalctl: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "journalctl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .map(|entry| journal_line(&entry))
+        .collect())
+}
+
+fn journal_line(entry: &Value) -> DaemonLogLine {
+    // Non-UTF-8 messages come back as an array of bytes.
+    let message = match entry.get("MESSAGE") {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(bytes)) => {
+            let bytes: Vec<u8> = bytes.iter().filter_map(|b| b.as_u64()).map(|b| b as u8).collect();
+            String::from_utf8_lossy(&bytes).to_string()
+        }
+        _ => String::new(),
+    };
+    let timestamp = entry
+        .get("__REALTIME_TIMESTAMP")
+        .and_then(Value::as_str)
+        .and_then(|micros| micros.parse::<u64>().ok())
+        .map(|micros| iso8601(UNIX_EPOCH + Duration::from_micros(micros)));
+    let priority = entry
+        .get("PRIORITY")
+        .and_then(Value::as_str)
+        .and_then(|priority| priority.parse::<u8>().ok());
+    let (level, _) = gateway_fields(&message);
+    let severity = level.unwrap_or_else(|| {
+        match priority {
+            Some(0..=3) => "error",
+            Some(4) => "warn",
+            Some(7) => "debug",
+            _ => "info",
+        }
+        .to_string()
+    });
+    DaemonLogLine {
+        timestamp,
+        severity,
+        message,
+        source: "journald".to_string(),
+    }
+}
+
+/// The launch agent's stdout/stderr files, merged by the gateway's own timestamps.
+pub fn read_log_files(files: &[PathBuf], lines: usize, since_ms: Option<u64>) -> Result<Vec<DaemonLogLine>, String> {
+    let since = since_timestamp(since_ms);
+    // (sort key, line): lines without a timestamp sort with the last one before them.
+    let mut merged: Vec<(String, DaemonLogLine)> = Vec::new();
+    for file in files {
+        let Some(text) = tail(file)? else {
+            continue;
+        };
+        let name = file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let stderr = name.contains("stderr");
+        let mut last_seen = String::new();
+        for message in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (level, timestamp) = gateway_fields(message);
+            if let Some(timestamp) = timestamp.as_ref() {
+                last_seen = timestamp.clone();
+            }
+            if since.as_ref().is_some_and(|since| last_seen < *since) {
+                continue;
+            }
+            let severity = level.unwrap_or_else(|| if stderr { "error" } else { "info" }.to_string());
+            merged.push((
+                last_seen.clone(),
+                DaemonLogLine {
+                    timestamp,
+                    severity,
+                    message: message.to_string(),
+                    source: name.clone(),
+                },
+            ));
+        }
+    }
+    merged.sort_by(|left, right| left.0.cmp(&right.0));
+    let skip = merged.len().saturating_sub(lines);
+    Ok(merged.into_iter().skip(skip).map(|(_, line)| line).collect())
+}
+
+/// Up to the last `MAX_TAIL_BYTES` of `path`, starting at a line boundary; `None`
+/// if the file does not exist.
+fn tail(path: &Path) -> Result<Option<String>, String> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(format!("failed to open {}: {err}", path.display())),
+    };
+    let len = file
+        .metadata()
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?
+        .len();
+    let start = len.saturating_sub(MAX_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+    let text = String::from_utf8_lossy(&bytes).to_string();
+    if start == 0 {
+        return Ok(Some(text));
+    }
+    Ok(Some(text.split_once('\n').map(|(_, rest)| rest.to_string()).unwrap_or_default()))
+}
+
+/// Application event log entries whose provider name contains `provider`.
+pub fn read_event_log(provider: &str, lines: usize, since_ms: Option<u64>) -> Result<Vec<DaemonLogLine>, String> {
+    let script = format!(
+        "$start = [DateTimeOffset]::FromUnixTimeMilliseconds({since}).LocalDateTime\n\
+         $filter = @{{ LogName = 'Application'; StartTime = $start }}\n\
+         $rows = @(Get-WinEvent -FilterHashtable $filter -MaxEvents {scan} -ErrorAction SilentlyContinue |\n\
+           Where-Object {{ $_.ProviderName -like '*{provider}*' }} |\n\
+           Select-Object -First {lines} |\n\
+           ForEach-Object {{ [pscustomobject]@{{ at = ([DateTimeOffset]$_.TimeCreated).ToUnixTimeMilliseconds(); \
+         level = [int]$_.Level; message = [string]$_.Message }} }})\n\
+         ConvertTo-Json -InputObject $rows -Compress",
+        since = since_ms.unwrap_or(0),
+        scan = MAX_WINDOWS_EVENTS,
+        provider = provider.replace('\'', "''"),
+        lines = lines,
+    );
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .map_err(|err| format!("failed to run PowerShell: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "reading the Application event log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let rows: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    // Get-WinEvent returns newest first.
+    Ok(rows.iter().rev().map(event_line).collect())
+}
+
+fn event_line(row: &Value) -> DaemonLogLine {
+    let timestamp = row
+        .get("at")
+        .and_then(Value::as_u64)
+        .map(|ms| iso8601(UNIX_EPOCH + Duration::from_millis(ms)));
+    let severity = match row.get("level").and_then(Value::as_u64) {
+        Some(1 | 2) => "error",
+        Some(3) => "warn",
+        Some(5) => "debug",
+        _ => "info",
+    };
+    DaemonLogLine {
+        timestamp,
+        severity: severity.to_string(),
+        message: row.get("message").and_then(Value::as_str).unwrap_or_default().to_string(),
+        source: "eventlog".to_string(),
+    }
+}
@@ -0,0 +1,102 @@
+use crate::gateway_client::GatewayError;
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+pub enum CommandErrorKind {
+    GatewayUnreachable,
+    HttpStatus { code: u16, body: String },
+    Decode,
+    Validation,
+    Platform,
+    LockPoisoned,
+    Cancelled,
+}
+
+/// Error returned by every Tauri command. `code` mirrors the gateway's
+/// `AppErrorCode` names so the web UI can map host and gateway failures the same way.
+#[derive(Debug, Serialize)]
+pub struct CommandError {
+    kind: CommandErrorKind,
+    code: &'static str,
+    message: String,
+    #[serde(rename = "retryHint")]
+    retry_hint: Option<String>,
+}
+
+impl CommandError {
+    fn new(kind: CommandErrorKind, message: impl Into<String>) -> Self {
+        let code = match &kind {
+            CommandErrorKind::GatewayUnreachable => "NetworkError",
+            CommandErrorKind::HttpStatus { code, .. } => match code {
+                400 | 422 => "ValidationError",
+                401 => "AuthError",
+                403 => "PermissionDenied",
+                404 => "NotFound",
+                429 => "RateLimited",
+                _ => "InternalServerError",
+            },
+            CommandErrorKind::Validation => "ValidationError",
+            CommandErrorKind::Cancelled => "Cancelled",
+            CommandErrorKind::Decode | CommandErrorKind::Platform | CommandErrorKind::LockPoisoned => {
+                "InternalServerError"
+            }
+        };
+        let retry_hint = match &kind {
+            CommandErrorKind::GatewayUnreachable => Some("Start the gateway and retry.".to_string()),
+            CommandErrorKind::HttpStatus { code: 429, .. } => Some("Wait a moment and retry.".to_string()),
+            CommandErrorKind::HttpStatus { code, .. } if *code >= 500 => {
+                Some("The gateway reported an internal error; retry shortly.".to_string())
+            }
+            CommandErrorKind::LockPoisoned => Some("Restart Major Claw to recover host state.".to_string()),
+            _ => None,
+        };
+        Self {
+            kind,
+            code,
+            message: message.into(),
+            retry_hint,
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(CommandErrorKind::Validation, message)
+    }
+
+    pub fn platform(message: impl Into<String>) -> Self {
+        Self::new(CommandErrorKind::Platform, message)
+    }
+
+    pub fn cancelled(what: &str) -> Self {
+        Self::new(CommandErrorKind::Cancelled, format!("{what} cancelled"))
+    }
+
+    pub fn lock_poisoned(what: &str) -> Self {
+        Self::new(CommandErrorKind::LockPoisoned, format!("{what} lock poisoned"))
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<GatewayError> for CommandError {
+    fn from(error: GatewayError) -> Self {
+        let message = error.to_string();
+        let kind = match error {
+            GatewayError::Request { .. } => CommandErrorKind::GatewayUnreachable,
+            GatewayError::Status { status, body, .. } => CommandErrorKind::HttpStatus {
+                code: status.as_u16(),
+                body,
+            },
+            GatewayError::Decode { .. } => CommandErrorKind::Decode,
+            GatewayError::Build { .. } | GatewayError::Unavailable { .. } => CommandErrorKind::Platform,
+        };
+        Self::new(kind, message)
+    }
+}
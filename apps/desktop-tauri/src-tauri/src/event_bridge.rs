@@ -0,0 +1,111 @@
+use crate::gateway_client::GatewayClient;
+use crate::sse::SseParser;
+use crate::write_queue;
+use crate::GatewayState;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const RECONNECT_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_MAX: Duration = Duration::from_secs(30);
+/// Emitted with `{ connected, error }` whenever the bridge connects or drops, so
+/// views can refetch whatever they may have missed while disconnected.
+pub const STREAM_STATUS_EVENT: &str = "gateway:stream";
+
+#[derive(Serialize, Clone)]
+struct StreamStatus {
+    connected: bool,
+    error: Option<String>,
+}
+
+/// Tauri event name for a gateway `/events/stream` event, or `None` for
+/// keep-alives and anything the UI doesn't subscribe to.
+fn tauri_event_name(gateway_event: &str) -> Option<&'static str> {
+    match gateway_event {
+        "chat.message" => Some("chat:message"),
+        "permission.pending" => Some("permission:pending"),
+        "agent.heartbeat" => Some("agent:heartbeat"),
+        "health.alert" => Some("health:alert"),
+        _ => None,
+    }
+}
+
+/// Holds one persistent connection to the gateway's `/events/stream` and re-emits
+/// its events as Tauri events, reconnecting with backoff whenever it drops, and
+/// right away when a profile switch points the client at another gateway.
+/// Replaces per-view polling of chat, permissions, heartbeats and health alerts.
+pub async fn run(app: AppHandle) {
+    let mut delay = RECONNECT_MIN;
+    loop {
+        if app.state::<GatewayState>().app_closing.load(Ordering::SeqCst) {
+            break;
+        }
+        let mut epoch = app.state::<GatewayState>().connection_epoch.subscribe();
+        epoch.mark_unchanged();
+        let result = pump(&app, &mut epoch).await;
+        let _ = app.emit(
+            STREAM_STATUS_EVENT,
+            StreamStatus {
+                connected: false,
+                error: result.as_ref().err().cloned(),
+            },
+        );
+        // A connection that delivered events was healthy; start the backoff over.
+        delay = match result {
+            Ok(true) => RECONNECT_MIN,
+            _ => (delay * 2).min(RECONNECT_MAX),
+        };
+        // A switch during the wait cuts it short: the new gateway owes no backoff.
+        if tokio::time::timeout(delay, epoch.changed()).await.is_ok() {
+            delay = RECONNECT_MIN;
+        }
+    }
+}
+
+/// Streams until the connection ends or `epoch` changes. `Ok(true)` means at least
+/// one event arrived.
+async fn pump(app: &AppHandle, epoch: &mut tokio::sync::watch::Receiver<u64>) -> Result<bool, String> {
+    let client = app.state::<GatewayClient>();
+    let mut response = client
+        .open_event_stream("/events/stream", "event stream")
+        .await
+        .map_err(|error| error.to_string())?;
+    let _ = app.emit(
+        STREAM_STATUS_EVENT,
+        StreamStatus {
+            connected: true,
+            error: None,
+        },
+    );
+    // Writes queued while the gateway was unreachable go out as soon as it is back.
+    tauri::async_runtime::spawn(write_queue::replay(app.clone()));
+    let mut parser = SseParser::default();
+    let mut received = false;
+    loop {
+        let next = tokio::select! {
+            next = response.chunk() => next,
+            _ = epoch.changed() => return Ok(received),
+        };
+        let chunk = match next {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => return Ok(received),
+            Err(error) => return Err(format!("event stream interrupted: {error}")),
+        };
+        for event in parser.feed(&chunk) {
+            received = true;
+            let Some(name) = tauri_event_name(&event.event) else {
+                continue;
+            };
+            match serde_json::from_str::<serde_json::Value>(&event.data) {
+                Ok(payload) => {
+                    let _ = app.emit(name, payload);
+                }
+                Err(error) => eprintln!("dropping malformed {} event: {error}", event.event),
+            }
+        }
+        if app.state::<GatewayState>().app_closing.load(Ordering::SeqCst) {
+            return Ok(received);
+        }
+    }
+}
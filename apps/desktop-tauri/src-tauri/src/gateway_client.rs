@@ -0,0 +1,1517 @@
+use crate::models::{
+    AgentActionResult, AgentBudget, AgentConfigPatch, AgentConnectionTestResult, AgentCreatePayload, AgentFullConfig,
+    AgentProfile, AnalyticsExportResult, AnalyticsSnapshot, AuditLogEntry, BudgetSnapshot, ChatDelta, ChatStreamEvent,
+    CheckpointRecord, ClawHubInstallResult, ClawHubLiveSkillsResult, ClawHubSkill, ConnectedModelProvider,
+    GatewayHealth, HealthSnapshot, HealthTelemetryEvent, IntegrationEntry, IntegrationsListResult, McpInvokeResult,
+    McpServerEntry, McpToolEntry, PermissionGrant, SwarmChatMessage, SwarmChatThread, SwarmSummary, TaskCreatePayload,
+    TaskPatch, TaskRecord, TelemetryExportResult, VaultDepositPayload, VaultEntry, VaultEntryPatch, VaultStorageInfo,
+    VaultStorageStats, VaultSummary, VaultVersion,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use crate::gateway_endpoint::GatewayEndpoint;
+use crate::sse::SseParser;
+use std::fmt;
+use std::sync::RwLock;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum GatewayError {
+    Request {
+        context: &'static str,
+        source: reqwest::Error,
+    },
+    Status {
+        context: &'static str,
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    Decode {
+        context: &'static str,
+        message: String,
+    },
+    Build {
+        source: reqwest::Error,
+    },
+    /// No client could be set up at launch; see `GatewayClient::unavailable`.
+    Unavailable {
+        reason: String,
+    },
+}
+
+impl fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GatewayError::Request { context, source } => write!(f, "{context} request failed: {source}"),
+            GatewayError::Status { context, status, body } => match gateway_error_message(body) {
+                Some(message) => write!(f, "{context} failed with status {status}: {message}"),
+                None if body.trim().is_empty() => write!(f, "{context} failed with status {status}"),
+                None => write!(f, "{context} failed with status {status}: {}", body.trim()),
+            },
+            GatewayError::Decode { context, message } => write!(f, "{context} decode failed: {message}"),
+            GatewayError::Build { source } => write!(f, "failed to build gateway http client: {source}"),
+            GatewayError::Unavailable { reason } => write!(f, "gateway client unavailable: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for GatewayError {}
+
+/// Longest error body kept from a non-2xx response; proxies can return whole HTML pages.
+const MAX_ERROR_BODY_BYTES: usize = 4096;
+
+/// Pulls `error.message` out of the gateway's `{ success: false, error: {...} }` body.
+fn gateway_error_message(body: &str) -> Option<String> {
+    let parsed = serde_json::from_str::<Value>(body).ok()?;
+    parsed
+        .get("error")
+        .and_then(|error| error.get("message"))
+        .and_then(|message| message.as_str())
+        .map(|message| message.to_string())
+}
+
+async fn check_status(response: reqwest::Response, context: &'static str) -> Result<reqwest::Response, GatewayError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let mut body = response.text().await.unwrap_or_default();
+    if body.len() > MAX_ERROR_BODY_BYTES {
+        let mut cut = MAX_ERROR_BODY_BYTES;
+        while !body.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        body.truncate(cut);
+    }
+    Err(GatewayError::Status { context, status, body })
+}
+
+/// Backoff for requests that are safe to repeat: GETs, and POSTs that carry an
+/// idempotency key. Sized to ride out a watchdog restart of the gateway.
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    /// No retry starts once this much time has passed since the first attempt.
+    max_elapsed: Duration,
+}
+
+const IDEMPOTENT_RETRY: RetryPolicy = RetryPolicy {
+    max_attempts: 5,
+    base_delay: Duration::from_millis(300),
+    max_delay: Duration::from_secs(3),
+    max_elapsed: Duration::from_secs(20),
+};
+
+impl RetryPolicy {
+    /// Exponential delay for the given retry (1-based) with "equal jitter": half the
+    /// step is fixed and the other half random, so concurrent callers spread out.
+    fn delay(&self, retry: u32) -> Duration {
+        let step = self
+            .base_delay
+            .saturating_mul(1 << retry.saturating_sub(1).min(16))
+            .min(self.max_delay);
+        let half = step / 2;
+        let jitter = getrandom::u32().unwrap_or(0) as f64 / u32::MAX as f64;
+        half + half.mul_f64(jitter)
+    }
+}
+
+/// Failures worth another attempt: the gateway was not listening, the connection
+/// dropped, or a proxy reported the upstream as temporarily gone. Timeouts are not
+/// retried: a hung gateway would otherwise hold the caller for several full timeouts.
+fn is_transient(error: &GatewayError) -> bool {
+    match error {
+        GatewayError::Request { source, .. } => !source.is_timeout() && (source.is_connect() || source.is_request()),
+        GatewayError::Status { status, .. } => matches!(status.as_u16(), 502..=504),
+        GatewayError::Decode { .. } | GatewayError::Build { .. } | GatewayError::Unavailable { .. } => false,
+    }
+}
+
+fn with_request_id(request: reqwest::RequestBuilder, request_id: Option<&str>) -> reqwest::RequestBuilder {
+    match request_id {
+        Some(id) => request.header("x-request-id", id),
+        None => request,
+    }
+}
+
+pub fn idempotency_key() -> String {
+    let mut bytes = [0u8; 16];
+    if getrandom::fill(&mut bytes).is_err() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|value| value.as_nanos())
+            .unwrap_or_default();
+        bytes = nanos.to_le_bytes();
+    }
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn parse_value<T: DeserializeOwned>(context: &'static str, value: Value) -> Result<T, GatewayError> {
+    serde_json::from_value(value).map_err(|err| GatewayError::Decode {
+        context,
+        message: err.to_string(),
+    })
+}
+
+/// Decoded body of a successful gateway response. Endpoints wrap their result in a
+/// named key (`{"agents": [...]}`, `{"task": {...}}`), so callers pick the key they expect.
+struct Envelope {
+    context: &'static str,
+    payload: Value,
+}
+
+impl Envelope {
+    fn take(&mut self, key: &str) -> Option<Value> {
+        self.payload.get_mut(key).map(Value::take)
+    }
+
+    fn required(&mut self, key: &str) -> Result<Value, GatewayError> {
+        self.take(key).ok_or_else(|| GatewayError::Decode {
+            context: self.context,
+            message: format!("response is missing `{key}`"),
+        })
+    }
+
+    fn list<T: DeserializeOwned>(mut self, key: &str) -> Result<Vec<T>, GatewayError> {
+        let value = self.required(key)?;
+        parse_value(self.context, value)
+    }
+
+    fn item<T: DeserializeOwned>(mut self, key: &str) -> Result<T, GatewayError> {
+        let value = self.required(key)?;
+        parse_value(self.context, value)
+    }
+
+    fn optional<T: DeserializeOwned>(mut self, key: &str) -> Result<Option<T>, GatewayError> {
+        match self.take(key) {
+            Some(value) if !value.is_null() => parse_value(self.context, value).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn whole<T: DeserializeOwned>(self) -> Result<T, GatewayError> {
+        parse_value(self.context, self.payload)
+    }
+
+    fn into_value(self) -> Value {
+        self.payload
+    }
+}
+
+/// Time allowed to open a TCP connection to the gateway before giving up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// Default end-to-end budget for a gateway request, including reading the body.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+/// Health probes must answer quickly; a slow `/health` is treated as down.
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+/// Vault relocation copies every blob to the new volume before responding.
+const RELOCATE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Longest silence tolerated on an event stream; the gateway pings every 15 seconds.
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(45);
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(30);
+
+/// TLS material for a gateway on another machine. Both parts are PEM: extra roots
+/// to trust (e.g. a team CA), and a client certificate with its private key for
+/// gateways behind mutual TLS. The platform's default roots stay trusted.
+#[derive(Default, Clone)]
+pub struct GatewayTls {
+    pub ca_cert_pem: Option<Vec<u8>>,
+    pub identity_pem: Option<Vec<u8>>,
+}
+
+impl GatewayTls {
+    fn apply(&self, mut builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, GatewayError> {
+        if let Some(pem) = &self.ca_cert_pem {
+            for certificate in
+                reqwest::Certificate::from_pem_bundle(pem).map_err(|source| GatewayError::Build { source })?
+            {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        if let Some(pem) = &self.identity_pem {
+            builder = builder.identity(reqwest::Identity::from_pem(pem).map_err(|source| GatewayError::Build { source })?);
+        }
+        Ok(builder)
+    }
+}
+
+fn gateway_http_client(tls: &GatewayTls) -> Result<reqwest::Client, GatewayError> {
+    tls.apply(reqwest::Client::builder())?
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .build()
+        .map_err(|source| GatewayError::Build { source })
+}
+
+/// Client for `text/event-stream` responses, which stay open indefinitely: no
+/// overall deadline, only an idle read timeout.
+fn gateway_stream_client(tls: &GatewayTls) -> Result<reqwest::Client, GatewayError> {
+    tls.apply(reqwest::Client::builder())?
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(STREAM_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .build()
+        .map_err(|source| GatewayError::Build { source })
+}
+
+/// Typed client for the gateway HTTP API, one method per endpoint. A single
+/// instance lives in Tauri managed state so every command shares its connection pool.
+pub struct GatewayClient {
+    base_url: RwLock<String>,
+    session_token: RwLock<String>,
+    /// `Err` holds why no client could be set up at launch; every request fails with
+    /// it until `reconfigure` succeeds.
+    clients: RwLock<Result<HttpClients, String>>,
+}
+
+/// Request and event-stream clients, rebuilt together when the TLS setup changes.
+struct HttpClients {
+    http: reqwest::Client,
+    stream: reqwest::Client,
+}
+
+impl HttpClients {
+    fn new(tls: &GatewayTls) -> Result<Self, GatewayError> {
+        Ok(Self {
+            http: gateway_http_client(tls)?,
+            stream: gateway_stream_client(tls)?,
+        })
+    }
+}
+
+impl GatewayClient {
+    pub fn new(
+        base_url: impl Into<String>,
+        session_token: impl Into<String>,
+        tls: &GatewayTls,
+    ) -> Result<Self, GatewayError> {
+        Ok(Self {
+            base_url: RwLock::new(base_url.into()),
+            session_token: RwLock::new(session_token.into()),
+            clients: RwLock::new(Ok(HttpClients::new(tls)?)),
+        })
+    }
+
+    /// Stand-in for a client that could not be set up at launch, so the app still
+    /// starts: every request fails with `reason` until `reconfigure` succeeds.
+    pub fn unavailable(endpoint: &GatewayEndpoint, reason: impl Into<String>) -> Self {
+        Self {
+            base_url: RwLock::new(endpoint.base_url()),
+            session_token: RwLock::new(String::new()),
+            clients: RwLock::new(Err(reason.into())),
+        }
+    }
+
+    /// Client for the gateway spawned by this host at `endpoint`.
+    pub fn local(endpoint: &GatewayEndpoint, session_token: impl Into<String>) -> Result<Self, GatewayError> {
+        Self::new(endpoint.base_url(), session_token, &GatewayTls::default())
+    }
+
+    /// Points every later request at another gateway, e.g. when switching between
+    /// the local gateway and a remote one. Nothing changes if the TLS setup is invalid.
+    pub fn reconfigure(
+        &self,
+        base_url: impl Into<String>,
+        session_token: impl Into<String>,
+        tls: &GatewayTls,
+    ) -> Result<(), GatewayError> {
+        let clients = HttpClients::new(tls)?;
+        *self.clients.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Ok(clients);
+        self.set_base_url(base_url);
+        self.set_session_token(session_token);
+        Ok(())
+    }
+
+    fn http(&self) -> Result<reqwest::Client, GatewayError> {
+        self.with_clients(|clients| clients.http.clone())
+    }
+
+    fn stream_http(&self) -> Result<reqwest::Client, GatewayError> {
+        self.with_clients(|clients| clients.stream.clone())
+    }
+
+    fn with_clients(
+        &self,
+        pick: impl FnOnce(&HttpClients) -> reqwest::Client,
+    ) -> Result<reqwest::Client, GatewayError> {
+        match &*self.clients.read().unwrap_or_else(|poisoned| poisoned.into_inner()) {
+            Ok(clients) => Ok(pick(clients)),
+            Err(reason) => Err(GatewayError::Unavailable { reason: reason.clone() }),
+        }
+    }
+
+    /// The token requests are sent with, which is also the one to start a gateway
+    /// with. A client that is `unavailable` has none, so it reports why instead.
+    pub fn session_token(&self) -> Result<String, GatewayError> {
+        self.http()?;
+        Ok(self.current_session_token())
+    }
+
+    fn current_session_token(&self) -> String {
+        self.session_token
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Sends `session_token` on every later request. Only call this once the
+    /// gateway has been restarted with the same token, or requests will get 401s.
+    pub fn set_session_token(&self, session_token: impl Into<String>) {
+        let mut guard = self.session_token.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *guard = session_token.into();
+    }
+
+    /// Points subsequent requests at a new base URL, e.g. after the gateway
+    /// was respawned on a fallback port. Pooled connections to the old one idle out.
+    pub fn set_base_url(&self, base_url: impl Into<String>) {
+        let mut guard = self.base_url.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *guard = base_url.into();
+    }
+
+    fn url(&self, path: &str) -> String {
+        let base_url = self.base_url.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        format!("{}{}", base_url, path)
+    }
+
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        context: &'static str,
+    ) -> Result<reqwest::Response, GatewayError> {
+        let response = request
+            .header("x-session-token", self.current_session_token())
+            .send()
+            .await
+            .map_err(|source| GatewayError::Request { context, source })?;
+        check_status(response, context).await
+    }
+
+    /// Sends the request built by `build`, rebuilding and resending it on transient
+    /// failures according to `policy`.
+    async fn send_with_retry(
+        &self,
+        build: impl Fn() -> reqwest::RequestBuilder,
+        context: &'static str,
+        policy: RetryPolicy,
+    ) -> Result<reqwest::Response, GatewayError> {
+        let started = std::time::Instant::now();
+        let mut attempt = 1;
+        loop {
+            match self.send(build(), context).await {
+                Err(error)
+                    if attempt < policy.max_attempts
+                        && started.elapsed() < policy.max_elapsed
+                        && is_transient(&error) =>
+                {
+                    tokio::time::sleep(policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn envelope(&self, response: reqwest::Response, context: &'static str) -> Result<Envelope, GatewayError> {
+        let payload = response
+            .json::<Value>()
+            .await
+            .map_err(|err| GatewayError::Decode {
+                context,
+                message: err.to_string(),
+            })?;
+        Ok(Envelope { context, payload })
+    }
+
+    async fn get(&self, path: &str, context: &'static str) -> Result<Envelope, GatewayError> {
+        let (http, url) = (self.http()?, self.url(path));
+        let response = self
+            .send_with_retry(|| http.get(&url), context, IDEMPOTENT_RETRY)
+            .await?;
+        self.envelope(response, context).await
+    }
+
+    async fn post<B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+        context: &'static str,
+    ) -> Result<Envelope, GatewayError> {
+        let response = self
+            .send(self.http()?.post(self.url(path)).json(body), context)
+            .await?;
+        self.envelope(response, context).await
+    }
+
+    /// POST tagged with the caller's `x-request-id`, which the gateway uses to
+    /// abort the request if `cancel_request` reaches it first.
+    async fn post_tracked<B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+        context: &'static str,
+        request_id: Option<&str>,
+    ) -> Result<Envelope, GatewayError> {
+        let response = self
+            .send(with_request_id(self.http()?.post(self.url(path)).json(body), request_id), context)
+            .await?;
+        self.envelope(response, context).await
+    }
+
+    /// POST that creates something. The same idempotency key is sent with every
+    /// attempt so the gateway replays its first response instead of creating a duplicate.
+    async fn post_idempotent<B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+        context: &'static str,
+        key: &str,
+    ) -> Result<Envelope, GatewayError> {
+        let (http, url) = (self.http()?, self.url(path));
+        let response = self
+            .send_with_retry(
+                || http.post(&url).header("idempotency-key", key).json(body),
+                context,
+                IDEMPOTENT_RETRY,
+            )
+            .await?;
+        self.envelope(response, context).await
+    }
+
+    async fn post_empty(&self, path: &str, context: &'static str) -> Result<Envelope, GatewayError> {
+        let response = self.send(self.http()?.post(self.url(path)), context).await?;
+        self.envelope(response, context).await
+    }
+
+    async fn patch<B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+        context: &'static str,
+    ) -> Result<Envelope, GatewayError> {
+        let response = self
+            .send(self.http()?.patch(self.url(path)).json(body), context)
+            .await?;
+        self.envelope(response, context).await
+    }
+
+    async fn delete(&self, path: &str, context: &'static str) -> Result<(), GatewayError> {
+        self.send(self.http()?.delete(self.url(path)), context).await?;
+        Ok(())
+    }
+
+    /// Opens a `text/event-stream` endpoint; read it with `Response::chunk` and `SseParser`.
+    pub async fn open_event_stream(&self, path: &str, context: &'static str) -> Result<reqwest::Response, GatewayError> {
+        let request = self
+            .stream_http()?
+            .get(self.url(path))
+            .header("accept", "text/event-stream");
+        self.send(request, context).await
+    }
+
+    pub async fn health(&self) -> Result<GatewayHealth, GatewayError> {
+        let context = "gateway health";
+        let response = self
+            .send(self.http()?.get(self.url("/health")).timeout(HEALTH_TIMEOUT), context)
+            .await?;
+        let payload = self.envelope(response, context).await?.into_value();
+        Ok(GatewayHealth {
+            status: payload
+                .get("status")
+                .and_then(|value| value.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            started_at: payload
+                .get("startedAt")
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string()),
+            instance_count: payload.get("instanceCount").and_then(|value| value.as_u64()),
+            owner: payload
+                .get("owner")
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string()),
+            pid: payload.get("pid").and_then(|value| value.as_u64()),
+        })
+    }
+
+    pub async fn request_shutdown(&self, reason: &str, actor: &str) -> Result<(), GatewayError> {
+        self.send(
+            self.http()?
+                .post(self.url("/system/shutdown"))
+                .json(&serde_json::json!({ "reason": reason, "actor": actor })),
+            "gateway shutdown",
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn red_phone(&self, reason: &str, actor: &str) -> Result<Option<AuditLogEntry>, GatewayError> {
+        self.post(
+            "/system/red-phone",
+            &serde_json::json!({ "reason": reason, "actor": actor }),
+            "red phone",
+        )
+        .await?
+        .optional("log")
+    }
+
+    pub async fn list_agents(&self) -> Result<Vec<AgentProfile>, GatewayError> {
+        self.get("/agents", "agents").await?.list("agents")
+    }
+
+    pub async fn create_agent(&self, payload: &AgentCreatePayload) -> Result<AgentProfile, GatewayError> {
+        self.post(
+            "/agents/create",
+            &serde_json::json!({
+                "name": payload.name,
+                "role": payload.role,
+                "parent_id": payload.parent_id,
+                "model_provider": payload.model_provider,
+                "model_name": payload.model_name,
+                "api_key": payload.api_key,
+                "temperature": payload.temperature,
+                "max_tokens": payload.max_tokens
+            }),
+            "create agent",
+        )
+        .await?
+        .item("agent")
+    }
+
+    pub async fn reorder_agents(&self, order: &[String]) -> Result<Vec<AgentProfile>, GatewayError> {
+        self.post("/agents/reorder", &serde_json::json!({ "order": order }), "reorder agents")
+            .await?
+            .list("agents")
+    }
+
+    pub async fn update_agent_config(
+        &self,
+        agent_id: &str,
+        config: &AgentConfigPatch,
+    ) -> Result<AgentProfile, GatewayError> {
+        self.patch(
+            &format!("/agents/{}/config", urlencoding::encode(agent_id)),
+            &serde_json::json!({
+                "model_provider": config.model_provider,
+                "model_name": config.model_name,
+                "api_key": config.api_key,
+                "temperature": config.temperature,
+                "max_tokens": config.max_tokens,
+                "status": config.status
+            }),
+            "update agent config",
+        )
+        .await?
+        .item("agent")
+    }
+
+    pub async fn get_agent_config(&self, agent_id: &str) -> Result<AgentFullConfig, GatewayError> {
+        self.get(&format!("/agents/{}/full", urlencoding::encode(agent_id)), "agent config")
+            .await?
+            .item("agent")
+    }
+
+    pub async fn run_agent_quick_action(&self, agent_id: &str, action: &str) -> Result<AgentActionResult, GatewayError> {
+        self.post(
+            &format!("/agents/{}/action", urlencoding::encode(agent_id)),
+            &serde_json::json!({ "action": action }),
+            "agent quick action",
+        )
+        .await?
+        .item("result")
+    }
+
+    pub async fn get_agent_logs(&self, agent_id: &str, limit: u32) -> Result<Vec<AuditLogEntry>, GatewayError> {
+        self.get(
+            &format!("/agents/{}/logs?limit={}", urlencoding::encode(agent_id), limit),
+            "agent logs",
+        )
+        .await?
+        .list("logs")
+    }
+
+    pub async fn test_agent_connection(
+        &self,
+        agent_id: &str,
+        api_key: Option<&str>,
+    ) -> Result<AgentConnectionTestResult, GatewayError> {
+        self.post(
+            &format!("/agents/{}/test-connection", urlencoding::encode(agent_id)),
+            &serde_json::json!({ "api_key": api_key }),
+            "agent connection test",
+        )
+        .await?
+        .item("result")
+    }
+
+    pub async fn list_tasks(&self) -> Result<Vec<TaskRecord>, GatewayError> {
+        self.get("/tasks", "tasks").await?.list("tasks")
+    }
+
+    pub async fn create_task(&self, payload: &TaskCreatePayload, key: &str) -> Result<TaskRecord, GatewayError> {
+        self.post_idempotent(
+            "/tasks/create",
+            &serde_json::json!({
+                "title": payload.title,
+                "description": payload.description,
+                "status": payload.status,
+                "priority": payload.priority,
+                "assignee_agent_id": payload.assignee_agent_id,
+                "parent_task_id": payload.parent_task_id
+            }),
+            "create task",
+            key,
+        )
+        .await?
+        .item("task")
+    }
+
+    pub async fn update_task(&self, task_id: &str, patch: &TaskPatch) -> Result<TaskRecord, GatewayError> {
+        let mut patch_payload = serde_json::Map::new();
+        if let Some(title) = &patch.title {
+            patch_payload.insert("title".to_string(), Value::String(title.clone()));
+        }
+        if let Some(description) = &patch.description {
+            patch_payload.insert("description".to_string(), Value::String(description.clone()));
+        }
+        if let Some(status) = &patch.status {
+            patch_payload.insert("status".to_string(), Value::String(status.clone()));
+        }
+        if let Some(priority) = &patch.priority {
+            patch_payload.insert("priority".to_string(), Value::String(priority.clone()));
+        }
+        if let Some(assignee) = &patch.assignee_agent_id {
+            let value = match assignee {
+                Some(value) => Value::String(value.clone()),
+                None => Value::Null,
+            };
+            patch_payload.insert("assignee_agent_id".to_string(), value);
+        }
+        self.patch(
+            &format!("/tasks/{}", urlencoding::encode(task_id)),
+            &Value::Object(patch_payload),
+            "update task",
+        )
+        .await?
+        .item("task")
+    }
+
+    pub async fn delete_task(&self, task_id: &str) -> Result<(), GatewayError> {
+        self.delete(&format!("/tasks/{}", urlencoding::encode(task_id)), "delete task")
+            .await
+    }
+
+    pub async fn chat_threads(&self) -> Result<Vec<SwarmChatThread>, GatewayError> {
+        self.get("/chat/threads", "chat threads").await?.list("threads")
+    }
+
+    pub async fn chat_summary(&self) -> Result<SwarmSummary, GatewayError> {
+        self.get("/chat/summary", "chat summary").await?.item("summary")
+    }
+
+    pub async fn chat_messages(&self, thread_id: &str) -> Result<Vec<SwarmChatMessage>, GatewayError> {
+        self.get(
+            &format!("/chat/messages?threadId={}", urlencoding::encode(thread_id)),
+            "chat messages",
+        )
+        .await?
+        .list("messages")
+    }
+
+    pub async fn chat_send(
+        &self,
+        thread_id: &str,
+        content: &str,
+        user_id: &str,
+    ) -> Result<Vec<SwarmChatMessage>, GatewayError> {
+        self.post(
+            "/chat/send",
+            &serde_json::json!({
+                "thread_id": thread_id,
+                "content": content,
+                "user_id": user_id
+            }),
+            "chat send",
+        )
+        .await?
+        .list("emitted")
+    }
+
+    /// Asks the gateway to abort the request sent with this `x-request-id`. Returns
+    /// whether the gateway still had it in flight.
+    pub async fn cancel_request(&self, request_id: &str) -> Result<bool, GatewayError> {
+        self.post(
+            "/requests/cancel",
+            &serde_json::json!({ "request_id": request_id }),
+            "cancel request",
+        )
+        .await?
+        .item("cancelled")
+    }
+
+    /// `/chat/send` as a `text/event-stream`, handing each delta and finished message
+    /// to `on_event` as it arrives. A gateway that answers with plain JSON instead
+    /// still works; its messages are passed on whole. A stream that ends before its
+    /// `done` event was cut off, and fails.
+    pub async fn chat_send_stream(
+        &self,
+        thread_id: &str,
+        content: &str,
+        user_id: &str,
+        request_id: Option<&str>,
+        mut on_event: impl FnMut(ChatStreamEvent),
+    ) -> Result<(), GatewayError> {
+        let context = "chat send stream";
+        let request = self
+            .stream_http()?
+            .post(self.url("/chat/send"))
+            .header("accept", "text/event-stream")
+            .json(&serde_json::json!({
+                "thread_id": thread_id,
+                "content": content,
+                "user_id": user_id
+            }));
+        let mut response = self.send(with_request_id(request, request_id), context).await?;
+        let is_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        if !is_stream {
+            let emitted: Vec<SwarmChatMessage> = self.envelope(response, context).await?.list("emitted")?;
+            for message in emitted {
+                on_event(ChatStreamEvent::Message { message });
+            }
+            return Ok(());
+        }
+
+        let mut parser = SseParser::default();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|source| GatewayError::Request { context, source })?
+        {
+            for event in parser.feed(&chunk) {
+                match event.event.as_str() {
+                    "delta" => on_event(ChatStreamEvent::Delta {
+                        delta: serde_json::from_str::<ChatDelta>(&event.data).map_err(|err| GatewayError::Decode {
+                            context,
+                            message: err.to_string(),
+                        })?,
+                    }),
+                    "message" => on_event(ChatStreamEvent::Message {
+                        message: serde_json::from_str(&event.data).map_err(|err| GatewayError::Decode {
+                            context,
+                            message: err.to_string(),
+                        })?,
+                    }),
+                    "done" => return Ok(()),
+                    _ => {}
+                }
+            }
+        }
+        Err(GatewayError::Decode {
+            context,
+            message: "stream ended before done".to_string(),
+        })
+    }
+
+    pub async fn chat_quick_action(&self, thread_id: &str, action: &str) -> Result<Vec<SwarmChatMessage>, GatewayError> {
+        self.post(
+            "/chat/quick",
+            &serde_json::json!({
+                "thread_id": thread_id,
+                "action": action
+            }),
+            "chat quick",
+        )
+        .await?
+        .list("emitted")
+    }
+
+    pub async fn chat_checkpoints(&self, swarm_id: &str, limit: u32) -> Result<Vec<CheckpointRecord>, GatewayError> {
+        self.get(
+            &format!(
+                "/chat/checkpoints?swarmId={}&limit={}",
+                urlencoding::encode(swarm_id),
+                limit
+            ),
+            "chat checkpoints",
+        )
+        .await?
+        .list("checkpoints")
+    }
+
+    pub async fn chat_rewind(
+        &self,
+        thread_id: &str,
+        checkpoint_id: &str,
+        edit_prompt: Option<&str>,
+        request_id: Option<&str>,
+    ) -> Result<Vec<SwarmChatMessage>, GatewayError> {
+        self.post_tracked(
+            "/chat/rewind",
+            &serde_json::json!({
+                "thread_id": thread_id,
+                "checkpoint_id": checkpoint_id,
+                "edit_prompt": edit_prompt
+            }),
+            "chat rewind",
+            request_id,
+        )
+        .await?
+        .list("emitted")
+    }
+
+    pub async fn get_budgets(&self) -> Result<BudgetSnapshot, GatewayError> {
+        self.get("/budgets", "budgets").await?.whole()
+    }
+
+    pub async fn update_budget(
+        &self,
+        agent_id: &str,
+        token_limit: u64,
+        cost_limit_usd: f64,
+        hard_kill: bool,
+    ) -> Result<AgentBudget, GatewayError> {
+        self.patch(
+            &format!("/budgets/{}", urlencoding::encode(agent_id)),
+            &serde_json::json!({
+                "token_limit": token_limit,
+                "cost_limit_usd": cost_limit_usd,
+                "hard_kill": hard_kill
+            }),
+            "update budget",
+        )
+        .await?
+        .item("budget")
+    }
+
+    pub async fn vault_summary(&self) -> Result<VaultSummary, GatewayError> {
+        self.get("/vault/summary", "vault summary").await?.item("summary")
+    }
+
+    pub async fn vault_capacity(&self) -> Result<VaultStorageStats, GatewayError> {
+        self.get("/vault/capacity", "vault capacity").await?.item("stats")
+    }
+
+    pub async fn vault_recent(&self, limit: u32) -> Result<Vec<VaultEntry>, GatewayError> {
+        self.get(&format!("/vault/recent?limit={limit}"), "vault recent")
+            .await?
+            .list("items")
+    }
+
+    pub async fn vault_search(&self, query: &str, limit: u32) -> Result<Vec<VaultEntry>, GatewayError> {
+        self.get(
+            &format!("/vault/search?query={}&limit={}", urlencoding::encode(query), limit),
+            "vault search",
+        )
+        .await?
+        .list("items")
+    }
+
+    pub async fn vault_deposit(&self, payload: &VaultDepositPayload, key: &str) -> Result<VaultEntry, GatewayError> {
+        self.post_idempotent("/vault/deposit", payload, "vault deposit", key)
+            .await?
+            .item("entry")
+    }
+
+    pub async fn vault_prune(&self, max_importance: u64) -> Result<Value, GatewayError> {
+        self.post(
+            "/vault/prune",
+            &serde_json::json!({ "max_importance": max_importance }),
+            "vault prune",
+        )
+        .await
+        .map(Envelope::into_value)
+    }
+
+    pub async fn vault_list_versions(&self, entry_id: &str) -> Result<Vec<VaultVersion>, GatewayError> {
+        self.get(
+            &format!("/vault/entries/{}/versions", urlencoding::encode(entry_id)),
+            "vault versions",
+        )
+        .await?
+        .list("versions")
+    }
+
+    pub async fn vault_update_entry(&self, entry_id: &str, patch: &VaultEntryPatch) -> Result<VaultEntry, GatewayError> {
+        self.patch(
+            &format!("/vault/entries/{}", urlencoding::encode(entry_id)),
+            patch,
+            "vault update entry",
+        )
+        .await?
+        .item("entry")
+    }
+
+    pub async fn vault_create_version(
+        &self,
+        entry_id: &str,
+        markdown_summary: Option<&str>,
+        blob_path: Option<&str>,
+        diff: Option<&str>,
+        importance_score: Option<u64>,
+        tags: Option<&[String]>,
+    ) -> Result<VaultVersion, GatewayError> {
+        self.post(
+            &format!("/vault/entries/{}/versions", urlencoding::encode(entry_id)),
+            &serde_json::json!({
+                "markdown_summary": markdown_summary,
+                "blob_path": blob_path,
+                "diff": diff,
+                "importance_score": importance_score,
+                "tags": tags
+            }),
+            "vault create version",
+        )
+        .await?
+        .item("version")
+    }
+
+    pub async fn vault_storage_info(&self) -> Result<VaultStorageInfo, GatewayError> {
+        self.get("/vault/storage/info", "vault storage info").await?.item("info")
+    }
+
+    pub async fn vault_relocate_storage(
+        &self,
+        path: &str,
+        move_existing: bool,
+        request_id: Option<&str>,
+    ) -> Result<VaultStorageInfo, GatewayError> {
+        let context = "vault relocate";
+        let request = self
+            .http()?
+            .post(self.url("/vault/storage/relocate"))
+            .timeout(RELOCATE_TIMEOUT)
+            .json(&serde_json::json!({
+                "path": path,
+                "move_existing": move_existing
+            }));
+        let request = with_request_id(request, request_id);
+        let response = self.send(request, context).await?;
+        self.envelope(response, context).await?.item("info")
+    }
+
+    pub async fn get_integrations(&self, query: &str, category: &str) -> Result<IntegrationsListResult, GatewayError> {
+        self.get(
+            &format!(
+                "/integrations/all?query={}&category={}",
+                urlencoding::encode(query),
+                urlencoding::encode(category)
+            ),
+            "integrations",
+        )
+        .await?
+        .whole()
+    }
+
+    pub async fn get_integration_status(&self, slug: &str) -> Result<Value, GatewayError> {
+        self.get(
+            &format!("/integrations/{}/status", urlencoding::encode(slug)),
+            "integration status",
+        )
+        .await
+        .map(Envelope::into_value)
+    }
+
+    pub async fn connect_integration(
+        &self,
+        slug: &str,
+        target_agent_ids: &[String],
+        config: &Value,
+    ) -> Result<IntegrationEntry, GatewayError> {
+        self.post(
+            &format!("/integrations/{}/connect", urlencoding::encode(slug)),
+            &serde_json::json!({
+                "target_agent_ids": target_agent_ids,
+                "config": config
+            }),
+            "connect integration",
+        )
+        .await?
+        .item("integration")
+    }
+
+    pub async fn get_connected_model_providers(&self) -> Result<Vec<ConnectedModelProvider>, GatewayError> {
+        self.get("/integrations/model-providers", "model providers")
+            .await?
+            .list("providers")
+    }
+
+    pub async fn mcp_list_servers(&self, query: &str) -> Result<Vec<McpServerEntry>, GatewayError> {
+        self.get(&format!("/mcp/servers?query={}", urlencoding::encode(query)), "mcp servers")
+            .await?
+            .list("servers")
+    }
+
+    pub async fn mcp_register_server(
+        &self,
+        url: &str,
+        name: Option<&str>,
+        capabilities: &[String],
+    ) -> Result<McpServerEntry, GatewayError> {
+        self.post(
+            "/mcp/servers/register",
+            &serde_json::json!({
+                "url": url,
+                "name": name,
+                "capabilities": capabilities
+            }),
+            "mcp register",
+        )
+        .await?
+        .item("server")
+    }
+
+    pub async fn mcp_connect_server(&self, server_id: &str, scopes: &[String]) -> Result<McpServerEntry, GatewayError> {
+        self.post(
+            &format!("/mcp/servers/{}/connect", urlencoding::encode(server_id)),
+            &serde_json::json!({ "scopes": scopes }),
+            "mcp connect",
+        )
+        .await?
+        .item("server")
+    }
+
+    pub async fn mcp_disconnect_server(&self, server_id: &str) -> Result<McpServerEntry, GatewayError> {
+        self.post_empty(
+            &format!("/mcp/servers/{}/disconnect", urlencoding::encode(server_id)),
+            "mcp disconnect",
+        )
+        .await?
+        .item("server")
+    }
+
+    pub async fn mcp_list_tools(&self, server_id: &str) -> Result<Vec<McpToolEntry>, GatewayError> {
+        self.get(
+            &format!("/mcp/servers/{}/tools", urlencoding::encode(server_id)),
+            "mcp tools",
+        )
+        .await?
+        .list("tools")
+    }
+
+    pub async fn mcp_invoke_tool(
+        &self,
+        server_id: &str,
+        tool_id: &str,
+        agent_id: &str,
+        args: &Value,
+        request_id: Option<&str>,
+    ) -> Result<McpInvokeResult, GatewayError> {
+        self.post_tracked(
+            &format!("/mcp/servers/{}/invoke", urlencoding::encode(server_id)),
+            &serde_json::json!({
+                "tool_id": tool_id,
+                "agent_id": agent_id,
+                "args": args
+            }),
+            "mcp invoke",
+            request_id,
+        )
+        .await?
+        .item("result")
+    }
+
+    pub async fn clawhub_search(&self, query: &str, sort: &str) -> Result<Vec<ClawHubSkill>, GatewayError> {
+        self.get(
+            &format!(
+                "/clawhub/search?query={}&sort={}",
+                urlencoding::encode(query),
+                urlencoding::encode(sort)
+            ),
+            "clawhub search",
+        )
+        .await?
+        .list("skills")
+    }
+
+    pub async fn get_live_skills(
+        &self,
+        sort: &str,
+        non_suspicious: bool,
+        cursor: Option<&str>,
+    ) -> Result<ClawHubLiveSkillsResult, GatewayError> {
+        let cursor_query = cursor
+            .map(|value| format!("&cursor={}", urlencoding::encode(value)))
+            .unwrap_or_default();
+        self.get(
+            &format!(
+                "/clawhub/live?sort={}&nonSuspicious={}{}",
+                urlencoding::encode(sort),
+                non_suspicious,
+                cursor_query
+            ),
+            "live skills",
+        )
+        .await?
+        .whole()
+    }
+
+    pub async fn clawhub_install(&self, slug: &str, target_agent: Option<&str>) -> Result<ClawHubInstallResult, GatewayError> {
+        self.post(
+            "/clawhub/install",
+            &serde_json::json!({
+                "slug": slug,
+                "target_agent": target_agent
+            }),
+            "clawhub install",
+        )
+        .await?
+        .item("result")
+    }
+
+    pub async fn clawhub_installed(&self, agent_id: Option<&str>) -> Result<Vec<ClawHubSkill>, GatewayError> {
+        let path = match agent_id {
+            Some(agent) => format!("/clawhub/installed?agentId={}", urlencoding::encode(agent)),
+            None => "/clawhub/installed".to_string(),
+        };
+        self.get(&path, "installed skills").await?.list("skills")
+    }
+
+    pub async fn clawhub_skill_details(&self, slug: &str) -> Result<Option<ClawHubSkill>, GatewayError> {
+        self.get(
+            &format!("/clawhub/details?slug={}", urlencoding::encode(slug)),
+            "clawhub details",
+        )
+        .await?
+        .optional("skill")
+    }
+
+    pub async fn toggle_skill(&self, agent_id: &str, slug: &str, enabled: bool) -> Result<bool, GatewayError> {
+        let payload = self
+            .post(
+                "/clawhub/toggle",
+                &serde_json::json!({
+                    "agent_id": agent_id,
+                    "slug": slug,
+                    "enabled": enabled
+                }),
+                "toggle skill",
+            )
+            .await?
+            .into_value();
+        Ok(payload
+            .get("result")
+            .and_then(|value| value.get("success"))
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false))
+    }
+
+    pub async fn permissions_request(
+        &self,
+        agent_id: &str,
+        capabilities: &[String],
+        context: &Value,
+    ) -> Result<Vec<PermissionGrant>, GatewayError> {
+        self.post(
+            "/permissions/request",
+            &serde_json::json!({
+                "agent_id": agent_id,
+                "capabilities": capabilities,
+                "context": context
+            }),
+            "permissions request",
+        )
+        .await?
+        .list("grants")
+    }
+
+    pub async fn permissions_approve(&self, grant_id: &str) -> Result<PermissionGrant, GatewayError> {
+        self.post(
+            "/permissions/approve",
+            &serde_json::json!({ "grant_id": grant_id }),
+            "permissions approve",
+        )
+        .await?
+        .item("grant")
+    }
+
+    pub async fn permissions_deny(&self, grant_id: &str) -> Result<PermissionGrant, GatewayError> {
+        self.post(
+            "/permissions/deny",
+            &serde_json::json!({ "grant_id": grant_id }),
+            "permissions deny",
+        )
+        .await?
+        .item("grant")
+    }
+
+    pub async fn permissions_pending(&self, agent_id: Option<&str>) -> Result<Vec<PermissionGrant>, GatewayError> {
+        let path = match agent_id {
+            Some(agent) => format!("/permissions/pending?agentId={}", urlencoding::encode(agent)),
+            None => "/permissions/pending".to_string(),
+        };
+        self.get(&path, "permissions pending").await?.list("pending")
+    }
+
+    pub async fn audit_logs(&self, limit: u32) -> Result<Vec<AuditLogEntry>, GatewayError> {
+        self.get(&format!("/audit/logs?limit={limit}"), "audit logs")
+            .await?
+            .list("logs")
+    }
+
+    pub async fn health_snapshot(&self) -> Result<HealthSnapshot, GatewayError> {
+        self.get("/telemetry/snapshot", "health snapshot")
+            .await?
+            .item("snapshot")
+    }
+
+    pub async fn health_events(&self, limit: u32, category: Option<&str>) -> Result<Vec<HealthTelemetryEvent>, GatewayError> {
+        let category_q = category
+            .map(|value| format!("&category={}", urlencoding::encode(value)))
+            .unwrap_or_default();
+        self.get(&format!("/telemetry/events?limit={limit}{category_q}"), "health events")
+            .await?
+            .list("events")
+    }
+
+    pub async fn export_health_telemetry(&self, format: &str, limit: u32) -> Result<TelemetryExportResult, GatewayError> {
+        self.get(
+            &format!("/telemetry/export?format={}&limit={}", urlencoding::encode(format), limit),
+            "health export",
+        )
+        .await?
+        .whole()
+    }
+
+    pub async fn analytics_snapshot(&self, range: &str) -> Result<AnalyticsSnapshot, GatewayError> {
+        self.get(
+            &format!("/analytics/snapshot?range={}", urlencoding::encode(range)),
+            "analytics snapshot",
+        )
+        .await?
+        .item("snapshot")
+    }
+
+    pub async fn export_analytics_report(&self, range: &str, format: &str) -> Result<AnalyticsExportResult, GatewayError> {
+        self.get(
+            &format!(
+                "/analytics/export?range={}&format={}",
+                urlencoding::encode(range),
+                urlencoding::encode(format)
+            ),
+            "analytics export",
+        )
+        .await?
+        .whole()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// Serves one canned `(status, body)` response per connection, in order, and
+    /// records each request it receives (head, blank line, body). Bodies that start
+    /// with `event:` are served as `text/event-stream`, the rest as JSON.
+    fn mock_gateway(responses: Vec<(u16, String)>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        std::thread::spawn(move || {
+            for (status, body) in responses {
+                let Ok((stream, _)) = listener.accept() else { return };
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                    request.push_str(&line);
+                }
+                let mut request_body = vec![0; content_length];
+                let _ = reader.read_exact(&mut request_body);
+                request.push_str("\r\n");
+                request.push_str(&String::from_utf8_lossy(&request_body));
+                recorded.lock().unwrap().push(request);
+                let content_type = if body.starts_with("event:") {
+                    "text/event-stream"
+                } else {
+                    "application/json"
+                };
+                let response = format!(
+                    "HTTP/1.1 {status} Mock\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\n\
+                     connection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = reader.get_mut().write_all(response.as_bytes());
+            }
+        });
+        (base_url, requests)
+    }
+
+    fn client(base_url: &str) -> GatewayClient {
+        GatewayClient::new(base_url, "test-token", &GatewayTls::default()).unwrap()
+    }
+
+    fn header_values(requests: &[String], name: &str) -> Vec<String> {
+        requests
+            .iter()
+            .filter_map(|request| {
+                request
+                    .lines()
+                    .take_while(|line| !line.is_empty())
+                    .find_map(|line| line.to_ascii_lowercase().strip_prefix(&format!("{name}:")).map(str::to_string))
+            })
+            .map(|value| value.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn typed_calls_decode_the_named_key_of_the_envelope() {
+        let (base_url, requests) = mock_gateway(vec![(200, r#"{"agents":[],"ignored":true}"#.to_string())]);
+        let agents = tauri::async_runtime::block_on(client(&base_url).list_agents()).unwrap();
+        assert!(agents.is_empty());
+        assert!(requests.lock().unwrap()[0].starts_with("GET /agents "));
+    }
+
+    #[test]
+    fn posts_send_their_payload_as_json() {
+        let (base_url, requests) = mock_gateway(vec![(200, r#"{"log":null}"#.to_string())]);
+        let log = tauri::async_runtime::block_on(client(&base_url).red_phone("drill", "ops")).unwrap();
+        assert!(log.is_none());
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("POST /system/red-phone "));
+        assert!(requests[0].to_ascii_lowercase().contains("content-type: application/json"));
+        let body = requests[0].split("\r\n\r\n").nth(1).unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body, serde_json::json!({ "reason": "drill", "actor": "ops" }));
+    }
+
+    #[test]
+    fn delete_reports_the_status_and_body_of_a_failure() {
+        let (base_url, requests) = mock_gateway(vec![(404, "task not found".to_string())]);
+        let result = tauri::async_runtime::block_on(client(&base_url).delete_task("a/b"));
+        let Err(GatewayError::Status { status, body, .. }) = result else {
+            panic!("expected a status error");
+        };
+        assert_eq!((status.as_u16(), body.as_str()), (404, "task not found"));
+        assert!(requests.lock().unwrap()[0].starts_with("DELETE /tasks/a%2Fb "));
+    }
+
+    #[test]
+    fn failures_keep_a_bounded_error_body() {
+        let body = format!("a{}", "é".repeat(MAX_ERROR_BODY_BYTES));
+        let (base_url, _) = mock_gateway(vec![(500, body)]);
+        let result = tauri::async_runtime::block_on(client(&base_url).post("/tasks", &(), "test post"));
+        let Err(GatewayError::Status { status, body, .. }) = result else {
+            panic!("expected a status error");
+        };
+        assert_eq!(status.as_u16(), 500);
+        // The limit falls inside a two-byte character, so the cut backs off to its start.
+        assert_eq!(body.len(), MAX_ERROR_BODY_BYTES - 1);
+        assert!(body.starts_with('a') && body[1..].chars().all(|c| c == 'é'));
+    }
+
+    #[test]
+    fn errors_show_the_gateway_message_and_missing_keys() {
+        let (base_url, _) = mock_gateway(vec![
+            (409, r#"{"success":false,"error":{"message":"agent name taken"}}"#.to_string()),
+            (200, r#"{"other":[]}"#.to_string()),
+        ]);
+        let conflict = tauri::async_runtime::block_on(client(&base_url).list_agents()).err().unwrap();
+        assert!(conflict.to_string().ends_with("status 409 Conflict: agent name taken"), "{conflict}");
+        let missing = tauri::async_runtime::block_on(client(&base_url).list_agents()).err().unwrap();
+        assert!(matches!(missing, GatewayError::Decode { ref message, .. } if message.contains("`agents`")));
+    }
+
+    #[test]
+    fn retry_delay_grows_with_jitter_up_to_the_cap() {
+        for retry in 1..=8 {
+            let step = IDEMPOTENT_RETRY.base_delay.saturating_mul(1 << (retry - 1)).min(IDEMPOTENT_RETRY.max_delay);
+            let delay = IDEMPOTENT_RETRY.delay(retry);
+            assert!(delay >= step / 2 && delay <= step, "retry {retry}: {delay:?} outside {step:?}");
+        }
+        assert!(IDEMPOTENT_RETRY.delay(u32::MAX) <= IDEMPOTENT_RETRY.max_delay);
+    }
+
+    #[test]
+    fn get_retries_a_transient_status_then_succeeds() {
+        let (base_url, requests) = mock_gateway(vec![
+            (503, "upstream restarting".to_string()),
+            (200, r#"{"agents":[]}"#.to_string()),
+        ]);
+        let agents = tauri::async_runtime::block_on(client(&base_url).list_agents()).unwrap();
+        assert!(agents.is_empty());
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(header_values(&requests, "x-session-token"), vec!["test-token"; 2]);
+    }
+
+    #[test]
+    fn post_idempotent_resends_the_same_key() {
+        let (base_url, requests) = mock_gateway(vec![
+            (502, String::new()),
+            (504, String::new()),
+            (200, r#"{"success":true}"#.to_string()),
+        ]);
+        let body = serde_json::json!({ "title": "task" });
+        tauri::async_runtime::block_on(client(&base_url).post_idempotent("/tasks", &body, "test post", "key-1"))
+            .unwrap();
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|request| request.starts_with("POST /tasks ")));
+        assert_eq!(header_values(&requests, "idempotency-key"), vec!["key-1"; 3]);
+    }
+
+    #[test]
+    fn plain_posts_are_not_retried() {
+        let (base_url, requests) = mock_gateway(vec![(502, String::new()), (200, r#"{"success":true}"#.to_string())]);
+        let result = tauri::async_runtime::block_on(client(&base_url).post("/tasks", &(), "test post"));
+        assert!(matches!(result, Err(GatewayError::Status { status, .. }) if status.as_u16() == 502));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    const DELTA: &str = "event: delta\ndata: {\"messageId\":\"m1\",\"threadId\":\"t1\",\"content\":\"Hi \"}\n\n";
+
+    #[test]
+    fn chat_streams_hand_on_deltas_and_carry_the_request_id() {
+        let (base_url, requests) = mock_gateway(vec![(200, format!("{DELTA}{DELTA}event: done\ndata: {{}}\n\n"))]);
+        let mut events = Vec::new();
+        tauri::async_runtime::block_on(client(&base_url).chat_send_stream("t1", "hello", "user", Some("stream-1"), |event| {
+            events.push(event)
+        }))
+        .unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| matches!(event, ChatStreamEvent::Delta { .. })));
+        assert_eq!(header_values(&requests.lock().unwrap(), "x-request-id"), vec!["stream-1"]);
+    }
+
+    #[test]
+    fn chat_streams_that_end_before_done_fail() {
+        let (base_url, _) = mock_gateway(vec![(200, DELTA.to_string())]);
+        let result =
+            tauri::async_runtime::block_on(client(&base_url).chat_send_stream("t1", "hello", "user", None, |_| {}));
+        assert!(matches!(result, Err(GatewayError::Decode { .. })));
+    }
+
+    #[test]
+    fn an_unavailable_client_reports_why_until_reconfigured() {
+        let endpoint = GatewayEndpoint::default();
+        let client = GatewayClient::unavailable(&endpoint, "no randomness");
+        assert!(matches!(client.session_token(), Err(GatewayError::Unavailable { .. })));
+        let error = tauri::async_runtime::block_on(client.list_agents()).err().unwrap();
+        assert_eq!(error.to_string(), "gateway client unavailable: no randomness");
+
+        let (base_url, _) = mock_gateway(vec![(200, r#"{"agents":[]}"#.to_string())]);
+        client.reconfigure(base_url, "token", &GatewayTls::default()).unwrap();
+        assert_eq!(client.session_token().unwrap(), "token");
+        assert!(tauri::async_runtime::block_on(client.list_agents()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::TcpListener;
+use std::path::Path;
+
+pub const DEFAULT_GATEWAY_HOST: &str = "127.0.0.1";
+pub const DEFAULT_GATEWAY_PORT: u16 = 4455;
+
+/// Where a local gateway profile's gateway listens. The default profile's endpoint
+/// can be overridden from env; the rest comes from `gateway-profiles.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GatewayEndpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for GatewayEndpoint {
+    fn default() -> Self {
+        Self {
+            host: DEFAULT_GATEWAY_HOST.to_string(),
+            port: DEFAULT_GATEWAY_PORT,
+        }
+    }
+}
+
+impl GatewayEndpoint {
+    /// `MAJORCLAW_GATEWAY_HOST` / `MAJORCLAW_GATEWAY_PORT` applied over `self`.
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Ok(host) = std::env::var("MAJORCLAW_GATEWAY_HOST") {
+            if !host.trim().is_empty() {
+                self.host = host.trim().to_string();
+            }
+        }
+        if let Some(port) = std::env::var("MAJORCLAW_GATEWAY_PORT")
+            .ok()
+            .and_then(|value| value.trim().parse::<u16>().ok())
+            .filter(|port| *port != 0)
+        {
+            self.port = port;
+        }
+        self
+    }
+
+    /// Reads the `gateway.json` written before gateway profiles existed.
+    pub fn load(file: &Path) -> Option<Self> {
+        let raw = fs::read_to_string(file).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    pub fn base_url(&self) -> String {
+        // IPv6 literals need brackets in a URL (`http://[::1]:4455`).
+        if self.host.contains(':') && !self.host.starts_with('[') {
+            format!("http://[{}]:{}", self.host, self.port)
+        } else {
+            format!("http://{}:{}", self.host, self.port)
+        }
+    }
+
+    pub fn port_is_free(&self) -> bool {
+        TcpListener::bind((self.host.as_str(), self.port)).is_ok()
+    }
+
+    /// Same host on a port the OS reports as unused. The probe socket is closed
+    /// before returning, so the gateway can bind it right after.
+    pub fn with_free_port(&self) -> Option<Self> {
+        let listener = TcpListener::bind((self.host.as_str(), 0)).ok()?;
+        let port = listener.local_addr().ok()?.port();
+        Some(Self {
+            host: self.host.clone(),
+            port,
+        })
+    }
+}
@@ -0,0 +1,223 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager};
+
+/// Where `tauri.bundle.conf.json` places the deployed gateway package inside the resource dir.
+const BUNDLED_GATEWAY_DIR: &str = "gateway";
+const BUNDLED_GATEWAY_ENTRY: &str = "dist/server.js";
+
+/// How this host starts a gateway process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchKind {
+    /// The sidecar `node` binary next to the app executable, running the compiled
+    /// gateway shipped in the app resources. The only option in release builds.
+    Bundled,
+    /// `pnpm --filter @majorclaw/gateway ...` in a source checkout, for debug builds.
+    Workspace,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayLaunch {
+    pub kind: LaunchKind,
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    #[serde(rename = "workingDir")]
+    pub working_dir: PathBuf,
+    /// Where the bundled runtime is copied for the always-on service when the app
+    /// runs as an AppImage, whose files only exist under `/tmp/.mount_*` while it runs.
+    #[serde(skip)]
+    daemon_runtime: Option<PathBuf>,
+}
+
+impl GatewayLaunch {
+    /// Debug builds run the gateway from the workspace with pnpm (watch mode, no
+    /// bundling step). Release builds only run the bundled artifact, so a packaged
+    /// app never depends on pnpm, PATH or a source checkout.
+    pub fn resolve(app: &AppHandle) -> Result<Self, String> {
+        if cfg!(debug_assertions) {
+            let root = workspace_root().ok_or_else(|| "could not locate the workspace root".to_string())?;
+            return Ok(Self::workspace(&root, "dev:server"));
+        }
+        Self::bundled(app)
+    }
+
+    fn bundled(app: &AppHandle) -> Result<Self, String> {
+        let resources = app
+            .path()
+            .resource_dir()
+            .map_err(|err| format!("failed to resolve the app resource dir: {err}"))?;
+        let working_dir = resources.join(BUNDLED_GATEWAY_DIR);
+        let entry = working_dir.join(BUNDLED_GATEWAY_ENTRY);
+        if !entry.is_file() {
+            return Err(format!("bundled gateway is missing at {}", entry.display()));
+        }
+        let program = sidecar_node_path()?;
+        if !program.is_file() {
+            return Err(format!("bundled node runtime is missing at {}", program.display()));
+        }
+        let daemon_runtime = match std::env::var_os("APPIMAGE") {
+            Some(_) => {
+                let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+                let version = app.package_info().version.to_string();
+                Some(PathBuf::from(home).join(".major-claw").join("runtime").join(version))
+            }
+            None => None,
+        };
+        Ok(Self {
+            kind: LaunchKind::Bundled,
+            program,
+            args: vec![BUNDLED_GATEWAY_ENTRY.to_string()],
+            working_dir,
+            daemon_runtime,
+        })
+    }
+
+    fn workspace(root: &Path, script: &str) -> Self {
+        Self {
+            kind: LaunchKind::Workspace,
+            program: resolve_pnpm_path(root),
+            args: ["--filter", "@majorclaw/gateway", script]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            working_dir: root.to_path_buf(),
+            daemon_runtime: None,
+        }
+    }
+
+    /// The same gateway without watch mode, for launchd/systemd units that should
+    /// keep running the code that was installed. From an AppImage it is the copy
+    /// `stage_for_daemon` makes, since the mounted image goes away with the app.
+    pub fn for_daemon(&self) -> Self {
+        match (self.kind, &self.daemon_runtime) {
+            (LaunchKind::Bundled, Some(runtime)) => Self {
+                program: runtime.join(self.program.file_name().unwrap_or_default()),
+                working_dir: runtime.join(BUNDLED_GATEWAY_DIR),
+                ..self.clone()
+            },
+            (LaunchKind::Bundled, None) => self.clone(),
+            (LaunchKind::Workspace, _) => Self::workspace(&self.working_dir, "start"),
+        }
+    }
+
+    /// Copies the runtime `for_daemon` points at out of the AppImage, once per app
+    /// version. Does nothing otherwise.
+    pub fn stage_for_daemon(&self) -> Result<(), String> {
+        let Some(runtime) = self.daemon_runtime.as_ref().filter(|_| self.kind == LaunchKind::Bundled) else {
+            return Ok(());
+        };
+        if !runtime.is_dir() {
+            // Copied under a temporary name and renamed, so a half-finished copy is never used.
+            let partial = runtime.with_extension("partial");
+            let _ = fs::remove_dir_all(&partial);
+            fs::create_dir_all(&partial).map_err(|err| format!("failed to create {}: {err}", partial.display()))?;
+            let program = partial.join(self.program.file_name().unwrap_or_default());
+            fs::copy(&self.program, &program)
+                .map_err(|err| format!("failed to copy {}: {err}", self.program.display()))?;
+            copy_tree(&self.working_dir, &partial.join(BUNDLED_GATEWAY_DIR))?;
+            fs::rename(&partial, runtime).map_err(|err| format!("failed to move {}: {err}", partial.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Removes runtimes `stage_for_daemon` copied for other app versions. Only call it
+    /// once the service has been switched to this version's copy: until then the old
+    /// one may still be what the installed unit runs.
+    pub fn prune_daemon_runtimes(&self) {
+        let Some(runtime) = self.daemon_runtime.as_ref().filter(|_| self.kind == LaunchKind::Bundled) else {
+            return;
+        };
+        if let Some(parent) = runtime.parent() {
+            for entry in fs::read_dir(parent).into_iter().flatten().flatten() {
+                if entry.path() != *runtime {
+                    let _ = fs::remove_dir_all(entry.path());
+                }
+            }
+        }
+    }
+
+    /// A `Command` with the program, arguments and working dir set; the caller adds
+    /// the environment and stdio.
+    pub fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args).current_dir(&self.working_dir);
+        command
+    }
+}
+
+/// Copies a directory tree, keeping symlinks (pnpm's `node_modules` layout) as links.
+fn copy_tree(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|err| format!("failed to create {}: {err}", to.display()))?;
+    let entries = fs::read_dir(from).map_err(|err| format!("failed to read {}: {err}", from.display()))?;
+    for entry in entries {
+        let entry = entry.map_err(|err| format!("failed to read {}: {err}", from.display()))?;
+        let (source, target) = (entry.path(), to.join(entry.file_name()));
+        let file_type = entry
+            .file_type()
+            .map_err(|err| format!("failed to inspect {}: {err}", source.display()))?;
+        if file_type.is_dir() {
+            copy_tree(&source, &target)?;
+        } else if file_type.is_symlink() {
+            let link = fs::read_link(&source).map_err(|err| format!("failed to read {}: {err}", source.display()))?;
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&link, &target)
+                .map_err(|err| format!("failed to link {}: {err}", target.display()))?;
+            #[cfg(not(unix))]
+            let _ = link;
+        } else {
+            fs::copy(&source, &target).map_err(|err| format!("failed to copy {}: {err}", source.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Tauri installs `externalBin` sidecars next to the main executable, without the
+/// target-triple suffix they are built with.
+fn sidecar_node_path() -> Result<PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|err| format!("failed to locate the app executable: {err}"))?;
+    let dir = exe
+        .parent()
+        .ok_or_else(|| format!("{} has no parent directory", exe.display()))?;
+    Ok(dir.join(if cfg!(windows) { "node.exe" } else { "node" }))
+}
+
+/// The source checkout: `MAJORCLAW_WORKSPACE_ROOT` if set, otherwise (debug builds
+/// only) the checkout this binary was compiled from.
+pub fn workspace_root() -> Option<PathBuf> {
+    if let Ok(explicit_root) = std::env::var("MAJORCLAW_WORKSPACE_ROOT") {
+        if !explicit_root.trim().is_empty() {
+            return Some(PathBuf::from(explicit_root));
+        }
+    }
+    if !cfg!(debug_assertions) {
+        return None;
+    }
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    Some(
+        manifest_dir
+            .parent()
+            .and_then(|p| p.parent())
+            .map(|p| p.to_path_buf())
+            .unwrap_or(manifest_dir),
+    )
+}
+
+fn resolve_pnpm_path(root: &Path) -> PathBuf {
+    let local = root
+        .join("node_modules")
+        .join(".bin")
+        .join(if cfg!(windows) { "pnpm.cmd" } else { "pnpm" });
+    if local.exists() {
+        return local;
+    }
+    for candidate in ["/opt/homebrew/bin/pnpm", "/usr/local/bin/pnpm", "/usr/bin/pnpm"] {
+        let path = PathBuf::from(candidate);
+        if path.exists() {
+            return path;
+        }
+    }
+    PathBuf::from("pnpm")
+}
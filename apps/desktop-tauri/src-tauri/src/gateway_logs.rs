@@ -0,0 +1,173 @@
+use crate::host_telemetry::iso8601;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Lines kept in memory for `gateway_process_logs`.
+const RING_CAPACITY: usize = 2_000;
+const LOG_FILE_NAME: &str = "gateway.log";
+const LOG_FILE_MAX_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept next to the live one (`gateway.log.1` is the newest).
+const LOG_FILE_KEEP: usize = 3;
+pub const GATEWAY_LOG_EVENT: &str = "gateway:log";
+
+#[derive(Serialize, Clone)]
+pub struct GatewayLogLine {
+    seq: u64,
+    stream: &'static str,
+    level: String,
+    message: String,
+    timestamp: String,
+}
+
+fn level_rank(level: &str) -> u8 {
+    match level {
+        "trace" | "debug" => 0,
+        "info" | "log" => 1,
+        "warn" | "warning" => 2,
+        "error" | "fatal" | "critical" => 3,
+        _ => 1,
+    }
+}
+
+/// Level of a gateway output line: the gateway logs JSON with a `level` field;
+/// anything else is `info` on stdout and `error` on stderr.
+fn detect_level(stream: &str, message: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(message)
+        .ok()
+        .and_then(|value| value.get("level").and_then(|level| level.as_str()).map(str::to_lowercase))
+        .unwrap_or_else(|| if stream == "stderr" { "error" } else { "info" }.to_string())
+}
+
+struct RotatingFile {
+    path: PathBuf,
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn new(path: PathBuf) -> Self {
+        let written = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+        Self {
+            path,
+            file: None,
+            written,
+        }
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if self.written + line.len() as u64 + 1 > LOG_FILE_MAX_BYTES {
+            self.rotate();
+        }
+        if self.file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            self.file = OpenOptions::new().create(true).append(true).open(&self.path).ok();
+        }
+        if let Some(file) = self.file.as_mut() {
+            if writeln!(file, "{line}").is_ok() {
+                self.written += line.len() as u64 + 1;
+            }
+        }
+    }
+
+    fn rotate(&mut self) {
+        self.file = None;
+        let rotated = |index: usize| PathBuf::from(format!("{}.{index}", self.path.display()));
+        let _ = fs::remove_file(rotated(LOG_FILE_KEEP));
+        for index in (1..LOG_FILE_KEEP).rev() {
+            let _ = fs::rename(rotated(index), rotated(index + 1));
+        }
+        let _ = fs::rename(&self.path, rotated(1));
+        self.written = 0;
+    }
+}
+
+/// Output of the gateway child process: a bounded in-memory ring, a rotating
+/// `gateway.log` in the app log dir, and a `gateway:log` event per line.
+pub struct GatewayLogs {
+    app: AppHandle,
+    ring: Mutex<VecDeque<GatewayLogLine>>,
+    file: Mutex<RotatingFile>,
+    next_seq: AtomicU64,
+}
+
+impl GatewayLogs {
+    pub fn new(app: AppHandle) -> Self {
+        let dir = app
+            .path()
+            .app_log_dir()
+            .unwrap_or_else(|_| std::env::temp_dir().join("major-claw"));
+        Self {
+            app,
+            ring: Mutex::new(VecDeque::with_capacity(RING_CAPACITY)),
+            file: Mutex::new(RotatingFile::new(dir.join(LOG_FILE_NAME))),
+            next_seq: AtomicU64::new(1),
+        }
+    }
+
+    /// Reads `output` line by line on a background thread until the pipe closes.
+    pub fn capture(self: &Arc<Self>, stream: &'static str, output: impl Read + Send + 'static) {
+        let logs = Arc::clone(self);
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(output);
+            let mut raw = Vec::new();
+            loop {
+                raw.clear();
+                match reader.read_until(b'\n', &mut raw) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        let message = String::from_utf8_lossy(&raw).trim_end().to_string();
+                        if !message.is_empty() {
+                            logs.push(stream, message);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    fn push(&self, stream: &'static str, message: String) {
+        let line = GatewayLogLine {
+            seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            stream,
+            level: detect_level(stream, &message),
+            message,
+            timestamp: iso8601(SystemTime::now()),
+        };
+        self.file
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .write_line(&format!("{} [{}] {} {}", line.timestamp, line.stream, line.level, line.message));
+        {
+            let mut ring = self.ring.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if ring.len() == RING_CAPACITY {
+                ring.pop_front();
+            }
+            ring.push_back(line.clone());
+        }
+        let _ = self.app.emit(GATEWAY_LOG_EVENT, line);
+    }
+
+    /// Most recent `limit` lines at or above `min_level`, oldest first.
+    pub fn recent(&self, limit: usize, min_level: Option<&str>) -> Vec<GatewayLogLine> {
+        let threshold = min_level.map(level_rank).unwrap_or(0);
+        let ring = self.ring.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut lines: Vec<GatewayLogLine> = ring
+            .iter()
+            .rev()
+            .filter(|line| level_rank(&line.level) >= threshold)
+            .take(limit)
+            .cloned()
+            .collect();
+        lines.reverse();
+        lines
+    }
+}
@@ -0,0 +1,187 @@
+use crate::gateway_endpoint::GatewayEndpoint;
+use crate::gateway_remote::RemoteGateway;
+use crate::secret_store::SecretStore;
+use crate::session_token::persist_session_token;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const PROFILES_FILE_NAME: &str = "gateway-profiles.json";
+/// The profile every install starts with: a gateway spawned by this host.
+pub const DEFAULT_PROFILE: &str = "local";
+const PROFILE_NAME_MAX: usize = 40;
+/// Files and secret account from before profiles existed, folded in on first load.
+const LEGACY_ENDPOINT_FILE_NAME: &str = "gateway.json";
+const LEGACY_REMOTE_FILE_NAME: &str = "gateway-remote.json";
+const LEGACY_REMOTE_TOKEN_ACCOUNT: &str = "gateway-remote-token";
+const LEGACY_REMOTE_PROFILE: &str = "remote";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GatewayTarget {
+    /// Spawned and supervised by this host.
+    Local(GatewayEndpoint),
+    /// Runs elsewhere; this host only connects to it.
+    Remote(RemoteGateway),
+}
+
+/// A named gateway connection such as local dev, staging or a team server. Its
+/// session token lives in the secret store under the profile name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayProfile {
+    pub name: String,
+    #[serde(flatten)]
+    pub target: GatewayTarget,
+}
+
+impl Default for GatewayProfile {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+            target: GatewayTarget::Local(GatewayEndpoint::default()),
+        }
+    }
+}
+
+impl GatewayProfile {
+    pub fn validated(&self) -> Result<Self, String> {
+        let name = self.name.trim();
+        if name.is_empty()
+            || name.len() > PROFILE_NAME_MAX
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "profile names must be 1-{PROFILE_NAME_MAX} characters of letters, digits, '-' or '_'"
+            ));
+        }
+        let target = match &self.target {
+            GatewayTarget::Local(endpoint) if endpoint.port == 0 => {
+                return Err("local profiles need a non-zero port".to_string());
+            }
+            GatewayTarget::Local(endpoint) => GatewayTarget::Local(endpoint.clone()),
+            GatewayTarget::Remote(remote) => GatewayTarget::Remote(remote.validated()?),
+        };
+        Ok(Self {
+            name: name.to_string(),
+            target,
+        })
+    }
+
+    pub fn is_remote(&self) -> bool {
+        matches!(self.target, GatewayTarget::Remote(_))
+    }
+}
+
+/// Every saved profile and which one the host is connected to, persisted as
+/// `gateway-profiles.json` in the app config dir.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayProfiles {
+    pub active: String,
+    pub profiles: Vec<GatewayProfile>,
+}
+
+impl Default for GatewayProfiles {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: vec![GatewayProfile::default()],
+        }
+    }
+}
+
+impl GatewayProfiles {
+    /// Reads `file`, or on first run builds the list from the older single-gateway
+    /// settings next to it. The default local profile always exists, and an unknown
+    /// `active` falls back to it.
+    pub fn load(file: Option<&Path>, secrets: &SecretStore) -> Self {
+        let mut profiles = file
+            .and_then(|file| fs::read_to_string(file).ok())
+            .and_then(|raw| serde_json::from_str::<Self>(&raw).ok())
+            .unwrap_or_else(|| Self::migrate(file.and_then(Path::parent), secrets));
+        if profiles.get(DEFAULT_PROFILE).is_none() {
+            profiles.profiles.insert(0, GatewayProfile::default());
+        }
+        if profiles.get(&profiles.active).is_none() {
+            profiles.active = DEFAULT_PROFILE.to_string();
+        }
+        profiles
+    }
+
+    fn migrate(config_dir: Option<&Path>, secrets: &SecretStore) -> Self {
+        let mut profiles = Self::default();
+        let Some(dir) = config_dir else {
+            return profiles;
+        };
+        if let Some(endpoint) = GatewayEndpoint::load(&dir.join(LEGACY_ENDPOINT_FILE_NAME)) {
+            profiles.profiles[0].target = GatewayTarget::Local(endpoint);
+        }
+        let remote = fs::read_to_string(dir.join(LEGACY_REMOTE_FILE_NAME))
+            .ok()
+            .and_then(|raw| serde_json::from_str::<RemoteGateway>(&raw).ok());
+        if let Some(remote) = remote {
+            if let Some(token) = secrets.load(LEGACY_REMOTE_TOKEN_ACCOUNT) {
+                if let Err(error) = persist_session_token(secrets, LEGACY_REMOTE_PROFILE, &token) {
+                    eprintln!("failed to carry over the remote gateway token: {error}");
+                }
+            }
+            profiles.profiles.push(GatewayProfile {
+                name: LEGACY_REMOTE_PROFILE.to_string(),
+                target: GatewayTarget::Remote(remote),
+            });
+            profiles.active = LEGACY_REMOTE_PROFILE.to_string();
+        }
+        profiles
+    }
+
+    pub fn persist(&self, file: &Path) -> Result<(), String> {
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+        }
+        let raw = serde_json::to_string_pretty(self).map_err(|err| err.to_string())?;
+        // Write then rename so a crash mid-write never leaves a torn profile list.
+        let partial = file.with_extension("json.tmp");
+        fs::write(&partial, raw)
+            .and_then(|()| fs::rename(&partial, file))
+            .map_err(|err| format!("failed to write {}: {err}", file.display()))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&GatewayProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// Adds `profile`, or replaces the one with the same name. The default profile
+    /// must stay local so there is always a gateway this host can start itself.
+    pub fn upsert(&mut self, profile: GatewayProfile) -> Result<(), String> {
+        if profile.name == DEFAULT_PROFILE && profile.is_remote() {
+            return Err(format!("the {DEFAULT_PROFILE:?} profile must stay local"));
+        }
+        match self.profiles.iter_mut().find(|existing| existing.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<(), String> {
+        if name == DEFAULT_PROFILE {
+            return Err(format!("the {DEFAULT_PROFILE:?} profile cannot be deleted"));
+        }
+        if name == self.active {
+            return Err("switch to another profile before deleting this one".to_string());
+        }
+        let before = self.profiles.len();
+        self.profiles.retain(|profile| profile.name != name);
+        if self.profiles.len() == before {
+            return Err(format!("no gateway profile named {name:?}"));
+        }
+        Ok(())
+    }
+}
+
+pub fn profiles_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(PROFILES_FILE_NAME))
+}
@@ -0,0 +1,73 @@
+use crate::gateway_client::{GatewayClient, GatewayTls};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+/// A gateway on another machine, shared by several desktops. While a remote
+/// profile is active the host never spawns or supervises a local gateway.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteGateway {
+    #[serde(rename = "baseUrl")]
+    pub base_url: String,
+    /// PEM bundle of extra CA certificates to trust, for gateways behind a private CA.
+    #[serde(rename = "caCertPath", default)]
+    pub ca_cert_path: Option<PathBuf>,
+    /// PEM file holding a client certificate and its private key, for mutual TLS.
+    #[serde(rename = "clientCertPath", default)]
+    pub client_cert_path: Option<PathBuf>,
+}
+
+impl RemoteGateway {
+    /// Checks the URL and returns a copy with it normalized (no trailing slash).
+    /// The session token travels in a header, so anything beyond loopback must use https.
+    pub fn validated(&self) -> Result<Self, String> {
+        let url = reqwest::Url::parse(self.base_url.trim())
+            .map_err(|err| format!("invalid gateway URL {:?}: {err}", self.base_url))?;
+        let host = url.host_str().ok_or_else(|| "gateway URL has no host".to_string())?;
+        let loopback = host == "localhost"
+            || host
+                .trim_matches(|c| c == '[' || c == ']')
+                .parse::<IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback());
+        match url.scheme() {
+            "https" => {}
+            "http" if loopback && self.client_cert_path.is_none() => {}
+            "http" => return Err("remote gateways must use https".to_string()),
+            other => return Err(format!("unsupported gateway URL scheme {other:?}")),
+        }
+        if url.query().is_some() || url.fragment().is_some() {
+            return Err("gateway URL must not have a query or fragment".to_string());
+        }
+        Ok(Self {
+            base_url: url.as_str().trim_end_matches('/').to_string(),
+            ca_cert_path: self.ca_cert_path.clone(),
+            client_cert_path: self.client_cert_path.clone(),
+        })
+    }
+
+    pub fn tls(&self) -> Result<GatewayTls, String> {
+        let read = |path: &PathBuf| fs::read(path).map_err(|err| format!("failed to read {}: {err}", path.display()));
+        Ok(GatewayTls {
+            ca_cert_pem: self.ca_cert_path.as_ref().map(read).transpose()?,
+            identity_pem: self.client_cert_path.as_ref().map(read).transpose()?,
+        })
+    }
+
+    /// Host and port shown in `GatewayStatus`.
+    pub fn host_port(&self) -> (String, u16) {
+        reqwest::Url::parse(&self.base_url)
+            .ok()
+            .map(|url| {
+                (
+                    url.host_str().unwrap_or_default().to_string(),
+                    url.port_or_known_default().unwrap_or_default(),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn client(&self, session_token: &str) -> Result<GatewayClient, String> {
+        GatewayClient::new(&self.base_url, session_token, &self.tls()?).map_err(|err| err.to_string())
+    }
+}
@@ -0,0 +1,85 @@
+use crate::models::HealthTelemetryEvent;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MAX_HOST_EVENTS: usize = 200;
+
+/// Health telemetry produced by the desktop host itself, such as watchdog restarts.
+/// It is kept outside the gateway so it survives the gateway being down;
+/// `get_health_events` merges it with the gateway's own events.
+#[derive(Default)]
+pub struct HostTelemetry {
+    events: Mutex<VecDeque<HealthTelemetryEvent>>,
+}
+
+impl HostTelemetry {
+    pub fn record(&self, severity: &str, source: &str, message: impl Into<String>, metadata: Value) {
+        let event = HealthTelemetryEvent {
+            id: format!("host_{:08x}", getrandom::u32().unwrap_or_default()),
+            category: "lifecycle".to_string(),
+            severity: severity.to_string(),
+            source: source.to_string(),
+            message: message.into(),
+            metadata,
+            created_at: iso8601(SystemTime::now()),
+        };
+        let mut events = self.events.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        events.push_front(event);
+        events.truncate(MAX_HOST_EVENTS);
+    }
+
+    /// Newest first, like the gateway's `/telemetry/events`.
+    pub fn recent(&self, limit: usize, category: Option<&str>) -> Vec<HealthTelemetryEvent> {
+        let events = self.events.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        events
+            .iter()
+            .filter(|event| category.is_none_or(|wanted| event.category == wanted))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Interleaves gateway and host events newest first and keeps at most `limit`.
+pub fn merge_events(
+    mut gateway: Vec<HealthTelemetryEvent>,
+    host: Vec<HealthTelemetryEvent>,
+    limit: usize,
+) -> Vec<HealthTelemetryEvent> {
+    gateway.extend(host);
+    gateway.sort_by(|left, right| right.created_at.cmp(&left.created_at));
+    gateway.truncate(limit);
+    gateway
+}
+
+/// UTC timestamp in the same shape as JavaScript's `toISOString()`, so host and
+/// gateway `createdAt` values sort together as plain strings.
+pub fn iso8601(at: SystemTime) -> String {
+    let elapsed = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = elapsed.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        rem / 3_600,
+        (rem % 3_600) / 60,
+        rem % 60,
+        elapsed.subsec_millis()
+    )
+}
+
+/// Days since 1970-01-01 to a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
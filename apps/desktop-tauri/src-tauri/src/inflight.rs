@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+use crate::error::CommandError;
+
+/// Long-running commands in flight, keyed by the request id the UI passed in, so
+/// `cancel_request` can drop one. Dropping the future also drops its gateway
+/// connection, which the gateway treats as an abort.
+#[derive(Default)]
+pub struct InflightRequests {
+    active: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+impl InflightRequests {
+    /// Runs `future` to completion unless `request_id` is cancelled first, in which
+    /// case it is dropped and `None` is returned. Without an id it cannot be cancelled.
+    /// An id that is already in flight is refused before `future` is polled, so one
+    /// call can never cancel or unregister another.
+    pub async fn run<F: Future>(&self, request_id: Option<&str>, future: F) -> Result<Option<F::Output>, CommandError> {
+        let Some(request_id) = request_id else {
+            return Ok(Some(future.await));
+        };
+        let (sender, mut cancelled) = oneshot::channel();
+        {
+            let mut active = self.lock();
+            if active.contains_key(request_id) {
+                return Err(CommandError::validation(format!("request id {request_id} is already in flight")));
+            }
+            active.insert(request_id.to_string(), sender);
+        }
+        let outcome = tokio::select! {
+            output = future => Some(output),
+            Ok(()) = &mut cancelled => None,
+        };
+        self.lock().remove(request_id);
+        Ok(outcome)
+    }
+
+    /// Returns `false` when nothing is running under that id.
+    pub fn cancel(&self, request_id: &str) -> bool {
+        let sender = self.lock().remove(request_id);
+        sender.is_some_and(|sender| sender.send(()).is_ok())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, oneshot::Sender<()>>> {
+        self.active.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_request_ids_are_refused_and_leave_the_first_cancellable() {
+        let inflight = InflightRequests::default();
+        tauri::async_runtime::block_on(async {
+            let first = inflight.run(Some("req-1"), std::future::pending::<()>());
+            let second = async {
+                tokio::task::yield_now().await;
+                let duplicate = inflight.run(Some("req-1"), async {}).await;
+                assert_eq!(duplicate.unwrap_err().to_string(), "request id req-1 is already in flight");
+                assert!(inflight.cancel("req-1"));
+            };
+            let (first, ()) = tokio::join!(first, second);
+            assert!(matches!(first, Ok(None)));
+        });
+        assert!(!inflight.cancel("req-1"));
+    }
+}
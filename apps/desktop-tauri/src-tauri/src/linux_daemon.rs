@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const AUTOSTART_FILE_NAME: &str = "major-claw-gateway.desktop";
+/// The runner's log is moved aside to `<log>.1` once it grows past this.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// How long `start` and `stop` wait for the runner to come up or go away.
+const START_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How the always-on gateway is kept running on Linux.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinuxBackend {
+    /// A systemd user service.
+    Systemd,
+    /// The pidfile runner, started now and again at every graphical login by an
+    /// XDG autostart entry. For desktops without a systemd user manager.
+    XdgAutostart,
+    /// The pidfile runner alone. Nothing restarts it after a reboot until the app is
+    /// opened again; for containers and minimal installs.
+    Pidfile,
+}
+
+impl LinuxBackend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinuxBackend::Systemd => "systemd",
+            LinuxBackend::XdgAutostart => "xdg_autostart",
+            LinuxBackend::Pidfile => "pidfile",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        [LinuxBackend::Systemd, LinuxBackend::XdgAutostart, LinuxBackend::Pidfile]
+            .into_iter()
+            .find(|backend| backend.as_str() == value)
+    }
+}
+
+/// Picks the backend: `MAJORCLAW_DAEMON_BACKEND` if set, otherwise systemd when the
+/// user manager answers, otherwise a runner backend that is already installed,
+/// otherwise the first usable of XDG autostart and the pidfile runner. A runner left
+/// installed never wins over a working systemd. The error says why none can be used.
+pub fn detect(runner: &PidfileRunner) -> Result<LinuxBackend, String> {
+    if let Ok(value) = std::env::var("MAJORCLAW_DAEMON_BACKEND") {
+        let value = value.trim();
+        if !value.is_empty() {
+            return LinuxBackend::parse(value).ok_or_else(|| {
+                format!("MAJORCLAW_DAEMON_BACKEND={value:?} is not one of systemd, xdg_autostart or pidfile")
+            });
+        }
+    }
+    let systemd = match Command::new("systemctl").args(["--user", "show-environment"]).output() {
+        Ok(output) if output.status.success() => return Ok(LinuxBackend::Systemd),
+        Ok(output) => String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        Err(error) => format!("systemctl could not run: {error}"),
+    };
+    if runner.autostart_entry.exists() {
+        return Ok(LinuxBackend::XdgAutostart);
+    }
+    if runner.script.exists() {
+        return Ok(LinuxBackend::Pidfile);
+    }
+    let desktop = ["XDG_CURRENT_DESKTOP", "DESKTOP_SESSION"]
+        .iter()
+        .any(|name| std::env::var(name).is_ok_and(|value| !value.trim().is_empty()));
+    if desktop {
+        return Ok(LinuxBackend::XdgAutostart);
+    }
+    match Command::new("sh").args(["-c", "true"]).status() {
+        Ok(status) if status.success() => Ok(LinuxBackend::Pidfile),
+        Ok(status) => Err(format!(
+            "systemd user services are unavailable ({systemd}), there is no desktop session for XDG autostart, \
+             and `sh -c true` exited with {status}"
+        )),
+        Err(error) => Err(format!(
+            "systemd user services are unavailable ({systemd}), there is no desktop session for XDG autostart, \
+             and `sh` could not run: {error}"
+        )),
+    }
+}
+
+/// A detached `sh` loop that restarts the gateway whenever it exits and records its
+/// own PID in a pidfile, for hosts without systemd. The script embeds the session
+/// token, so it is written owner-only like the other daemon definitions.
+pub struct PidfileRunner {
+    pub script: PathBuf,
+    pub pidfile: PathBuf,
+    pub log: PathBuf,
+    pub autostart_entry: PathBuf,
+}
+
+impl PidfileRunner {
+    pub fn locate() -> Result<Self, String> {
+        let home = PathBuf::from(std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?);
+        let base = home.join(".major-claw");
+        let config = std::env::var("XDG_CONFIG_HOME")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".config"));
+        Ok(Self {
+            script: base.join("daemon").join("gateway-daemon.sh"),
+            // Not `gateway.pid`: that is the gateway's own single-instance lock.
+            pidfile: base.join("run").join("gateway-daemon.pid"),
+            log: base.join("logs").join("gateway-daemon.log"),
+            autostart_entry: config.join("autostart").join(AUTOSTART_FILE_NAME),
+        })
+    }
+
+    pub fn render_script(
+        &self,
+        program: &Path,
+        args: &[String],
+        working_dir: &Path,
+        environment: &BTreeMap<String, String>,
+    ) -> String {
+        let exports = environment
+            .iter()
+            .map(|(key, value)| format!("export {key}={}", shell_quote(value)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let command = std::iter::once(program.to_string_lossy().to_string())
+            .chain(args.iter().cloned())
+            .map(|arg| shell_quote(&arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let pidfile = shell_quote(&self.pidfile.to_string_lossy());
+        let log = shell_quote(&self.log.to_string_lossy());
+        format!(
+            "#!/bin/sh\n\
+             # Major Claw always-on gateway runner; managed by the app, edits are overwritten.\n\
+             pidfile={pidfile}\n\
+             log={log}\n\
+             running=$(cat \"$pidfile\" 2>/dev/null)\n\
+             if [ -n \"$running\" ] && [ \"$running\" != \"$$\" ] && kill -0 \"$running\" 2>/dev/null; then\n\
+             \x20 exit 0\n\
+             fi\n\
+             mkdir -p \"$(dirname \"$pidfile\")\" \"$(dirname \"$log\")\"\n\
+             echo $$ > \"$pidfile\"\n\
+             cd {cwd} || exit 1\n\
+             {exports}\n\
+             trap '' HUP\n\
+             # setsid gives each gateway its own process group, so stopping also reaches\n\
+             # what it spawned (pnpm's node child in a workspace launch).\n\
+             launcher=\n\
+             command -v setsid >/dev/null 2>&1 && launcher=setsid\n\
+             trap 'kill -TERM -\"$gateway\" 2>/dev/null || kill -TERM \"$gateway\" 2>/dev/null; rm -f \"$pidfile\"; exit 0' TERM INT\n\
+             while :; do\n\
+             \x20 if [ -f \"$log\" ] && [ \"$(wc -c < \"$log\")\" -gt {max_log} ]; then mv -f \"$log\" \"$log.1\"; fi\n\
+             \x20 $launcher {command} >> \"$log\" 2>&1 &\n\
+             \x20 gateway=$!\n\
+             \x20 wait \"$gateway\"\n\
+             \x20 sleep 3\n\
+             done\n",
+            cwd = shell_quote(&working_dir.to_string_lossy()),
+            max_log = MAX_LOG_BYTES,
+        )
+    }
+
+    /// Starts the runner at every graphical login; the runner exits straight away if
+    /// one is already running.
+    pub fn render_autostart_entry(&self) -> String {
+        let script = self
+            .script
+            .to_string_lossy()
+            .replace('\\', "\\\\\\\\")
+            .replace('"', "\\\\\"")
+            .replace('`', "\\\\`")
+            .replace('$', "\\\\$")
+            .replace('%', "%%");
+        format!(
+            "[Desktop Entry]\nType=Application\nName=Major Claw Gateway\n\
+             Comment=Keeps the Major Claw gateway running in the background\n\
+             Exec=sh \"{script}\"\nTerminal=false\nNoDisplay=true\nX-GNOME-Autostart-enabled=true\n"
+        )
+    }
+
+    /// PID of the running runner, if the pidfile points at a live process running
+    /// this runner's script (not a recycled PID).
+    pub fn pid(&self) -> Option<u32> {
+        let pid = fs::read_to_string(&self.pidfile).ok()?.trim().parse::<u32>().ok()?;
+        let cmdline = fs::read(format!("/proc/{pid}/cmdline")).ok()?;
+        let script = self.script.to_string_lossy();
+        String::from_utf8_lossy(&cmdline)
+            .split('\0')
+            .any(|arg| arg == script)
+            .then_some(pid)
+    }
+
+    /// Launches the runner detached from the app, so it outlives it, and waits
+    /// briefly for it to record its PID. Like `stop`, it sleeps while it waits, so
+    /// async callers run it through `spawn_blocking`.
+    pub fn start(&self) -> Result<(), String> {
+        if self.pid().is_some() {
+            return Ok(());
+        }
+        let output = Command::new("sh")
+            .args(["-c", "sh \"$0\" </dev/null >/dev/null 2>&1 & echo $!"])
+            .arg(&self.script)
+            .output()
+            .map_err(|err| format!("failed to launch {}: {err}", self.script.display()))?;
+        if !output.status.success() {
+            return Err(format!("failed to launch {}", self.script.display()));
+        }
+        let started = Instant::now();
+        while started.elapsed() < START_TIMEOUT {
+            if self.pid().is_some() {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Err(format!(
+            "the gateway runner did not start; see {} for its output",
+            self.log.display()
+        ))
+    }
+
+    /// Stops the runner, which stops its gateway on the way out, and waits for it to exit.
+    pub fn stop(&self) -> Result<(), String> {
+        let Some(pid) = self.pid() else {
+            let _ = fs::remove_file(&self.pidfile);
+            return Ok(());
+        };
+        let status = Command::new("kill")
+            .args(["-TERM", &pid.to_string()])
+            .status()
+            .map_err(|err| format!("failed to stop the gateway runner: {err}"))?;
+        if !status.success() {
+            return Err(format!("kill -TERM {pid} failed"));
+        }
+        let started = Instant::now();
+        while started.elapsed() < START_TIMEOUT {
+            if self.pid().is_none() {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Err(format!("the gateway runner (pid {pid}) did not stop"))
+    }
+
+    /// Stops the runner and removes every file it was installed with.
+    pub fn uninstall(&self) -> Result<(), String> {
+        self.stop()?;
+        remove_if_present(&self.script)?;
+        self.remove_autostart_entry()
+    }
+
+    /// Stops the runner from starting at login, leaving the script in place.
+    pub fn remove_autostart_entry(&self) -> Result<(), String> {
+        remove_if_present(&self.autostart_entry)
+    }
+}
+
+fn remove_if_present(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("failed to remove {}: {err}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Single-quotes a value for `sh`, which takes everything inside literally.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runner() -> PidfileRunner {
+        PidfileRunner {
+            script: PathBuf::from("/home/me/.major-claw/daemon/gateway-daemon.sh"),
+            pidfile: PathBuf::from("/home/me/.major-claw/run/gateway-daemon.pid"),
+            log: PathBuf::from("/home/me/My Logs/gateway-daemon.log"),
+            autostart_entry: PathBuf::from("/home/me/.config/autostart/gateway.desktop"),
+        }
+    }
+
+    #[test]
+    fn shell_quote_survives_a_round_trip_through_sh() {
+        for value in ["plain", "it's", "$HOME `id` \"x\" \\n", "a'b''c", ""] {
+            let script = format!("printf %s {}", shell_quote(value));
+            let output = Command::new("sh").arg("-c").arg(script).output().unwrap();
+            assert_eq!(String::from_utf8(output.stdout).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn render_script_quotes_the_command_environment_and_paths() {
+        let environment = BTreeMap::from([("MAJORCLAW_NOTE".to_string(), "it's $HOME".to_string())]);
+        let script = runner().render_script(
+            Path::new("/opt/Major Claw/node"),
+            &["gateway.js".to_string(), "--name=a b".to_string()],
+            Path::new("/opt/Major Claw"),
+            &environment,
+        );
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("\nexport MAJORCLAW_NOTE='it'\\''s $HOME'\n"));
+        assert!(script.contains("\ncd '/opt/Major Claw' || exit 1\n"));
+        assert!(script.contains("\nlog='/home/me/My Logs/gateway-daemon.log'\n"));
+        assert!(script.contains("$launcher '/opt/Major Claw/node' 'gateway.js' '--name=a b' >> \"$log\" 2>&1 &"));
+    }
+}
@@ -1,52 +1,251 @@
 mod commands;
+mod daemon_install;
+mod daemon_logs;
+mod error;
+mod event_bridge;
+mod gateway_client;
+mod gateway_endpoint;
+mod gateway_launch;
+mod gateway_logs;
+mod gateway_profiles;
+mod gateway_remote;
+mod host_telemetry;
+mod inflight;
+mod linux_daemon;
+mod models;
+mod preflight;
+mod read_cache;
+mod secret_store;
+mod session_token;
+mod sse;
+mod supervisor;
+mod systemd_unit;
+mod write_queue;
 
+use gateway_endpoint::GatewayEndpoint;
+use commands::GatewayOwner;
+use error::CommandError;
+use gateway_launch::GatewayLaunch;
+use gateway_logs::GatewayLogs;
+use gateway_profiles::{GatewayProfile, GatewayProfiles, GatewayTarget, DEFAULT_PROFILE};
+use gateway_remote::RemoteGateway;
+use std::path::PathBuf;
 use std::process::Child;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tauri::Manager;
 
-struct GatewayState {
+/// Process state for one gateway profile. A fresh one is built on every switch;
+/// the old one's child is stopped before it is dropped.
+struct ProfileRuntime {
+    name: String,
     child: Mutex<Option<Child>>,
+    endpoint: Mutex<GatewayEndpoint>,
+    /// Set while the host uses a gateway it found already running instead of its own child.
+    attached: Mutex<Option<GatewayOwner>>,
+    /// When the current child was spawned, however it was started (supervisor restart,
+    /// manual start, profile switch or token rotation).
+    spawned_at: Mutex<Option<Instant>>,
+    /// Set for remote profiles; spawning and the watchdog are off while it is.
+    remote: Option<RemoteGateway>,
+}
+
+impl ProfileRuntime {
+    fn new(profile: &GatewayProfile) -> Self {
+        let (endpoint, remote) = match &profile.target {
+            GatewayTarget::Local(endpoint) if profile.name == DEFAULT_PROFILE => {
+                (endpoint.clone().with_env_overrides(), None)
+            }
+            GatewayTarget::Local(endpoint) => (endpoint.clone(), None),
+            GatewayTarget::Remote(remote) => (GatewayEndpoint::default(), Some(remote.clone())),
+        };
+        Self {
+            name: profile.name.clone(),
+            child: Mutex::new(None),
+            endpoint: Mutex::new(endpoint),
+            attached: Mutex::new(None),
+            spawned_at: Mutex::new(None),
+            remote,
+        }
+    }
+}
+
+struct GatewayState {
+    profiles: Mutex<GatewayProfiles>,
+    profiles_file: Option<PathBuf>,
+    active: Mutex<Arc<ProfileRuntime>>,
+    /// Resolved once at launch; an error here is reported when a start is attempted.
+    launch: Result<GatewayLaunch, String>,
+    logs: Arc<GatewayLogs>,
     desired_running: AtomicBool,
     app_closing: AtomicBool,
+    /// Bumped whenever the client is pointed at another gateway, so long-lived
+    /// connections such as the event bridge drop the old one and reconnect.
+    connection_epoch: tokio::sync::watch::Sender<u64>,
+    /// Whether the always-on service is enabled, as last seen; `None` until it is
+    /// asked for. The supervisor checks it every tick, so it is not re-read each time.
+    daemon_enabled: Mutex<Option<bool>>,
 }
 
-impl Default for GatewayState {
-    fn default() -> Self {
+impl GatewayState {
+    /// `active` may differ from the persisted choice when that profile could not be
+    /// reached at launch; the file keeps the user's choice for the next launch.
+    fn new(
+        mut profiles: GatewayProfiles,
+        active: &GatewayProfile,
+        profiles_file: Option<PathBuf>,
+        launch: Result<GatewayLaunch, String>,
+        logs: GatewayLogs,
+    ) -> Self {
+        profiles.active = active.name.clone();
         Self {
-            child: Mutex::new(None),
+            active: Mutex::new(Arc::new(ProfileRuntime::new(active))),
+            profiles: Mutex::new(profiles),
+            profiles_file,
+            launch,
+            logs: Arc::new(logs),
             desired_running: AtomicBool::new(true),
             app_closing: AtomicBool::new(false),
+            connection_epoch: tokio::sync::watch::Sender::new(0),
+            daemon_enabled: Mutex::new(None),
+        }
+    }
+
+    fn active(&self) -> Arc<ProfileRuntime> {
+        self.active.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    fn remote(&self) -> Option<RemoteGateway> {
+        self.active().remote.clone()
+    }
+
+    fn profiles(&self) -> std::sync::MutexGuard<'_, GatewayProfiles> {
+        self.profiles.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Writes the profile list to `gateway-profiles.json`, if the config dir is known.
+    fn persist_profiles(&self, profiles: &GatewayProfiles) -> Result<(), CommandError> {
+        match self.profiles_file.as_deref() {
+            Some(file) => profiles.persist(file).map_err(CommandError::platform),
+            None => Ok(()),
         }
     }
+
+    /// Makes `profile` the active one and remembers the choice for the next launch.
+    /// The caller stops the previous runtime's child first.
+    fn activate(&self, profile: &GatewayProfile) -> Result<(), CommandError> {
+        {
+            let mut profiles = self.profiles();
+            profiles.active = profile.name.clone();
+            self.persist_profiles(&profiles)?;
+        }
+        *self.active.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(ProfileRuntime::new(profile));
+        Ok(())
+    }
+
+    /// Where a daemon unit's gateway should listen: the default profile's endpoint,
+    /// including a fallback port it was moved to while active.
+    fn daemon_endpoint(&self) -> GatewayEndpoint {
+        let runtime = self.active();
+        if runtime.name == DEFAULT_PROFILE {
+            return runtime.endpoint.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+        }
+        let profile = self.profiles().get(DEFAULT_PROFILE).cloned().unwrap_or_default();
+        ProfileRuntime::new(&profile).endpoint.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Saves the port a local profile ended up on, so it is tried first next time.
+    fn remember_endpoint(&self, name: &str, endpoint: &GatewayEndpoint) -> Result<(), CommandError> {
+        let mut profiles = self.profiles();
+        let Some(profile) = profiles.profiles.iter_mut().find(|profile| profile.name == name) else {
+            return Ok(());
+        };
+        profile.target = GatewayTarget::Local(endpoint.clone());
+        self.persist_profiles(&profiles)
+    }
 }
 
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_log::Builder::default().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .manage(GatewayState::default())
         .setup(|app| {
-            let gateway_state = app.state::<GatewayState>();
-            if let Err(error) = commands::auto_start_gateway(gateway_state.inner()) {
-                eprintln!("failed to auto-start gateway on launch: {error}");
+            let secrets = secret_store::SecretStore::new(app.path().app_config_dir().ok());
+            let profiles_file = gateway_profiles::profiles_file(app.handle());
+            let profiles = GatewayProfiles::load(profiles_file.as_deref(), &secrets);
+            let mut active = profiles.get(&profiles.active).cloned().unwrap_or_default();
+            let remote_client = match &active.target {
+                GatewayTarget::Remote(remote) => {
+                    let token = session_token::stored_session_token(&secrets, &active.name).unwrap_or_default();
+                    match remote.client(&token) {
+                        Ok(client) => Some(client),
+                        Err(error) => {
+                            let name = &active.name;
+                            eprintln!("failed to connect to gateway profile {name:?}, using the local one: {error}");
+                            active = profiles.get(DEFAULT_PROFILE).cloned().unwrap_or_default();
+                            None
+                        }
+                    }
+                }
+                GatewayTarget::Local(_) => None,
+            };
+            let client = match remote_client {
+                Some(client) => client,
+                None => {
+                    // Like the launch below, a failure here is logged and then reported by
+                    // every gateway call, rather than keeping the app from starting.
+                    let endpoint = ProfileRuntime::new(&active).endpoint.into_inner().unwrap_or_default();
+                    let client = session_token::profile_session_token(&secrets, &active.name)
+                        .map_err(|error| error.to_string())
+                        .and_then(|token| {
+                            gateway_client::GatewayClient::local(&endpoint, token).map_err(|error| error.to_string())
+                        });
+                    client.unwrap_or_else(|error| {
+                        eprintln!("gateway client is unavailable: {error}");
+                        gateway_client::GatewayClient::unavailable(&endpoint, error)
+                    })
+                }
+            };
+            app.manage(secrets);
+            app.manage(client);
+            let logs = GatewayLogs::new(app.handle().clone());
+            let launch = GatewayLaunch::resolve(app.handle());
+            if let Err(error) = &launch {
+                eprintln!("gateway launch is unavailable: {error}");
             }
+            app.manage(GatewayState::new(profiles, &active, profiles_file, launch, logs));
+            app.manage(host_telemetry::HostTelemetry::default());
+            app.manage(supervisor::Supervisor::default());
+            app.manage(inflight::InflightRequests::default());
+            app.manage(read_cache::ReadCache::new(app.path().app_cache_dir().ok()));
+            app.manage(write_queue::WriteQueue::new(app.path().app_data_dir().ok()));
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                loop {
-                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                    let state = app_handle.state::<GatewayState>();
-                    if state.app_closing.load(Ordering::SeqCst) {
-                        break;
-                    }
-                    if !state.desired_running.load(Ordering::SeqCst) {
-                        continue;
-                    }
-                    if let Err(error) = commands::auto_start_gateway(state.inner()) {
-                        eprintln!("gateway watchdog restart attempt failed: {error}");
+                // Both shell out to the service manager, so they run off the async runtime.
+                // Later checks of whether the service is enabled use the cached answer.
+                let blocking_handle = app_handle.clone();
+                let _ = tauri::async_runtime::spawn_blocking(move || {
+                    let gateway_state = blocking_handle.state::<GatewayState>();
+                    let gateway_client = blocking_handle.state::<gateway_client::GatewayClient>();
+                    commands::refresh_daemon_enabled(gateway_state.inner());
+                    let upgrade = gateway_client
+                        .session_token()
+                        .map_err(CommandError::from)
+                        .and_then(|token| commands::upgrade_installed_daemon(gateway_state.inner(), &token));
+                    if let Err(error) = upgrade {
+                        eprintln!("failed to upgrade the always-on gateway service: {error}");
                     }
+                })
+                .await;
+                let gateway_state = app_handle.state::<GatewayState>();
+                let gateway_client = app_handle.state::<gateway_client::GatewayClient>();
+                if let Err(error) = commands::ensure_gateway(gateway_state.inner(), gateway_client.inner()).await {
+                    eprintln!("failed to auto-start gateway on launch: {error}");
                 }
             });
+            tauri::async_runtime::spawn(supervisor::run(app.handle().clone()));
+            tauri::async_runtime::spawn(event_bridge::run(app.handle().clone()));
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -60,7 +259,8 @@ fn main() {
                 let app_handle = window.app_handle().clone();
                 tauri::async_runtime::spawn(async move {
                     let gateway_state = app_handle.state::<GatewayState>();
-                    let _ = commands::stop_gateway_for_exit(gateway_state.inner()).await;
+                    let gateway_client = app_handle.state::<gateway_client::GatewayClient>();
+                    let _ = commands::stop_gateway_for_exit(gateway_state.inner(), gateway_client.inner()).await;
                     if let Some(main_window) = app_handle.get_webview_window("main") {
                         let _ = main_window.close();
                     }
@@ -72,21 +272,39 @@ fn main() {
             commands::stop_gateway,
             commands::gateway_daemon_status,
             commands::gateway_daemon_set_enabled,
+            commands::gateway_daemon_install_or_upgrade,
             commands::gateway_daemon_start,
             commands::gateway_daemon_stop,
             commands::gateway_daemon_restart,
+            commands::gateway_daemon_logs,
             commands::red_phone_shutdown,
             commands::open_official_integrations,
             commands::close_official_integrations,
             commands::back_official_integrations,
             commands::gateway_status,
+            commands::gateway_preflight,
+            commands::gateway_supervisor_status,
+            commands::gateway_process_logs,
             commands::gateway_session_token,
+            commands::rotate_gateway_session_token,
+            commands::remote_gateway_config,
+            commands::connect_remote_gateway,
+            commands::disconnect_remote_gateway,
+            commands::list_gateway_profiles,
+            commands::save_gateway_profile,
+            commands::delete_gateway_profile,
+            commands::switch_gateway_profile,
             commands::gateway_health,
+            commands::cancel_request,
             commands::list_agents,
             commands::list_tasks,
             commands::create_task,
             commands::update_task,
             commands::delete_task,
+            commands::write_queue_pending,
+            commands::write_queue_conflicts,
+            commands::dismiss_write_conflict,
+            commands::replay_write_queue,
             commands::create_agent,
             commands::reorder_agents,
             commands::update_agent_config,
@@ -95,6 +313,7 @@ fn main() {
             commands::chat_summary,
             commands::chat_messages,
             commands::chat_send,
+            commands::chat_send_stream,
             commands::chat_quick_action,
             commands::chat_checkpoints,
             commands::chat_rewind,
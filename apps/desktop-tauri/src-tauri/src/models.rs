@@ -0,0 +1,565 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+pub struct GatewayHealth {
+    pub status: String,
+    #[serde(rename = "startedAt")]
+    pub started_at: Option<String>,
+    #[serde(rename = "instanceCount")]
+    pub instance_count: Option<u64>,
+    /// `app`, `daemon` or `external`, as set by whoever launched the gateway.
+    pub owner: Option<String>,
+    pub pid: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AgentProfile {
+    id: String,
+    name: String,
+    role: String,
+    #[serde(rename = "modelProfileId")]
+    model_profile_id: String,
+    status: String,
+    #[serde(rename = "parentId")]
+    parent_id: Option<String>,
+    #[serde(rename = "modelProvider")]
+    model_provider: Option<String>,
+    #[serde(rename = "modelName")]
+    model_name: Option<String>,
+    temperature: Option<f64>,
+    #[serde(rename = "maxTokens")]
+    max_tokens: Option<u32>,
+    #[serde(rename = "lastHeartbeat")]
+    last_heartbeat: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AgentStats {
+    #[serde(rename = "agentId")]
+    agent_id: String,
+    #[serde(rename = "tokensToday")]
+    tokens_today: u64,
+    #[serde(rename = "tasksCompleted")]
+    tasks_completed: u64,
+    #[serde(rename = "lastUpdated")]
+    last_updated: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AgentFullConfig {
+    id: String,
+    name: String,
+    role: String,
+    #[serde(rename = "modelProfileId")]
+    model_profile_id: String,
+    status: String,
+    #[serde(rename = "parentId")]
+    parent_id: Option<String>,
+    #[serde(rename = "modelProvider")]
+    model_provider: Option<String>,
+    #[serde(rename = "modelName")]
+    model_name: Option<String>,
+    temperature: Option<f64>,
+    #[serde(rename = "maxTokens")]
+    max_tokens: Option<u32>,
+    #[serde(rename = "lastHeartbeat")]
+    last_heartbeat: Option<String>,
+    #[serde(rename = "apiKeyMasked")]
+    api_key_masked: Option<String>,
+    #[serde(rename = "installedSkills")]
+    installed_skills: Vec<String>,
+    stats: AgentStats,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AgentCreatePayload {
+    pub name: String,
+    pub role: String,
+    #[serde(rename = "parentId")]
+    pub parent_id: Option<String>,
+    #[serde(rename = "modelProvider")]
+    pub model_provider: String,
+    #[serde(rename = "modelName")]
+    pub model_name: String,
+    #[serde(rename = "apiKey")]
+    pub api_key: Option<String>,
+    pub temperature: Option<f64>,
+    #[serde(rename = "maxTokens")]
+    pub max_tokens: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AgentConfigPatch {
+    #[serde(rename = "modelProvider")]
+    pub model_provider: Option<String>,
+    #[serde(rename = "modelName")]
+    pub model_name: Option<String>,
+    #[serde(rename = "apiKey")]
+    pub api_key: Option<String>,
+    pub temperature: Option<f64>,
+    #[serde(rename = "maxTokens")]
+    pub max_tokens: Option<u32>,
+    pub status: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AgentActionResult {
+    success: bool,
+    message: String,
+    action: String,
+    #[serde(rename = "agentId")]
+    agent_id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AgentConnectionTestResult {
+    ok: bool,
+    message: String,
+    status: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TaskRecord {
+    id: String,
+    title: String,
+    description: Option<String>,
+    status: String,
+    priority: String,
+    #[serde(rename = "assigneeAgentId")]
+    assignee_agent_id: Option<String>,
+    #[serde(rename = "parentTaskId")]
+    parent_task_id: Option<String>,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    #[serde(rename = "updatedAt")]
+    updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TaskCreatePayload {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    #[serde(rename = "assigneeAgentId")]
+    pub assignee_agent_id: Option<String>,
+    #[serde(rename = "parentTaskId")]
+    pub parent_task_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TaskPatch {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    #[serde(rename = "assigneeAgentId")]
+    pub assignee_agent_id: Option<Option<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IntegrationEntry {
+    slug: String,
+    name: String,
+    category: String,
+    description: String,
+    setup: Vec<String>,
+    permissions: Vec<String>,
+    status: String,
+    #[serde(rename = "assignedAgentIds")]
+    assigned_agent_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IntegrationCategoryStat {
+    name: String,
+    #[serde(rename = "connectedCount")]
+    connected_count: u64,
+    #[serde(rename = "totalCount")]
+    total_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IntegrationsListResult {
+    items: Vec<IntegrationEntry>,
+    categories: Vec<IntegrationCategoryStat>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct McpServerEntry {
+    id: String,
+    url: String,
+    name: String,
+    capabilities: Vec<String>,
+    connected: bool,
+    #[serde(rename = "approvedScopes")]
+    approved_scopes: Vec<String>,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    #[serde(rename = "lastConnectedAt")]
+    last_connected_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct McpToolEntry {
+    id: String,
+    #[serde(rename = "serverId")]
+    server_id: String,
+    name: String,
+    description: String,
+    scopes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct McpInvokeResult {
+    output: String,
+    #[serde(rename = "latencyMs")]
+    latency_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VaultEntry {
+    id: String,
+    #[serde(rename = "type")]
+    entry_type: String,
+    title: String,
+    #[serde(rename = "markdownSummary")]
+    markdown_summary: String,
+    #[serde(rename = "importanceScore")]
+    importance_score: u64,
+    tags: Vec<String>,
+    #[serde(rename = "agentId")]
+    agent_id: String,
+    #[serde(rename = "taskId")]
+    task_id: Option<String>,
+    version: u64,
+    #[serde(rename = "blobPath")]
+    blob_path: Option<String>,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    encrypted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VaultSummary {
+    #[serde(rename = "usedGb")]
+    used_gb: f64,
+    #[serde(rename = "capacityGb")]
+    capacity_gb: f64,
+    #[serde(rename = "archivedItems")]
+    archived_items: u64,
+    #[serde(rename = "fileItems")]
+    file_items: u64,
+    #[serde(rename = "knowledgeItems")]
+    knowledge_items: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VaultStorageStats {
+    #[serde(rename = "snapshotTime")]
+    snapshot_time: String,
+    #[serde(rename = "archiveGb")]
+    archive_gb: f64,
+    #[serde(rename = "filesGb")]
+    files_gb: f64,
+    #[serde(rename = "totalGb")]
+    total_gb: f64,
+    #[serde(rename = "freeGb")]
+    free_gb: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VaultStorageInfo {
+    #[serde(rename = "rootPath")]
+    root_path: String,
+    #[serde(rename = "volumeName")]
+    volume_name: String,
+    #[serde(rename = "totalGb")]
+    total_gb: f64,
+    #[serde(rename = "freeGb")]
+    free_gb: f64,
+    #[serde(rename = "vaultUsedGb")]
+    vault_used_gb: f64,
+    #[serde(rename = "isExternal")]
+    is_external: bool,
+    #[serde(rename = "isNetwork")]
+    is_network: bool,
+    #[serde(rename = "warningLevel")]
+    warning_level: String,
+    #[serde(rename = "isOfflineFallback")]
+    is_offline_fallback: bool,
+    #[serde(rename = "tempCachePath")]
+    temp_cache_path: Option<String>,
+    #[serde(rename = "updatedAt")]
+    updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VaultVersion {
+    #[serde(rename = "entryId")]
+    entry_id: String,
+    #[serde(rename = "versionNum")]
+    version_num: u64,
+    #[serde(rename = "blobPath")]
+    blob_path: Option<String>,
+    diff: Option<String>,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VaultDepositPayload {
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub title: String,
+    pub markdown_summary: String,
+    pub importance_score: u64,
+    pub tags: Vec<String>,
+    pub agent_id: String,
+    pub task_id: Option<String>,
+    pub encrypted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VaultEntryPatch {
+    pub title: Option<String>,
+    pub markdown_summary: Option<String>,
+    pub importance_score: Option<u64>,
+    pub tags: Option<Vec<String>>,
+    pub encrypted: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConnectedModelProvider {
+    provider: String,
+    label: String,
+    #[serde(rename = "modelHint")]
+    model_hint: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ClawHubSkill {
+    slug: String,
+    name: String,
+    author: String,
+    description: String,
+    downloads: u64,
+    stars: Option<u64>,
+    version: String,
+    categories: Vec<String>,
+    permissions: Vec<String>,
+    installed: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ClawHubLiveSkillsResult {
+    skills: Vec<ClawHubSkill>,
+    #[serde(rename = "nextCursor")]
+    next_cursor: Option<String>,
+    source: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ClawHubInstallResult {
+    slug: String,
+    installed: bool,
+    #[serde(rename = "assignedAgentId")]
+    assigned_agent_id: Option<String>,
+    message: String,
+    #[serde(rename = "requestedPermissionIds")]
+    requested_permission_ids: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PermissionGrant {
+    id: String,
+    #[serde(rename = "agentId")]
+    agent_id: String,
+    capability: String,
+    granted: bool,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuditLogEntry {
+    id: String,
+    category: String,
+    action: String,
+    actor: String,
+    metadata: serde_json::Value,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SwarmChatThread {
+    id: String,
+    title: String,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    #[serde(rename = "updatedAt")]
+    updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SwarmChatMessage {
+    id: String,
+    #[serde(rename = "threadId")]
+    thread_id: String,
+    #[serde(rename = "type")]
+    message_type: String,
+    author: String,
+    content: String,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    #[serde(rename = "parentMessageId")]
+    parent_message_id: Option<String>,
+    metadata: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChatDelta {
+    #[serde(rename = "messageId")]
+    message_id: String,
+    #[serde(rename = "threadId")]
+    thread_id: String,
+    content: String,
+}
+
+/// Sent over the `chat_send_stream` channel: text deltas while a reply is being
+/// produced, each finished message, then exactly one terminal event, `done` or
+/// `error` when the send failed (possibly partway through the reply).
+#[derive(Serialize, Clone)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum ChatStreamEvent {
+    Delta {
+        delta: ChatDelta,
+    },
+    Message {
+        message: SwarmChatMessage,
+    },
+    Done {
+        cancelled: bool,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SwarmSummary {
+    #[serde(rename = "onlineAgents")]
+    online_agents: u64,
+    #[serde(rename = "activeTasks")]
+    active_tasks: u64,
+    #[serde(rename = "spendTodayUsd")]
+    spend_today_usd: f64,
+    heartbeat: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CheckpointRecord {
+    id: String,
+    #[serde(rename = "swarmId")]
+    swarm_id: String,
+    step: u64,
+    #[serde(rename = "stateJson")]
+    state_json: String,
+    #[serde(rename = "promptSnapshot")]
+    prompt_snapshot: Option<String>,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AgentBudget {
+    #[serde(rename = "agentId")]
+    agent_id: String,
+    #[serde(rename = "tokenLimit")]
+    token_limit: u64,
+    #[serde(rename = "costLimitUsd")]
+    cost_limit_usd: f64,
+    #[serde(rename = "currentTokens")]
+    current_tokens: u64,
+    #[serde(rename = "currentCostUsd")]
+    current_cost_usd: f64,
+    #[serde(rename = "hardKill")]
+    hard_kill: bool,
+    #[serde(rename = "updatedAt")]
+    updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BudgetSnapshot {
+    global: AgentBudget,
+    agents: Vec<AgentBudget>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HealthSnapshot {
+    heartbeat: String,
+    #[serde(rename = "uptimeSeconds")]
+    uptime_seconds: u64,
+    #[serde(rename = "gatewayStatus")]
+    gateway_status: String,
+    #[serde(rename = "activeAgents")]
+    active_agents: u64,
+    #[serde(rename = "totalAgents")]
+    total_agents: u64,
+    #[serde(rename = "errorAgents")]
+    error_agents: u64,
+    #[serde(rename = "pendingApprovals")]
+    pending_approvals: u64,
+    #[serde(rename = "spendTodayUsd")]
+    spend_today_usd: f64,
+    #[serde(rename = "vaultUsedGb")]
+    vault_used_gb: f64,
+    #[serde(rename = "vaultCapacityGb")]
+    vault_capacity_gb: f64,
+    #[serde(rename = "vaultUsagePct")]
+    vault_usage_pct: f64,
+    alerts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HealthTelemetryEvent {
+    pub id: String,
+    pub category: String,
+    pub severity: String,
+    pub source: String,
+    pub message: String,
+    pub metadata: serde_json::Value,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TelemetryExportResult {
+    format: String,
+    #[serde(rename = "generatedAt")]
+    generated_at: String,
+    payload: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AnalyticsSnapshot {
+    range: String,
+    kpis: serde_json::Value,
+    trends: serde_json::Value,
+    #[serde(rename = "perAgent")]
+    per_agent: serde_json::Value,
+    forecasts: serde_json::Value,
+    recommendations: Vec<String>,
+    #[serde(rename = "generatedAt")]
+    generated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AnalyticsExportResult {
+    range: String,
+    format: String,
+    #[serde(rename = "generatedAt")]
+    generated_at: String,
+    payload: String,
+}
@@ -0,0 +1,285 @@
+use crate::gateway_endpoint::GatewayEndpoint;
+use crate::gateway_launch::{GatewayLaunch, LaunchKind};
+use crate::host_telemetry::iso8601;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+/// First Node release with `node:sqlite` available without a flag; `@majorclaw/db` needs it.
+const MIN_NODE_VERSION: (u64, u64) = (22, 13);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Serialize)]
+pub struct PreflightCheck {
+    id: &'static str,
+    label: &'static str,
+    status: CheckStatus,
+    detail: String,
+    /// What to do about a warning or failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl PreflightCheck {
+    fn new(id: &'static str, label: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            id,
+            label,
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Everything a gateway launch depends on, checked without starting one. `status`
+/// is the worst of the individual checks.
+#[derive(Serialize)]
+pub struct PreflightReport {
+    status: CheckStatus,
+    checks: Vec<PreflightCheck>,
+    #[serde(rename = "checkedAt")]
+    checked_at: String,
+}
+
+/// What the host knows about the gateway it would launch.
+pub struct PreflightInput<'a> {
+    pub launch: Result<&'a GatewayLaunch, &'a str>,
+    /// `None` while a remote profile is active; nothing is spawned then.
+    pub endpoint: Option<GatewayEndpoint>,
+    /// Whether a gateway this host spawned or attached to is running, which explains a busy port.
+    pub owns_running_gateway: bool,
+}
+
+pub fn run(input: PreflightInput<'_>) -> PreflightReport {
+    let mut checks = Vec::new();
+    match input.launch {
+        Ok(launch) => {
+            checks.extend(check_runtime(launch));
+            checks.push(check_working_dir(launch));
+        }
+        Err(error) => checks.push(
+            PreflightCheck::new("launch", "Gateway launch", CheckStatus::Fail, error)
+                .hint("Reinstall the app, or set MAJORCLAW_WORKSPACE_ROOT to a source checkout."),
+        ),
+    }
+    checks.push(check_port(input.endpoint.as_ref(), input.owns_running_gateway));
+    checks.push(check_vault_dir());
+    if cfg!(target_os = "linux") {
+        checks.push(check_systemd_user_session());
+    }
+    PreflightReport {
+        status: checks.iter().map(|check| check.status).max().unwrap_or(CheckStatus::Pass),
+        checks,
+        checked_at: iso8601(SystemTime::now()),
+    }
+}
+
+fn check_runtime(launch: &GatewayLaunch) -> Vec<PreflightCheck> {
+    let mut checks = Vec::new();
+    let node = match launch.kind {
+        LaunchKind::Bundled => launch.program.clone(),
+        LaunchKind::Workspace => {
+            checks.push(match program_version(&launch.program) {
+                Ok(version) => PreflightCheck::new(
+                    "pnpm",
+                    "pnpm",
+                    CheckStatus::Pass,
+                    format!("{} {version}", launch.program.display()),
+                ),
+                Err(error) => PreflightCheck::new("pnpm", "pnpm", CheckStatus::Fail, error)
+                    .hint("Install pnpm, or run `pnpm install` in the workspace so node_modules/.bin/pnpm exists."),
+            });
+            PathBuf::from("node")
+        }
+    };
+    checks.push(match program_version(&node) {
+        Ok(version) => match parse_node_version(&version) {
+            Some(parsed) if parsed < MIN_NODE_VERSION => PreflightCheck::new(
+                "node",
+                "Node.js",
+                CheckStatus::Fail,
+                format!(
+                    "{} {version} is older than the required {}.{}",
+                    node.display(),
+                    MIN_NODE_VERSION.0,
+                    MIN_NODE_VERSION.1
+                ),
+            )
+            .hint("Upgrade Node.js; the gateway's database needs `node:sqlite`."),
+            Some(_) => {
+                PreflightCheck::new("node", "Node.js", CheckStatus::Pass, format!("{} {version}", node.display()))
+            }
+            None => PreflightCheck::new(
+                "node",
+                "Node.js",
+                CheckStatus::Warn,
+                format!("{} reported an unrecognised version {version:?}", node.display()),
+            ),
+        },
+        Err(error) => PreflightCheck::new("node", "Node.js", CheckStatus::Fail, error).hint(match launch.kind {
+            LaunchKind::Bundled => "Reinstall the app; the bundled node runtime is damaged.",
+            LaunchKind::Workspace => "Install Node.js and make sure `node` is on PATH.",
+        }),
+    });
+    checks
+}
+
+fn check_working_dir(launch: &GatewayLaunch) -> PreflightCheck {
+    let (label, hint) = match launch.kind {
+        LaunchKind::Bundled => ("Bundled gateway", "Reinstall the app."),
+        LaunchKind::Workspace => ("Workspace root", "Set MAJORCLAW_WORKSPACE_ROOT to the source checkout."),
+    };
+    if launch.working_dir.is_dir() {
+        PreflightCheck::new("working_dir", label, CheckStatus::Pass, launch.working_dir.display().to_string())
+    } else {
+        PreflightCheck::new(
+            "working_dir",
+            label,
+            CheckStatus::Fail,
+            format!("{} does not exist", launch.working_dir.display()),
+        )
+        .hint(hint)
+    }
+}
+
+fn check_port(endpoint: Option<&GatewayEndpoint>, owns_running_gateway: bool) -> PreflightCheck {
+    const ID: &str = "port";
+    const LABEL: &str = "Gateway port";
+    let Some(endpoint) = endpoint else {
+        return PreflightCheck::new(ID, LABEL, CheckStatus::Pass, "a remote gateway profile is active");
+    };
+    let address = format!("{}:{}", endpoint.host, endpoint.port);
+    if endpoint.port_is_free() {
+        return PreflightCheck::new(ID, LABEL, CheckStatus::Pass, format!("{address} is free"));
+    }
+    if owns_running_gateway {
+        return PreflightCheck::new(ID, LABEL, CheckStatus::Pass, format!("{address} is held by the running gateway"));
+    }
+    match endpoint.with_free_port() {
+        Some(fallback) => PreflightCheck::new(
+            ID,
+            LABEL,
+            CheckStatus::Warn,
+            format!("{address} is in use; the gateway will fall back to port {}", fallback.port),
+        )
+        .hint("Stop whatever holds the port, or another gateway that was started outside the app."),
+        None => PreflightCheck::new(
+            ID,
+            LABEL,
+            CheckStatus::Fail,
+            format!("{address} is in use and no free port is available on {}", endpoint.host),
+        ),
+    }
+}
+
+/// The gateway's default vault location (see `VaultService` in the gateway).
+fn vault_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(
+        PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+            .join("com.jonnycatx.major-claw")
+            .join("vault"),
+    )
+}
+
+fn check_vault_dir() -> PreflightCheck {
+    const ID: &str = "vault_dir";
+    const LABEL: &str = "Vault directory";
+    let Some(dir) = vault_dir() else {
+        return PreflightCheck::new(ID, LABEL, CheckStatus::Fail, "HOME is not set");
+    };
+    // The gateway creates the vault dir on start, so a missing one only needs a writable ancestor.
+    let Some(existing) = dir.ancestors().find(|path| path.is_dir()) else {
+        return PreflightCheck::new(ID, LABEL, CheckStatus::Fail, format!("no parent of {} exists", dir.display()));
+    };
+    match probe_write(existing) {
+        Ok(()) if existing == dir => {
+            PreflightCheck::new(ID, LABEL, CheckStatus::Pass, format!("{} is writable", dir.display()))
+        }
+        Ok(()) => PreflightCheck::new(
+            ID,
+            LABEL,
+            CheckStatus::Pass,
+            format!("{} will be created under {}", dir.display(), existing.display()),
+        ),
+        Err(error) => PreflightCheck::new(
+            ID,
+            LABEL,
+            CheckStatus::Fail,
+            format!("cannot write to {}: {error}", existing.display()),
+        )
+        .hint("Fix the directory's permissions, or relocate the vault from Vault settings."),
+    }
+}
+
+fn probe_write(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".majorclaw-preflight-{}", std::process::id()));
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)
+}
+
+/// Daemon mode on Linux prefers a systemd user service, which needs a reachable user
+/// manager (often missing in containers and over plain SSH). Without one it falls
+/// back to an XDG autostart entry or a plain background process.
+fn check_systemd_user_session() -> PreflightCheck {
+    const ID: &str = "systemd_user";
+    const LABEL: &str = "systemd user session";
+    match Command::new("systemctl").args(["--user", "show-environment"]).output() {
+        Ok(output) if output.status.success() => {
+            PreflightCheck::new(ID, LABEL, CheckStatus::Pass, "systemctl --user is available")
+        }
+        Ok(output) => PreflightCheck::new(
+            ID,
+            LABEL,
+            CheckStatus::Warn,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )
+        .hint(
+            "Always-on mode will use a background process instead of systemd; for a systemd user service \
+             try `loginctl enable-linger $USER`.",
+        ),
+        Err(error) => PreflightCheck::new(ID, LABEL, CheckStatus::Warn, format!("systemctl could not run: {error}"))
+            .hint("Always-on mode will use an XDG autostart entry or a background process instead of systemd."),
+    }
+}
+
+/// First line of `<program> --version`, or why it could not be run.
+fn program_version(program: &Path) -> Result<String, String> {
+    let output = Command::new(program)
+        .arg("--version")
+        .output()
+        .map_err(|err| format!("failed to run {}: {err}", program.display()))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} --version exited with {}: {}",
+            program.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().next().unwrap_or_default().trim().to_string())
+}
+
+fn parse_node_version(version: &str) -> Option<(u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
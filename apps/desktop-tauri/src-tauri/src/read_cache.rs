@@ -0,0 +1,102 @@
+use crate::error::CommandError;
+use crate::gateway_client::GatewayError;
+use crate::host_telemetry::iso8601;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// A list command's result, possibly served from disk while the gateway is down.
+#[derive(Serialize)]
+pub struct Cached<T> {
+    data: T,
+    stale: bool,
+    #[serde(rename = "cachedAt")]
+    cached_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile<T> {
+    #[serde(rename = "cachedAt")]
+    cached_at: String,
+    data: T,
+}
+
+/// Last successful response of each cached read command, one JSON file per command
+/// and gateway profile in the app cache dir, so the UI keeps showing agents, tasks
+/// and the vault after `stop_gateway` or the red phone, and never another profile's.
+pub struct ReadCache {
+    dir: Option<PathBuf>,
+}
+
+impl ReadCache {
+    pub fn new(cache_dir: Option<PathBuf>) -> Self {
+        Self {
+            dir: cache_dir.map(|dir| dir.join("read-cache")),
+        }
+    }
+
+    /// Awaits `fetch` and caches what it returns. If the gateway can't be reached,
+    /// serves the cached copy marked `stale`; any other failure, or a miss, is an error.
+    pub async fn read<T, F>(&self, profile: &str, key: &str, fetch: F) -> Result<Cached<T>, CommandError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Future<Output = Result<T, GatewayError>>,
+    {
+        match fetch.await {
+            Ok(data) => {
+                let cached_at = iso8601(SystemTime::now());
+                self.store(profile, key, &cached_at, &data);
+                Ok(Cached {
+                    data,
+                    stale: false,
+                    cached_at: Some(cached_at),
+                })
+            }
+            Err(error @ GatewayError::Request { .. }) => match self.load::<T>(profile, key) {
+                Some(file) => Ok(Cached {
+                    data: file.data,
+                    stale: true,
+                    cached_at: Some(file.cached_at),
+                }),
+                None => Err(CommandError::from(error)),
+            },
+            Err(error) => Err(CommandError::from(error)),
+        }
+    }
+
+    fn path(&self, profile: &str, key: &str) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(profile).join(format!("{key}.json")))
+    }
+
+    fn store<T: Serialize>(&self, profile: &str, key: &str, cached_at: &str, data: &T) {
+        let Some(path) = self.path(profile, key) else {
+            return;
+        };
+        let file = CacheFile {
+            cached_at: cached_at.to_string(),
+            data,
+        };
+        let written = serde_json::to_vec(&file)
+            .map_err(|error| error.to_string())
+            .and_then(|bytes| {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+                }
+                // Write then rename so a crash mid-write never leaves a torn cache file.
+                let partial = path.with_extension("json.tmp");
+                fs::write(&partial, bytes).map_err(|error| error.to_string())?;
+                fs::rename(&partial, &path).map_err(|error| error.to_string())
+            });
+        if let Err(error) = written {
+            eprintln!("failed to cache {key}: {error}");
+        }
+    }
+
+    fn load<T: DeserializeOwned>(&self, profile: &str, key: &str) -> Option<CacheFile<T>> {
+        let raw = fs::read(self.path(profile, key)?).ok()?;
+        serde_json::from_slice(&raw).ok()
+    }
+}
@@ -0,0 +1,234 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::{Command, Stdio};
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+const SECRET_SERVICE: &str = "com.jonnycatx.major-claw";
+const SECRETS_DIR_NAME: &str = "secrets";
+
+/// Small secret store shared by the desktop host and the daemon units. Secrets go
+/// to the platform store (Secret Service via `secret-tool` on Linux, the login
+/// keychain via `security` on macOS) and fall back to owner-only files under the
+/// app config dir when that store is missing or locked. `MAJORCLAW_SECRET_STORE=file`
+/// forces the file backend, which is what tests and headless CI use.
+pub struct SecretStore {
+    fallback_dir: Option<PathBuf>,
+    platform_enabled: bool,
+}
+
+impl SecretStore {
+    pub fn new(config_dir: Option<PathBuf>) -> Self {
+        let platform_enabled = !matches!(
+            std::env::var("MAJORCLAW_SECRET_STORE").ok().as_deref(),
+            Some("file")
+        );
+        Self {
+            fallback_dir: config_dir.map(|dir| dir.join(SECRETS_DIR_NAME)),
+            platform_enabled,
+        }
+    }
+
+    pub fn load(&self, account: &str) -> Option<String> {
+        let from_platform = if self.platform_enabled {
+            platform_load(account)
+        } else {
+            None
+        };
+        from_platform
+            .or_else(|| self.file_load(account))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn save(&self, account: &str, secret: &str) -> Result<(), String> {
+        if self.platform_enabled {
+            match platform_save(account, secret) {
+                Ok(()) => {
+                    // Don't leave an older copy behind that `load` could pick up later.
+                    self.file_remove(account);
+                    return Ok(());
+                }
+                Err(error) => eprintln!("platform secret store unavailable, using file fallback: {error}"),
+            }
+        }
+        self.file_save(account, secret)
+    }
+
+    fn file_path(&self, account: &str) -> Option<PathBuf> {
+        self.fallback_dir.as_ref().map(|dir| dir.join(account))
+    }
+
+    fn file_load(&self, account: &str) -> Option<String> {
+        fs::read_to_string(self.file_path(account)?).ok()
+    }
+
+    fn file_remove(&self, account: &str) {
+        if let Some(path) = self.file_path(account) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    fn file_save(&self, account: &str, secret: &str) -> Result<(), String> {
+        let path = self
+            .file_path(account)
+            .ok_or_else(|| "no app config directory for secret file fallback".to_string())?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&path)
+            .map_err(|err| format!("failed to open {}: {err}", path.display()))?;
+        file.write_all(secret.as_bytes())
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn platform_load(account: &str) -> Option<String> {
+    let output = Command::new("secret-tool")
+        .args(["lookup", "service", SECRET_SERVICE, "account", account])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn platform_save(account: &str, secret: &str) -> Result<(), String> {
+    // secret-tool reads the secret from stdin so it never shows up in argv.
+    let mut child = Command::new("secret-tool")
+        .args([
+            "store",
+            "--label=Major Claw gateway",
+            "service",
+            SECRET_SERVICE,
+            "account",
+            account,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("failed to run secret-tool: {err}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(secret.as_bytes())
+            .map_err(|err| format!("failed to pass secret to secret-tool: {err}"))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("failed to wait for secret-tool: {err}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("secret-tool store failed: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_load(account: &str) -> Option<String> {
+    let output = Command::new("security")
+        .args(["find-generic-password", "-s", SECRET_SERVICE, "-a", account, "-w"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Runs `add-generic-password` through `security -i`, which reads the command from
+/// stdin, so the secret never appears in argv where `ps` would show it.
+#[cfg(target_os = "macos")]
+fn platform_save(account: &str, secret: &str) -> Result<(), String> {
+    let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let command = format!(
+        "add-generic-password -U -s {} -a {} -w {}\n",
+        quote(SECRET_SERVICE),
+        quote(account),
+        quote(secret)
+    );
+    let mut child = Command::new("security")
+        .arg("-i")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("failed to run security: {err}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(command.as_bytes())
+            .map_err(|err| format!("failed to pass secret to security: {err}"))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("failed to wait for security: {err}"))?;
+    // Interactive mode exits 0 even when a command fails; the failure is only on stderr.
+    if output.status.success() && output.stderr.iter().all(u8::is_ascii_whitespace) {
+        Ok(())
+    } else {
+        Err(format!(
+            "security add-generic-password failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn platform_load(_account: &str) -> Option<String> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn platform_save(_account: &str, _secret: &str) -> Result<(), String> {
+    Err("no platform secret store on this OS".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A store forced onto the file backend, in a fresh directory.
+    fn file_store(name: &str) -> (SecretStore, PathBuf) {
+        std::env::set_var("MAJORCLAW_SECRET_STORE", "file");
+        let dir = std::env::temp_dir().join(format!("major-claw-secret-store-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        (SecretStore::new(Some(dir.clone())), dir.join(SECRETS_DIR_NAME))
+    }
+
+    #[test]
+    fn file_backend_round_trips_overwrites_and_deletes() {
+        let (store, _) = file_store("round-trip");
+        assert_eq!(store.load("account"), None);
+        store.save("account", "first\n").unwrap();
+        assert_eq!(store.load("account").as_deref(), Some("first"));
+        store.save("account", "second").unwrap();
+        assert_eq!(store.load("account").as_deref(), Some("second"));
+        store.file_remove("account");
+        assert_eq!(store.load("account"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_backend_writes_owner_only_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (store, dir) = file_store("mode");
+        store.save("account", "secret").unwrap();
+        let mode = fs::metadata(dir.join("account")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::CommandError;
+use crate::gateway_profiles::DEFAULT_PROFILE;
+use crate::secret_store::SecretStore;
+
+/// Bytes of OS randomness behind a session token (hex-encoded to 64 characters).
+const SESSION_TOKEN_BYTES: usize = 32;
+/// Secret store account shared by the desktop host and the daemon units.
+const SESSION_TOKEN_ACCOUNT: &str = "gateway-session-token";
+
+/// Fresh token for the host/gateway boundary, drawn from the OS CSPRNG.
+pub fn generate_session_token() -> Result<String, CommandError> {
+    let mut bytes = [0u8; SESSION_TOKEN_BYTES];
+    getrandom::fill(&mut bytes)
+        .map_err(|err| CommandError::platform(format!("failed to read OS randomness for session token: {err}")))?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Secret store account holding a profile's token. The default local profile keeps
+/// the original account, which the daemon units read too.
+fn token_account(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        SESSION_TOKEN_ACCOUNT.to_string()
+    } else {
+        format!("{SESSION_TOKEN_ACCOUNT}.{profile}")
+    }
+}
+
+/// Token for the default local profile: an explicit `MAJORCLAW_GATEWAY_SESSION_TOKEN`
+/// wins (useful when pairing with a gateway started by hand), then the token kept in
+/// the secret store, so an always-on daemon started earlier still accepts us.
+/// Otherwise a new token is generated and stored. Other local profiles skip the env
+/// var; remote profiles only ever use the token saved for them.
+pub fn profile_session_token(store: &SecretStore, profile: &str) -> Result<String, CommandError> {
+    if profile == DEFAULT_PROFILE {
+        if let Ok(existing) = std::env::var("MAJORCLAW_GATEWAY_SESSION_TOKEN") {
+            if !existing.trim().is_empty() {
+                return Ok(existing.trim().to_string());
+            }
+        }
+    }
+    if let Some(stored) = stored_session_token(store, profile) {
+        return Ok(stored);
+    }
+    let generated = generate_session_token()?;
+    if let Err(error) = persist_session_token(store, profile, &generated) {
+        eprintln!("{error}; daemon mode will not share this token");
+    }
+    Ok(generated)
+}
+
+pub fn stored_session_token(store: &SecretStore, profile: &str) -> Option<String> {
+    store.load(&token_account(profile))
+}
+
+pub fn persist_session_token(store: &SecretStore, profile: &str, token: &str) -> Result<(), CommandError> {
+    store
+        .save(&token_account(profile), token)
+        .map_err(|err| CommandError::platform(format!("failed to store gateway session token: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_keep_their_tokens_under_separate_accounts() {
+        assert_eq!(token_account(DEFAULT_PROFILE), SESSION_TOKEN_ACCOUNT);
+        assert_eq!(token_account("work"), "gateway-session-token.work");
+
+        std::env::set_var("MAJORCLAW_SECRET_STORE", "file");
+        let dir = std::env::temp_dir().join(format!("major-claw-session-token-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = SecretStore::new(Some(dir.clone()));
+        persist_session_token(&store, DEFAULT_PROFILE, "local-token").unwrap();
+        persist_session_token(&store, "work", "work-token").unwrap();
+        assert_eq!(stored_session_token(&store, DEFAULT_PROFILE).as_deref(), Some("local-token"));
+        assert_eq!(stored_session_token(&store, "work").as_deref(), Some("work-token"));
+        assert_eq!(stored_session_token(&store, "other"), None);
+        assert!(dir.join("secrets").join("gateway-session-token.work").exists());
+    }
+
+    #[test]
+    fn generated_tokens_are_fresh_hex() {
+        let (first, second) = (generate_session_token().unwrap(), generate_session_token().unwrap());
+        assert_eq!(first.len(), SESSION_TOKEN_BYTES * 2);
+        assert!(first.chars().all(|ch| ch.is_ascii_hexdigit()));
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,87 @@
+/// One server-sent event as framed by the gateway's `text/event-stream` endpoints.
+pub struct SseEvent {
+    pub event: String,
+    pub data: String,
+}
+
+/// Incremental `text/event-stream` parser: feed it response chunks as they arrive
+/// and it returns every event completed so far. Only the `event` and `data`
+/// fields are kept; comments, `id` and `retry` lines are ignored.
+#[derive(Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        // Buffer raw bytes so a UTF-8 sequence split across chunks is decoded whole.
+        self.buffer.extend(chunk.iter().copied().filter(|byte| *byte != b'\r'));
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|pair| pair == b"\n\n") {
+            let block: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            let block = String::from_utf8_lossy(&block);
+            let mut event = String::from("message");
+            let mut data_lines = Vec::new();
+            for line in block.lines() {
+                if let Some(value) = line.strip_prefix("event:") {
+                    event = value.trim().to_string();
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    data_lines.push(value.strip_prefix(' ').unwrap_or(value));
+                }
+            }
+            if !data_lines.is_empty() {
+                events.push(SseEvent {
+                    event,
+                    data: data_lines.join("\n"),
+                });
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(events: Vec<SseEvent>) -> Vec<(String, String)> {
+        events.into_iter().map(|event| (event.event, event.data)).collect()
+    }
+
+    #[test]
+    fn frames_events_split_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.feed(b"event: chat.message\ndata: {\"a\"").is_empty());
+        let events = pairs(parser.feed(b":1}\n\nevent: health.alert\ndata: {}\n\n"));
+        assert_eq!(
+            events,
+            vec![
+                ("chat.message".to_string(), "{\"a\":1}".to_string()),
+                ("health.alert".to_string(), "{}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn joins_data_lines_and_defaults_the_event_name() {
+        let mut parser = SseParser::default();
+        let events = pairs(parser.feed(b"data: first\ndata:second\n\n"));
+        assert_eq!(events, vec![("message".to_string(), "first\nsecond".to_string())]);
+    }
+
+    #[test]
+    fn accepts_crlf_and_skips_comments_and_empty_blocks() {
+        let mut parser = SseParser::default();
+        let events = pairs(parser.feed(b": ping\r\n\r\nid: 7\r\nretry: 100\r\nevent: x\r\ndata: y\r\n\r\n"));
+        assert_eq!(events, vec![("x".to_string(), "y".to_string())]);
+    }
+
+    #[test]
+    fn decodes_utf8_split_across_chunks() {
+        let mut parser = SseParser::default();
+        let bytes = "data: héllo\n\n".as_bytes();
+        let split = bytes.iter().position(|byte| *byte == 0xc3).unwrap() + 1;
+        assert!(parser.feed(&bytes[..split]).is_empty());
+        assert_eq!(pairs(parser.feed(&bytes[split..])), vec![("message".to_string(), "héllo".to_string())]);
+    }
+}
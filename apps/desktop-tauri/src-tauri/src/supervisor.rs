@@ -0,0 +1,294 @@
+use crate::commands::{self, GatewayProcess};
+use crate::gateway_client::GatewayClient;
+use crate::host_telemetry::{iso8601, HostTelemetry};
+use crate::GatewayState;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// `pnpm dev:server` needs a while before `/health` answers; probes are skipped
+/// for this long after each spawn, whoever started it.
+const STARTUP_GRACE: Duration = Duration::from_secs(30);
+/// Consecutive failed `/health` probes before a running gateway is treated as hung.
+const HEALTH_FAILURE_THRESHOLD: u32 = 3;
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const BACKOFF_MAX: Duration = Duration::from_secs(120);
+/// Restarts inside `CRASH_LOOP_WINDOW` that open the circuit and stop respawning.
+const CRASH_LOOP_LIMIT: usize = 5;
+const CRASH_LOOP_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Serialize, Clone, Default)]
+pub struct SupervisorStatus {
+    #[serde(rename = "restartCount")]
+    restart_count: u32,
+    #[serde(rename = "consecutiveRestarts")]
+    consecutive_restarts: u32,
+    #[serde(rename = "healthFailures")]
+    health_failures: u32,
+    #[serde(rename = "lastExitCode")]
+    last_exit_code: Option<i32>,
+    #[serde(rename = "lastRestartReason")]
+    last_restart_reason: Option<String>,
+    #[serde(rename = "lastRestartAt")]
+    last_restart_at: Option<String>,
+    #[serde(rename = "nextAttemptAt")]
+    next_attempt_at: Option<String>,
+    #[serde(rename = "circuitOpen")]
+    circuit_open: bool,
+}
+
+#[derive(Default)]
+struct SupervisorInner {
+    status: SupervisorStatus,
+    recent_restarts: VecDeque<Instant>,
+    next_attempt: Option<Instant>,
+    /// Why the gateway went down, held while backoff delays the restart. By then the
+    /// exited child has been reaped and later polls only see it as missing.
+    pending: Option<RestartReason>,
+}
+
+/// Restart bookkeeping for the gateway watchdog, kept in Tauri managed state so
+/// `gateway_supervisor_status` can show it.
+#[derive(Default)]
+pub struct Supervisor {
+    inner: Mutex<SupervisorInner>,
+}
+
+impl Supervisor {
+    fn lock(&self) -> std::sync::MutexGuard<'_, SupervisorInner> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn status(&self) -> SupervisorStatus {
+        self.lock().status.clone()
+    }
+
+    /// Closes the circuit and clears backoff, e.g. when the user starts the gateway by hand.
+    pub fn reset(&self) {
+        let mut inner = self.lock();
+        let restart_count = inner.status.restart_count;
+        *inner = SupervisorInner::default();
+        inner.status.restart_count = restart_count;
+    }
+
+    fn record_healthy(&self) {
+        let mut inner = self.lock();
+        inner.status.health_failures = 0;
+        inner.status.consecutive_restarts = 0;
+        inner.status.next_attempt_at = None;
+        inner.next_attempt = None;
+        inner.pending = None;
+    }
+
+    fn record_health_failure(&self) -> u32 {
+        let mut inner = self.lock();
+        inner.status.health_failures += 1;
+        inner.status.health_failures
+    }
+
+    fn record_exit(&self, code: Option<i32>) {
+        self.lock().status.last_exit_code = code;
+    }
+
+    /// The reason to restart with, or `None` while backoff or an open circuit holds the
+    /// restart back. Meanwhile a reason other than `Missing` is kept for the restart
+    /// that follows.
+    fn ready_to_restart(&self, reason: RestartReason) -> Option<RestartReason> {
+        let mut inner = self.lock();
+        let reason = match inner.pending.take() {
+            Some(pending) if matches!(reason, RestartReason::Missing) => pending,
+            _ => reason,
+        };
+        if inner.status.circuit_open || inner.next_attempt.is_some_and(|at| Instant::now() < at) {
+            inner.pending = Some(reason);
+            return None;
+        }
+        Some(reason)
+    }
+
+    /// Books a restart and schedules the earliest next one. Returns `false` when
+    /// this restart trips the crash-loop breaker and should not be attempted.
+    fn begin_restart(&self, reason: &RestartReason) -> bool {
+        let mut inner = self.lock();
+        let now = Instant::now();
+        while inner
+            .recent_restarts
+            .front()
+            .is_some_and(|at| now.duration_since(*at) > CRASH_LOOP_WINDOW)
+        {
+            inner.recent_restarts.pop_front();
+        }
+        inner.status.last_restart_reason = Some(reason.code().to_string());
+        inner.status.health_failures = 0;
+        if inner.recent_restarts.len() >= CRASH_LOOP_LIMIT {
+            inner.status.circuit_open = true;
+            inner.status.next_attempt_at = None;
+            inner.next_attempt = None;
+            return false;
+        }
+        inner.recent_restarts.push_back(now);
+        inner.status.restart_count += 1;
+        inner.status.consecutive_restarts += 1;
+        inner.status.last_restart_at = Some(iso8601(SystemTime::now()));
+        let delay = backoff_delay(inner.status.consecutive_restarts);
+        inner.next_attempt = Some(now + delay);
+        inner.status.next_attempt_at = Some(iso8601(SystemTime::now() + delay));
+        true
+    }
+}
+
+/// Delay before the restart after this one: doubles per consecutive restart
+/// without a healthy probe in between, capped at `BACKOFF_MAX`.
+fn backoff_delay(consecutive_restarts: u32) -> Duration {
+    BACKOFF_BASE
+        .saturating_mul(1 << consecutive_restarts.saturating_sub(1).min(16))
+        .min(BACKOFF_MAX)
+}
+
+/// Keeps the owned gateway alive: respawns it when the process exits, and kills and
+/// respawns it when it stays up but stops answering `/health`. A gateway owned by the
+/// always-on service or started outside the app is attached to, never respawned. Restarts back off
+/// exponentially, and a crash loop opens a circuit that stops respawning until the
+/// user starts the gateway again. Every restart is recorded as a host health
+/// telemetry event with its reason.
+pub async fn run(app: AppHandle) {
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let state = app.state::<GatewayState>();
+        let client = app.state::<GatewayClient>();
+        let telemetry = app.state::<HostTelemetry>();
+        let supervisor = app.state::<Supervisor>();
+        if state.app_closing.load(Ordering::SeqCst) {
+            break;
+        }
+        if !state.desired_running.load(Ordering::SeqCst) || state.remote().is_some() {
+            continue;
+        }
+
+        let restart_reason = match commands::poll_gateway_process(state.inner()) {
+            Ok(GatewayProcess::Running(spawned_at)) => {
+                if spawned_at.is_some_and(|at| at.elapsed() < STARTUP_GRACE) {
+                    continue;
+                }
+                match client.health().await {
+                    Ok(_) => {
+                        supervisor.record_healthy();
+                        continue;
+                    }
+                    Err(error) => {
+                        let failures = supervisor.record_health_failure();
+                        if failures < HEALTH_FAILURE_THRESHOLD {
+                            continue;
+                        }
+                        let _ = commands::stop_gateway_process(
+                            state.inner(),
+                            client.inner(),
+                            "watchdog_health_failure",
+                            "watchdog",
+                            1_500,
+                            1_500,
+                        )
+                        .await;
+                        RestartReason::Unhealthy {
+                            failures,
+                            error: error.to_string(),
+                        }
+                    }
+                }
+            }
+            Ok(GatewayProcess::Exited(code)) => RestartReason::Exited { code },
+            // No child of ours: a gateway found already running (usually the always-on
+            // service) is used as is, and one the daemon owns is left to its service manager.
+            Ok(GatewayProcess::Missing) => match commands::attach_existing_gateway(state.inner(), client.inner())
+                .await
+            {
+                Ok(Some(_)) => {
+                    supervisor.record_healthy();
+                    continue;
+                }
+                Ok(None) if commands::daemon_owns_gateway(state.inner()) => continue,
+                Ok(None) => RestartReason::Missing,
+                Err(error) => {
+                    eprintln!("gateway watchdog could not attach to the running gateway: {error}");
+                    continue;
+                }
+            },
+            Err(error) => {
+                eprintln!("gateway watchdog could not inspect the gateway: {error}");
+                continue;
+            }
+        };
+
+        if let RestartReason::Exited { code } = &restart_reason {
+            supervisor.record_exit(*code);
+        }
+        let Some(restart_reason) = supervisor.ready_to_restart(restart_reason) else {
+            continue;
+        };
+        if !supervisor.begin_restart(&restart_reason) {
+            telemetry.record(
+                "critical",
+                "host.watchdog",
+                format!(
+                    "Gateway restarted {CRASH_LOOP_LIMIT} times within {} minutes; automatic restarts paused",
+                    CRASH_LOOP_WINDOW.as_secs() / 60
+                ),
+                serde_json::json!({ "reason": "crash_loop", "lastReason": restart_reason.code() }),
+            );
+            continue;
+        }
+        let outcome = commands::ensure_gateway(state.inner(), client.inner()).await;
+        if let Err(error) = &outcome {
+            eprintln!("gateway watchdog restart attempt failed: {error}");
+        }
+        restart_reason.record(telemetry.inner(), outcome.err().map(|error| error.to_string()));
+    }
+}
+
+enum RestartReason {
+    Exited { code: Option<i32> },
+    Unhealthy { failures: u32, error: String },
+    Missing,
+}
+
+impl RestartReason {
+    fn code(&self) -> &'static str {
+        match self {
+            RestartReason::Exited { .. } => "process_exited",
+            RestartReason::Unhealthy { .. } => "health_check_failed",
+            RestartReason::Missing => "not_running",
+        }
+    }
+
+    fn record(&self, telemetry: &HostTelemetry, spawn_error: Option<String>) {
+        let (severity, message, mut metadata) = match self {
+            RestartReason::Exited { code } => (
+                "critical",
+                match code {
+                    Some(code) => format!("Gateway exited with code {code}; restarting"),
+                    None => "Gateway was terminated by a signal; restarting".to_string(),
+                },
+                serde_json::json!({ "exitCode": code }),
+            ),
+            RestartReason::Unhealthy { failures, error } => (
+                "critical",
+                format!("Gateway failed {failures} health checks; killed and restarting"),
+                serde_json::json!({ "failures": failures, "lastError": error }),
+            ),
+            RestartReason::Missing => (
+                "warning",
+                "Gateway was not running; starting it".to_string(),
+                serde_json::json!({}),
+            ),
+        };
+        metadata["reason"] = serde_json::Value::String(self.code().to_string());
+        if let Some(error) = spawn_error {
+            metadata["spawnError"] = serde_json::Value::String(error);
+        }
+        telemetry.record(severity, "host.watchdog", message, metadata);
+    }
+}
@@ -0,0 +1,201 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Caps applied to the always-on gateway so a runaway agent cannot starve the
+/// desktop. Overridable with `MAJORCLAW_DAEMON_MEMORY_MAX` (systemd size syntax,
+/// e.g. `2G`) and `MAJORCLAW_DAEMON_CPU_QUOTA` (e.g. `150%`); an empty value drops
+/// the limit.
+#[derive(Debug, Clone)]
+pub struct ResourceLimits {
+    pub memory_max: Option<String>,
+    pub cpu_quota: Option<String>,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            memory_max: Some("1G".to_string()),
+            cpu_quota: Some("200%".to_string()),
+        }
+    }
+}
+
+impl ResourceLimits {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str, fallback: Option<String>| match std::env::var(name) {
+            Ok(value) if value.trim().is_empty() => None,
+            Ok(value) => Some(value.trim().to_string()),
+            Err(_) => fallback,
+        };
+        Self {
+            memory_max: read("MAJORCLAW_DAEMON_MEMORY_MAX", defaults.memory_max),
+            cpu_quota: read("MAJORCLAW_DAEMON_CPU_QUOTA", defaults.cpu_quota),
+        }
+    }
+}
+
+/// A systemd user service for the gateway.
+#[derive(Debug, Clone)]
+pub struct SystemdUnit {
+    pub description: String,
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub working_dir: PathBuf,
+    pub environment: BTreeMap<String, String>,
+    /// Owner-only file with the secret part of the environment, kept out of the unit.
+    pub environment_file: Option<PathBuf>,
+    pub limits: ResourceLimits,
+}
+
+impl SystemdUnit {
+    pub fn render(&self) -> String {
+        let exec_start = std::iter::once(self.program.to_string_lossy().to_string())
+            .chain(self.args.iter().cloned())
+            // Command lines expand `$VAR`; arguments are meant literally.
+            .map(|arg| quote(&arg.replace('$', "$$")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut service = vec![
+            "Type=simple".to_string(),
+            // Path settings take the value verbatim; only specifiers need escaping.
+            format!("WorkingDirectory={}", self.working_dir.to_string_lossy().replace('%', "%%")),
+            format!("ExecStart={exec_start}"),
+            "Restart=always".to_string(),
+            "RestartSec=3".to_string(),
+        ];
+        if let Some(environment_file) = &self.environment_file {
+            service.push(format!("EnvironmentFile={}", environment_file.to_string_lossy().replace('%', "%%")));
+        }
+        service.extend(
+            self.environment
+                .iter()
+                .map(|(key, value)| format!("Environment={}", quote(&format!("{key}={value}")))),
+        );
+        if let Some(memory_max) = self.limits.memory_max.as_deref() {
+            service.push(format!("MemoryMax={memory_max}"));
+        }
+        if let Some(cpu_quota) = self.limits.cpu_quota.as_deref() {
+            service.push(format!("CPUQuota={cpu_quota}"));
+        }
+        format!(
+            "[Unit]\nDescription={description}\nAfter=network.target\n\n[Service]\n{service}\n\n\
+             [Install]\nWantedBy=default.target\n",
+            description = self.description,
+            service = service.join("\n"),
+        )
+    }
+}
+
+/// Double-quotes a value for a unit file, escaping backslashes, quotes and `%`
+/// specifiers so systemd reads it back unchanged.
+fn quote(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"").replace('%', "%%");
+    format!("\"{escaped}\"")
+}
+
+/// Renders variables for `EnvironmentFile=`, which reads shell-like `KEY="value"`
+/// lines; no `%` specifiers apply there, but `\`, `"`, `$` and `` ` `` are escaped.
+pub fn render_environment_file(environment: &BTreeMap<String, String>) -> String {
+    environment
+        .iter()
+        .map(|(key, value)| {
+            let escaped = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('$', "\\$")
+                .replace('`', "\\`");
+            format!("{key}=\"{escaped}\"\n")
+        })
+        .collect()
+}
+
+/// Outcome of `systemd-analyze --user verify` on an installed unit.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UnitVerification {
+    Passed,
+    Failed { output: String },
+    /// `systemd-analyze` is not installed or could not run.
+    Skipped { reason: String },
+}
+
+pub fn verify(path: &Path) -> UnitVerification {
+    match Command::new("systemd-analyze").args(["--user", "verify"]).arg(path).output() {
+        Ok(output) if output.status.success() => UnitVerification::Passed,
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            UnitVerification::Failed {
+                output: format!("{}\n{}", stderr.trim(), stdout.trim()).trim().to_string(),
+            }
+        }
+        Err(error) => UnitVerification::Skipped {
+            reason: format!("systemd-analyze could not run: {error}"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit() -> SystemdUnit {
+        SystemdUnit {
+            description: "Major Claw Gateway".to_string(),
+            program: PathBuf::from("/opt/major claw/node"),
+            args: vec!["dist/server.js".to_string(), "--price=$5".to_string()],
+            working_dir: PathBuf::from("/opt/major claw/100%"),
+            environment: BTreeMap::from([
+                ("MAJORCLAW_GATEWAY_PORT".to_string(), "4455".to_string()),
+                ("QUOTED".to_string(), "say \"hi\" \\ 50%".to_string()),
+            ]),
+            environment_file: Some(PathBuf::from("/home/u/.major-claw/daemon/gateway-daemon.env")),
+            limits: ResourceLimits::default(),
+        }
+    }
+
+    #[test]
+    fn quote_escapes_backslashes_quotes_and_specifiers() {
+        assert_eq!(quote(r#"a "b" \c 10%"#), r#""a \"b\" \\c 10%%""#);
+    }
+
+    #[test]
+    fn render_quotes_exec_start_and_environment() {
+        let rendered = unit().render();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert!(lines.contains(&r#"ExecStart="/opt/major claw/node" "dist/server.js" "--price=$$5""#));
+        assert!(lines.contains(&"WorkingDirectory=/opt/major claw/100%%"));
+        assert!(lines.contains(&"EnvironmentFile=/home/u/.major-claw/daemon/gateway-daemon.env"));
+        assert!(lines.contains(&r#"Environment="MAJORCLAW_GATEWAY_PORT=4455""#));
+        assert!(lines.contains(&r#"Environment="QUOTED=say \"hi\" \\ 50%%""#));
+        assert!(lines.contains(&"MemoryMax=1G"));
+        assert!(lines.contains(&"CPUQuota=200%"));
+        assert!(lines.contains(&"WantedBy=default.target"));
+    }
+
+    #[test]
+    fn render_drops_limits_that_are_unset() {
+        let mut unit = unit();
+        unit.limits = ResourceLimits {
+            memory_max: None,
+            cpu_quota: None,
+        };
+        unit.environment_file = None;
+        let rendered = unit.render();
+        assert!(!rendered.contains("MemoryMax="));
+        assert!(!rendered.contains("CPUQuota="));
+        assert!(!rendered.contains("EnvironmentFile="));
+    }
+
+    #[test]
+    fn environment_file_escapes_shell_specials() {
+        let rendered = render_environment_file(&BTreeMap::from([(
+            "MAJORCLAW_GATEWAY_SESSION_TOKEN".to_string(),
+            r#"a"b\c$d`e"#.to_string(),
+        )]));
+        assert_eq!(rendered, "MAJORCLAW_GATEWAY_SESSION_TOKEN=\"a\\\"b\\\\c\\$d\\`e\"\n");
+    }
+}
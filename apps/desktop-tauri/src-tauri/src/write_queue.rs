@@ -0,0 +1,521 @@
+use crate::error::CommandError;
+use crate::gateway_client::{GatewayClient, GatewayError};
+use crate::gateway_profiles::DEFAULT_PROFILE;
+use crate::host_telemetry::iso8601;
+use crate::models::{TaskCreatePayload, TaskPatch, VaultDepositPayload, VaultEntryPatch};
+use crate::GatewayState;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::{AppHandle, Emitter, Manager};
+
+const QUEUE_FILE_NAME: &str = "write-queue";
+const CONFLICTS_FILE_NAME: &str = "write-conflicts";
+/// Emitted with `{ pending, conflicts }` whenever either list changes.
+pub const WRITE_QUEUE_EVENT: &str = "write-queue:changed";
+/// Replays of one write that may fail for a reason about the write itself before
+/// it is given up on and moved to the conflicts.
+const MAX_REPLAY_ATTEMPTS: u32 = 5;
+
+/// A mutation that can wait on disk for the gateway to come back.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WriteOp {
+    CreateTask {
+        payload: TaskCreatePayload,
+    },
+    UpdateTask {
+        #[serde(rename = "taskId")]
+        task_id: String,
+        patch: TaskPatch,
+    },
+    DeleteTask {
+        #[serde(rename = "taskId")]
+        task_id: String,
+    },
+    VaultDeposit {
+        payload: VaultDepositPayload,
+    },
+    VaultUpdateEntry {
+        #[serde(rename = "entryId")]
+        entry_id: String,
+        patch: VaultEntryPatch,
+    },
+}
+
+impl WriteOp {
+    async fn apply(&self, client: &GatewayClient, key: &str) -> Result<(), GatewayError> {
+        match self {
+            WriteOp::CreateTask { payload } => client.create_task(payload, key).await.map(drop),
+            WriteOp::UpdateTask { task_id, patch } => client.update_task(task_id, patch).await.map(drop),
+            WriteOp::DeleteTask { task_id } => client.delete_task(task_id).await,
+            WriteOp::VaultDeposit { payload } => client.vault_deposit(payload, key).await.map(drop),
+            WriteOp::VaultUpdateEntry { entry_id, patch } => client.vault_update_entry(entry_id, patch).await.map(drop),
+        }
+    }
+
+    /// Whether the request carries the idempotency key, so the gateway drops a repeat
+    /// of one it already applied.
+    fn sends_idempotency_key(&self) -> bool {
+        matches!(self, WriteOp::CreateTask { .. } | WriteOp::VaultDeposit { .. })
+    }
+
+    /// Whether trying this write again after `error` is safe and may succeed. A write
+    /// that never reached the gateway always is; after a timeout or a dropped
+    /// connection the gateway may already have applied it, so only a keyed write is.
+    fn retryable(&self, error: &GatewayError) -> bool {
+        match error {
+            GatewayError::Request { .. } if unreachable(error) => true,
+            GatewayError::Request { .. } => self.sends_idempotency_key(),
+            GatewayError::Status { status, .. } => {
+                status.is_server_error() || matches!(status.as_u16(), 401 | 408 | 429)
+            }
+            GatewayError::Decode { .. } | GatewayError::Build { .. } | GatewayError::Unavailable { .. } => false,
+        }
+    }
+}
+
+/// The request never reached the gateway, so nothing was applied.
+fn unreachable(error: &GatewayError) -> bool {
+    matches!(error, GatewayError::Request { source, .. } if source.is_connect())
+}
+
+/// The gateway is not taking writes right now: it is unreachable, refuses the session
+/// token, or is rate limiting. None of that is about the write, so replay stops
+/// without counting an attempt against it.
+fn gateway_unavailable(error: &GatewayError) -> bool {
+    unreachable(error) || matches!(error, GatewayError::Status { status, .. } if matches!(status.as_u16(), 401 | 429))
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QueuedWrite {
+    id: String,
+    op: WriteOp,
+    /// Sent on every replay so a create the gateway already applied is not duplicated.
+    #[serde(rename = "idempotencyKey")]
+    idempotency_key: String,
+    #[serde(rename = "queuedAt")]
+    queued_at: String,
+    /// Replays that failed while the gateway was reachable.
+    #[serde(default)]
+    attempts: u32,
+}
+
+/// A queued write replay gave up on: the gateway rejected it, e.g. an update to a
+/// task that was deleted in the meantime, or it kept failing in a way retrying will
+/// not fix. It is dropped from the queue and kept here, the dead-letter list, until
+/// the user dismisses it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WriteConflict {
+    id: String,
+    op: WriteOp,
+    #[serde(rename = "queuedAt")]
+    queued_at: String,
+    #[serde(rename = "failedAt")]
+    failed_at: String,
+    /// Status the gateway rejected it with; `None` when it failed another way.
+    status: Option<u16>,
+    message: String,
+}
+
+/// What a queueable command did: applied now, or parked until the gateway is back.
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum WriteOutcome<T> {
+    Applied {
+        result: T,
+    },
+    Queued {
+        #[serde(rename = "queueId")]
+        queue_id: String,
+    },
+}
+
+#[derive(Serialize, Clone)]
+struct WriteQueueCounts {
+    pending: usize,
+    conflicts: usize,
+}
+
+/// The lists of one profile; `lock` swaps them for another profile's on demand.
+#[derive(Default)]
+struct QueueState {
+    profile: Option<String>,
+    pending: Vec<QueuedWrite>,
+    conflicts: Vec<WriteConflict>,
+}
+
+/// Task and vault writes made while the gateway is unreachable, persisted in the
+/// app data dir and replayed in order once it answers again. Each gateway profile
+/// has its own queue, and only the active profile's is replayed.
+pub struct WriteQueue {
+    dir: Option<PathBuf>,
+    state: Mutex<QueueState>,
+    /// Held for a whole replay so two reconnects never replay the same write twice.
+    replaying: tokio::sync::Mutex<()>,
+}
+
+impl WriteQueue {
+    pub fn new(data_dir: Option<PathBuf>) -> Self {
+        Self {
+            dir: data_dir,
+            state: Mutex::new(QueueState::default()),
+            replaying: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// The lists of `profile`, read from disk if another profile's were loaded. Every
+    /// change is persisted as it is made, so nothing is lost by swapping them out.
+    fn lock(&self, profile: &str) -> std::sync::MutexGuard<'_, QueueState> {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.profile.as_deref() != Some(profile) {
+            *state = QueueState {
+                profile: Some(profile.to_string()),
+                pending: read_list(self.dir.as_deref(), &file_name(QUEUE_FILE_NAME, profile)),
+                conflicts: read_list(self.dir.as_deref(), &file_name(CONFLICTS_FILE_NAME, profile)),
+            };
+        }
+        state
+    }
+
+    pub fn pending(&self, profile: &str) -> Vec<QueuedWrite> {
+        self.lock(profile).pending.clone()
+    }
+
+    pub fn conflicts(&self, profile: &str) -> Vec<WriteConflict> {
+        self.lock(profile).conflicts.clone()
+    }
+
+    /// Awaits `apply` now, or queues `op` when the gateway is unreachable, or, for a
+    /// write that sends its idempotency key, when the request failed in transit. Writes
+    /// made while older ones are still queued are queued behind them to keep their
+    /// order. `key` is the idempotency key `apply` was built with and is reused on replay.
+    pub async fn submit<T>(
+        &self,
+        app: &AppHandle,
+        op: WriteOp,
+        key: String,
+        apply: impl Future<Output = Result<T, GatewayError>>,
+    ) -> Result<WriteOutcome<T>, CommandError> {
+        let profile = active_profile(app);
+        if self.lock(&profile).pending.is_empty() {
+            match apply.await {
+                Ok(result) => return Ok(WriteOutcome::Applied { result }),
+                Err(error @ GatewayError::Request { .. }) if !unreachable(&error) && !op.sends_idempotency_key() => {
+                    return Err(CommandError::from(error));
+                }
+                Err(GatewayError::Request { .. }) => {}
+                Err(error) => return Err(CommandError::from(error)),
+            }
+        }
+        let queued = QueuedWrite {
+            id: format!("write_{key}"),
+            op,
+            idempotency_key: key,
+            queued_at: iso8601(SystemTime::now()),
+            attempts: 0,
+        };
+        let queue_id = queued.id.clone();
+        {
+            let mut state = self.lock(&profile);
+            state.pending.push(queued);
+            self.persist(&state)?;
+        }
+        self.notify(app, &profile);
+        // The gateway may already be back, in which case this drains right away.
+        tauri::async_runtime::spawn(replay(app.clone()));
+        Ok(WriteOutcome::Queued { queue_id })
+    }
+
+    pub fn dismiss_conflict(&self, app: &AppHandle, conflict_id: Option<&str>) -> Result<usize, CommandError> {
+        let profile = active_profile(app);
+        let removed = {
+            let mut state = self.lock(&profile);
+            let before = state.conflicts.len();
+            match conflict_id {
+                Some(id) => state.conflicts.retain(|conflict| conflict.id != id),
+                None => state.conflicts.clear(),
+            }
+            let removed = before - state.conflicts.len();
+            self.persist(&state)?;
+            removed
+        };
+        self.notify(app, &profile);
+        Ok(removed)
+    }
+
+    fn persist(&self, state: &QueueState) -> Result<(), CommandError> {
+        let (Some(dir), Some(profile)) = (self.dir.as_ref(), state.profile.as_deref()) else {
+            return Ok(());
+        };
+        write_list(dir, &file_name(QUEUE_FILE_NAME, profile), &state.pending)?;
+        write_list(dir, &file_name(CONFLICTS_FILE_NAME, profile), &state.conflicts)
+    }
+
+    fn notify(&self, app: &AppHandle, profile: &str) {
+        let counts = {
+            let state = self.lock(profile);
+            WriteQueueCounts {
+                pending: state.pending.len(),
+                conflicts: state.conflicts.len(),
+            }
+        };
+        let _ = app.emit(WRITE_QUEUE_EVENT, counts);
+    }
+}
+
+/// Replays queued writes oldest first until the queue is empty or the gateway
+/// stops taking writes (see `gateway_unavailable`). Another failure retrying could
+/// fix (see `WriteOp::retryable`) leaves the write queued for next time, up to
+/// `MAX_REPLAY_ATTEMPTS`; any other failure moves it to the conflicts and replay moves on, so
+/// one bad write never blocks the ones behind it. Only the active profile's queue
+/// is replayed, and replay stops if another profile becomes active meanwhile.
+/// Returns how many were applied.
+pub async fn replay(app: AppHandle) -> usize {
+    let queue = app.state::<WriteQueue>();
+    let client = app.state::<GatewayClient>().inner();
+    let _replaying = queue.replaying.lock().await;
+    let profile = active_profile(&app);
+    // The counts shown may belong to the profile active before a switch.
+    queue.notify(&app, &profile);
+    queue
+        .drain(
+            &profile,
+            |write| async move { write.op.apply(client, &write.idempotency_key).await },
+            || active_profile(&app) == profile,
+            || queue.notify(&app, &profile),
+        )
+        .await
+}
+
+impl WriteQueue {
+    /// The replay loop: sends `profile`'s writes through `apply` in order while
+    /// `still_active` holds, calling `changed` after each one leaves the queue.
+    async fn drain<F, Fut>(
+        &self,
+        profile: &str,
+        mut apply: F,
+        still_active: impl Fn() -> bool,
+        changed: impl Fn(),
+    ) -> usize
+    where
+        F: FnMut(QueuedWrite) -> Fut,
+        Fut: Future<Output = Result<(), GatewayError>>,
+    {
+        let mut applied = 0;
+        while still_active() {
+            let Some(next) = self.lock(profile).pending.first().cloned() else {
+                break;
+            };
+            let conflict = match apply(next.clone()).await {
+                Ok(()) => None,
+                Err(error) if gateway_unavailable(&error) => break,
+                Err(error) if next.op.retryable(&error) && next.attempts + 1 < MAX_REPLAY_ATTEMPTS => {
+                    let mut state = self.lock(profile);
+                    if let Some(write) = state.pending.iter_mut().find(|write| write.id == next.id) {
+                        write.attempts += 1;
+                    }
+                    if let Err(error) = self.persist(&state) {
+                        eprintln!("failed to persist the write queue: {error}");
+                    }
+                    break;
+                }
+                Err(error) => {
+                    let (status, message) = match &error {
+                        GatewayError::Status { status, body, .. } => (Some(status.as_u16()), body.clone()),
+                        other => (None, other.to_string()),
+                    };
+                    Some(WriteConflict {
+                        id: next.id.clone(),
+                        op: next.op.clone(),
+                        queued_at: next.queued_at.clone(),
+                        failed_at: iso8601(SystemTime::now()),
+                        status,
+                        message,
+                    })
+                }
+            };
+            {
+                let mut state = self.lock(profile);
+                state.pending.retain(|write| write.id != next.id);
+                match conflict {
+                    Some(conflict) => state.conflicts.push(conflict),
+                    None => applied += 1,
+                }
+                if let Err(error) = self.persist(&state) {
+                    eprintln!("failed to persist the write queue: {error}");
+                }
+            }
+            changed();
+        }
+        applied
+    }
+}
+
+fn active_profile(app: &AppHandle) -> String {
+    app.state::<GatewayState>().active().name.clone()
+}
+
+/// `<base>.json` for the default profile, which kept its queue there before queues
+/// were per profile, and `<base>.<profile>.json` for the others.
+fn file_name(base: &str, profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        format!("{base}.json")
+    } else {
+        format!("{base}.{profile}.json")
+    }
+}
+
+fn read_list<T: DeserializeOwned>(dir: Option<&Path>, name: &str) -> Vec<T> {
+    dir.and_then(|dir| fs::read(dir.join(name)).ok())
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_list<T: Serialize>(dir: &Path, name: &str, items: &[T]) -> Result<(), CommandError> {
+    let bytes = serde_json::to_vec_pretty(items)
+        .map_err(|error| CommandError::platform(format!("failed to encode {name}: {error}")))?;
+    fs::create_dir_all(dir)
+        .map_err(|error| CommandError::platform(format!("failed to create {}: {error}", dir.display())))?;
+    let path = dir.join(name);
+    let partial = path.with_extension("json.tmp");
+    fs::write(&partial, bytes)
+        .and_then(|()| fs::rename(&partial, &path))
+        .map_err(|error| CommandError::platform(format!("failed to write {}: {error}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    fn delete(id: &str, attempts: u32) -> QueuedWrite {
+        QueuedWrite {
+            id: id.to_string(),
+            op: WriteOp::DeleteTask { task_id: format!("task-{id}") },
+            idempotency_key: format!("key-{id}"),
+            queued_at: "2026-01-01T00:00:00Z".to_string(),
+            attempts,
+        }
+    }
+
+    fn queue(writes: Vec<QueuedWrite>) -> WriteQueue {
+        let queue = WriteQueue::new(None);
+        queue.lock(DEFAULT_PROFILE).pending = writes;
+        queue
+    }
+
+    fn status(code: u16) -> GatewayError {
+        GatewayError::Status {
+            context: "test",
+            status: reqwest::StatusCode::from_u16(code).unwrap(),
+            body: format!("status {code}"),
+        }
+    }
+
+    async fn connect_error() -> GatewayError {
+        let source = reqwest::Client::new().get("http://127.0.0.1:1/").send().await.unwrap_err();
+        GatewayError::Request { context: "test", source }
+    }
+
+    /// Drains the default profile, failing the writes in `failures` with the given
+    /// status (0 for a refused connection). Returns the applied count and the ids tried.
+    fn drain(queue: &WriteQueue, failures: &[(&str, u16)]) -> (usize, Vec<String>) {
+        let tried = RefCell::new(Vec::new());
+        let applied = tauri::async_runtime::block_on(queue.drain(
+            DEFAULT_PROFILE,
+            |write| {
+                tried.borrow_mut().push(write.id.clone());
+                let failure = failures.iter().find(|(id, _)| *id == write.id).map(|(_, code)| *code);
+                async move {
+                    match failure {
+                        None => Ok(()),
+                        Some(0) => Err(connect_error().await),
+                        Some(code) => Err(status(code)),
+                    }
+                }
+            },
+            || true,
+            || {},
+        ));
+        (applied, tried.into_inner())
+    }
+
+    fn ids<'a>(ids: impl IntoIterator<Item = &'a String>) -> Vec<&'a str> {
+        ids.into_iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn replays_in_order_and_dead_letters_rejected_writes() {
+        let queue = queue(vec![delete("a", 0), delete("b", 0), delete("c", 0)]);
+        let (applied, tried) = drain(&queue, &[("b", 404)]);
+        assert_eq!((applied, ids(&tried)), (2, vec!["a", "b", "c"]));
+        let state = queue.lock(DEFAULT_PROFILE);
+        assert!(state.pending.is_empty());
+        assert_eq!(ids(state.conflicts.iter().map(|c| &c.id)), vec!["b"]);
+        assert_eq!(state.conflicts[0].status, Some(404));
+        assert_eq!(state.conflicts[0].message, "status 404");
+    }
+
+    #[test]
+    fn an_unreachable_gateway_stops_the_replay_and_keeps_the_order() {
+        let queue = queue(vec![delete("a", 0), delete("b", 0), delete("c", 0)]);
+        let (applied, tried) = drain(&queue, &[("b", 0)]);
+        assert_eq!((applied, ids(&tried)), (1, vec!["a", "b"]));
+        let state = queue.lock(DEFAULT_PROFILE);
+        assert_eq!(ids(state.pending.iter().map(|w| &w.id)), vec!["b", "c"]);
+        assert_eq!(state.pending[0].attempts, 0);
+        assert!(state.conflicts.is_empty());
+    }
+
+    #[test]
+    fn retryable_failures_count_attempts_before_giving_up() {
+        let queue = queue(vec![delete("a", 0), delete("b", 0)]);
+        assert_eq!(drain(&queue, &[("a", 503)]), (0, vec!["a".to_string()]));
+        assert_eq!(queue.lock(DEFAULT_PROFILE).pending[0].attempts, 1);
+
+        queue.lock(DEFAULT_PROFILE).pending[0].attempts = MAX_REPLAY_ATTEMPTS - 1;
+        let (applied, tried) = drain(&queue, &[("a", 503)]);
+        assert_eq!((applied, ids(&tried)), (1, vec!["a", "b"]));
+        let state = queue.lock(DEFAULT_PROFILE);
+        assert!(state.pending.is_empty());
+        assert_eq!(state.conflicts[0].status, Some(503));
+    }
+
+    #[test]
+    fn auth_and_rate_limit_failures_stop_the_replay_without_counting() {
+        let queue = queue(vec![delete("a", MAX_REPLAY_ATTEMPTS - 1), delete("b", 0)]);
+        for code in [401, 429] {
+            assert_eq!(drain(&queue, &[("a", code)]), (0, vec!["a".to_string()]));
+            let state = queue.lock(DEFAULT_PROFILE);
+            assert_eq!(state.pending[0].attempts, MAX_REPLAY_ATTEMPTS - 1);
+            assert!(state.conflicts.is_empty());
+        }
+    }
+
+    #[test]
+    fn replay_stops_once_the_profile_is_no_longer_active() {
+        let queue = queue(vec![delete("a", 0), delete("b", 0)]);
+        let changes = Cell::new(0);
+        let applied = tauri::async_runtime::block_on(queue.drain(
+            DEFAULT_PROFILE,
+            |_| async { Ok(()) },
+            || changes.get() < 1,
+            || changes.set(changes.get() + 1),
+        ));
+        assert_eq!(applied, 1);
+        assert_eq!(ids(queue.lock(DEFAULT_PROFILE).pending.iter().map(|w| &w.id)), vec!["b"]);
+    }
+
+    #[test]
+    fn retries_server_rate_limit_and_connect_failures_only() {
+        assert!(!delete("a", 0).op.sends_idempotency_key());
+        assert!(delete("a", 0).op.retryable(&status(429)));
+        assert!(!delete("a", 0).op.retryable(&status(409)));
+        assert!(tauri::async_runtime::block_on(async { delete("a", 0).op.retryable(&connect_error().await) }));
+    }
+}
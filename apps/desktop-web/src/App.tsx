@@ -66,6 +66,7 @@ import {
   updateAgentConfig,
   updateTask,
   deleteTask,
+  isQueuedWrite,
   toggleSkill,
   type AgentBudget,
   type CheckpointRecord,
@@ -1211,7 +1212,8 @@ export default function App() {
     if (!created) {
       return;
     }
-    if (created && input.blobPath) {
+    // A deposit saved offline has no entry yet to attach the file version to.
+    if (!isQueuedWrite(created) && input.blobPath) {
       const versionInput: {
         diff: string;
         markdownSummary: string;
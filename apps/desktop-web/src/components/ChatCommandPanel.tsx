@@ -1,7 +1,15 @@
 import { useEffect, useMemo, useRef, useState } from "react";
 import type { AgentProfile } from "@majorclaw/shared-types";
 import type { SwarmChatMessage, SwarmSummary } from "../tauriGateway.js";
-import { chatMessages, chatQuickAction, chatSend, chatSummary, chatThreads, gatewaySessionToken } from "../tauriGateway.js";
+import {
+  chatMessages,
+  chatQuickAction,
+  chatSend,
+  chatSummary,
+  chatThreads,
+  gatewayBaseUrl,
+  gatewaySessionToken
+} from "../tauriGateway.js";
 import { emitAppError } from "../utils/errorBus.js";
 import { normalizeError } from "../utils/errorMapper.js";
 
@@ -75,13 +83,15 @@ export function ChatCommandPanel({
   const [showRecallContext, setShowRecallContext] = useState(true);
   const [collapsedParents, setCollapsedParents] = useState<Record<string, boolean>>({});
   const [sessionToken, setSessionToken] = useState<string | null>(null);
+  const [baseUrl, setBaseUrl] = useState<string | null>(null);
   const listRef = useRef<HTMLDivElement>(null);
   const lastStreamErrorAtRef = useRef(0);
 
   useEffect(() => {
     const boot = async () => {
-      const token = await gatewaySessionToken();
+      const [token, resolvedBaseUrl] = await Promise.all([gatewaySessionToken(), gatewayBaseUrl()]);
       setSessionToken(token);
+      setBaseUrl(resolvedBaseUrl);
       const threads = await chatThreads();
       const selected = threads[0]?.id ?? "thread_cso_default";
       setThreadId(selected);
@@ -93,8 +103,11 @@ export function ChatCommandPanel({
   }, []);
 
   useEffect(() => {
+    if (!baseUrl) {
+      return;
+    }
     const tokenQuery = sessionToken ? `&token=${encodeURIComponent(sessionToken)}` : "";
-    const stream = new EventSource(`http://127.0.0.1:4455/chat/stream?threadId=${encodeURIComponent(threadId)}${tokenQuery}`);
+    const stream = new EventSource(`${baseUrl}/chat/stream?threadId=${encodeURIComponent(threadId)}${tokenQuery}`);
     stream.addEventListener("messages", (event) => {
       const payload = JSON.parse((event as MessageEvent<string>).data) as SwarmChatMessage[];
       setMessages((current) => mergeMessages(current, payload));
@@ -121,7 +134,7 @@ export function ChatCommandPanel({
     return () => {
       stream.close();
     };
-  }, [threadId, sessionToken]);
+  }, [threadId, sessionToken, baseUrl]);
 
   useEffect(() => {
     if (listRef.current) {
@@ -1,5 +1,5 @@
 import { useEffect, useMemo, useRef, useState } from "react";
-import { gatewaySessionToken, type HealthSnapshot, type HealthTelemetryEvent } from "../tauriGateway.js";
+import { gatewayBaseUrl, gatewaySessionToken, type HealthSnapshot, type HealthTelemetryEvent } from "../tauriGateway.js";
 
 type StreamState = {
   connected: boolean;
@@ -25,11 +25,11 @@ export function useTelemetryStream(enabled: boolean, maxEvents = 160): StreamSta
     let disposed = false;
 
     const start = async () => {
-      const token = await gatewaySessionToken();
+      const [token, baseUrl] = await Promise.all([gatewaySessionToken(), gatewayBaseUrl()]);
       if (disposed) {
         return;
       }
-      const targetUrl = new URL("http://127.0.0.1:4455/telemetry/stream");
+      const targetUrl = new URL(`${baseUrl}/telemetry/stream`);
       if (token) {
         targetUrl.searchParams.set("token", token);
       }
@@ -6,13 +6,22 @@ import { normalizeError } from "./utils/errorMapper.js";
 
 type GatewayStatus = {
   running: boolean;
+  host: string;
   port: number;
+  mode: "local" | "remote";
+  owner: "app" | "daemon" | "external" | "remote" | "none";
+  profile: string;
+  baseUrl: string;
 };
 
+const defaultGatewayBaseUrl = "http://127.0.0.1:4455";
+
 type GatewayHealth = {
   status: string;
   startedAt?: string;
   instanceCount?: number;
+  owner?: "app" | "daemon" | "external";
+  pid?: number;
 };
 
 export type GatewayDaemonStatus = {
@@ -25,6 +34,41 @@ export type GatewayDaemonStatus = {
   logHint: string;
   lastError?: string;
   message: string;
+  /** `systemd-analyze verify` on the unit written by the last Linux install. */
+  unitVerification?:
+    | { status: "passed" }
+    | { status: "failed"; output: string }
+    | { status: "skipped"; reason: string };
+  install?: {
+    action: "installed" | "upgraded" | "unchanged";
+    path: string;
+    backupPath: string | null;
+    removed: string[];
+    added: string[];
+    changedSecrets: string[];
+    dryRun: boolean;
+  };
+  backend: "launchd" | "systemd" | "xdg_autostart" | "pidfile" | "windows_service" | null;
+  unsupportedReason: string | null;
+};
+
+export type GatewaySupervisorStatus = {
+  restartCount: number;
+  consecutiveRestarts: number;
+  healthFailures: number;
+  lastExitCode: number | null;
+  lastRestartReason: string | null;
+  lastRestartAt: string | null;
+  nextAttemptAt: string | null;
+  circuitOpen: boolean;
+};
+
+export type GatewayLogLine = {
+  seq: number;
+  stream: "stdout" | "stderr";
+  level: string;
+  message: string;
+  timestamp: string;
 };
 
 export type RedPhoneResult = {
@@ -379,6 +423,10 @@ export type GatewayErrorEnvelope = {
 
 function emitInvokeError(error: unknown, context: string, retry?: () => void): void {
   const normalized = normalizeError(error, context);
+  // The user asked for this one to stop; there is nothing to report.
+  if (normalized.code === "Cancelled") {
+    return;
+  }
   const detail: { error: ErrorResponse; context: string; retry?: () => void } = {
     error: normalized,
     context
@@ -389,6 +437,58 @@ function emitInvokeError(error: unknown, context: string, retry?: () => void): v
   emitAppError(detail);
 }
 
+type CachedRead<T> = {
+  data: T;
+  stale: boolean;
+  cachedAt: string | null;
+};
+
+export type StaleRead = {
+  command: string;
+  cachedAt: string | null;
+};
+
+const staleReadListeners = new Set<(read: StaleRead) => void>();
+
+/** Called whenever a list command is answered from the host's offline cache. */
+export function onStaleRead(listener: (read: StaleRead) => void): () => void {
+  staleReadListeners.add(listener);
+  return () => {
+    staleReadListeners.delete(listener);
+  };
+}
+
+async function invokeCached<T>(command: string, args?: Record<string, unknown>): Promise<T | null> {
+  const result = await invokeTauri<CachedRead<T>>(command, args);
+  if (!result) {
+    return null;
+  }
+  if (result.stale) {
+    for (const listener of staleReadListeners) {
+      listener({ command, cachedAt: result.cachedAt });
+    }
+  }
+  return result.data;
+}
+
+type WriteOutcome<T> = { status: "applied"; result: T } | { status: "queued"; queueId: string };
+
+/** A write the host saved offline; it is replayed once the gateway is back. */
+export type QueuedWriteResult = { queued: true; queueId: string };
+
+export function isQueuedWrite(value: unknown): value is QueuedWriteResult {
+  return typeof value === "object" && value !== null && (value as { queued?: unknown }).queued === true;
+}
+
+/** `result` once applied, a `QueuedWriteResult` when the host parked the write, `null` on failure. */
+async function invokeWrite<T>(command: string, args?: Record<string, unknown>): Promise<T | QueuedWriteResult | null> {
+  const outcome = await invokeTauri<WriteOutcome<T>>(command, args);
+  if (!outcome) {
+    return null;
+  }
+  return outcome.status === "applied" ? outcome.result : { queued: true, queueId: outcome.queueId };
+}
+
 async function invokeTauri<T>(command: string, args?: Record<string, unknown>): Promise<T | null> {
   try {
     const core = await import("@tauri-apps/api/core");
@@ -419,6 +519,10 @@ export async function setGatewayDaemonEnabled(enabled: boolean): Promise<Gateway
   return invokeTauri<GatewayDaemonStatus>("gateway_daemon_set_enabled", { enabled });
 }
 
+export async function installOrUpgradeGatewayDaemon(dryRun = false): Promise<GatewayDaemonStatus | null> {
+  return invokeTauri<GatewayDaemonStatus>("gateway_daemon_install_or_upgrade", { dry_run: dryRun });
+}
+
 export async function startGatewayDaemon(): Promise<GatewayDaemonStatus | null> {
   return invokeTauri<GatewayDaemonStatus>("gateway_daemon_start");
 }
@@ -431,6 +535,18 @@ export async function restartGatewayDaemon(): Promise<GatewayDaemonStatus | null
   return invokeTauri<GatewayDaemonStatus>("gateway_daemon_restart");
 }
 
+export type DaemonLogLine = {
+  timestamp: string | null;
+  severity: "debug" | "info" | "warn" | "error";
+  message: string;
+  source: string;
+};
+
+/** Always-on gateway output, oldest first; `since` is epoch milliseconds. */
+export async function getGatewayDaemonLogs(lines?: number, since?: number): Promise<DaemonLogLine[] | null> {
+  return invokeTauri<DaemonLogLine[]>("gateway_daemon_logs", { lines: lines ?? null, since: since ?? null });
+}
+
 export async function openOfficialIntegrations(fragment?: string): Promise<boolean> {
   try {
     const core = await import("@tauri-apps/api/core");
@@ -466,20 +582,142 @@ export async function getGatewayStatus(): Promise<GatewayStatus | null> {
   return invokeTauri<GatewayStatus>("gateway_status");
 }
 
+export type PreflightStatus = "pass" | "warn" | "fail";
+
+export type GatewayPreflightCheck = {
+  id: string;
+  label: string;
+  status: PreflightStatus;
+  detail: string;
+  hint?: string;
+};
+
+export type GatewayPreflightReport = {
+  status: PreflightStatus;
+  checks: GatewayPreflightCheck[];
+  checkedAt: string;
+};
+
+/** Checks runtime, workspace/bundle, port, vault dir and systemd before (or after a failed) gateway start. */
+export async function gatewayPreflight(): Promise<GatewayPreflightReport | null> {
+  return invokeTauri<GatewayPreflightReport>("gateway_preflight");
+}
+
+export async function getGatewaySupervisorStatus(): Promise<GatewaySupervisorStatus | null> {
+  return invokeTauri<GatewaySupervisorStatus>("gateway_supervisor_status");
+}
+
+export async function getGatewayProcessLogs(limit = 200, level?: string): Promise<GatewayLogLine[]> {
+  return (await invokeTauri<GatewayLogLine[]>("gateway_process_logs", { limit, level })) ?? [];
+}
+
+export type GatewayPushEvent =
+  | "chat:message"
+  | "permission:pending"
+  | "agent:heartbeat"
+  | "health:alert"
+  | "gateway:stream"
+  | "gateway:log"
+  | "write-queue:changed";
+
+export async function subscribeGatewayEvent<T>(name: GatewayPushEvent, onEvent: (payload: T) => void): Promise<() => void> {
+  try {
+    const event = await import("@tauri-apps/api/event");
+    return await event.listen<T>(name, (message) => onEvent(message.payload));
+  } catch {
+    return () => undefined;
+  }
+}
+
+export async function subscribeGatewayLogs(onLine: (line: GatewayLogLine) => void): Promise<() => void> {
+  return subscribeGatewayEvent<GatewayLogLine>("gateway:log", onLine);
+}
+
+export async function gatewayBaseUrl(): Promise<string> {
+  const status = await getGatewayStatus();
+  return status?.baseUrl ?? defaultGatewayBaseUrl;
+}
+
+export type RemoteGatewayConfig = {
+  baseUrl: string;
+  caCertPath?: string | null;
+  clientCertPath?: string | null;
+};
+
+export async function getRemoteGatewayConfig(): Promise<RemoteGatewayConfig | null> {
+  return invokeTauri<RemoteGatewayConfig>("remote_gateway_config");
+}
+
+/**
+ * Saves a gateway on another machine as a profile (`remote` unless named) and switches to it;
+ * local spawning and the watchdog turn off.
+ */
+export async function connectRemoteGateway(
+  config: RemoteGatewayConfig,
+  token: string,
+  name?: string
+): Promise<GatewayStatus | null> {
+  return invokeTauri<GatewayStatus>("connect_remote_gateway", {
+    config: {
+      baseUrl: config.baseUrl,
+      caCertPath: config.caCertPath ?? null,
+      clientCertPath: config.clientCertPath ?? null
+    },
+    token,
+    name: name ?? null
+  });
+}
+
+/** Switches back to the default local profile. */
+export async function disconnectRemoteGateway(): Promise<GatewayStatus | null> {
+  return invokeTauri<GatewayStatus>("disconnect_remote_gateway");
+}
+
+export type GatewayProfile =
+  | { name: string; kind: "local"; host: string; port: number }
+  | ({ name: string; kind: "remote" } & RemoteGatewayConfig);
+
+export type GatewayProfiles = {
+  active: string;
+  profiles: GatewayProfile[];
+};
+
+export async function listGatewayProfiles(): Promise<GatewayProfiles | null> {
+  return invokeTauri<GatewayProfiles>("list_gateway_profiles");
+}
+
+/** Adds or replaces a profile without switching; remote profiles need a token the first time. */
+export async function saveGatewayProfile(profile: GatewayProfile, token?: string): Promise<GatewayProfiles | null> {
+  return invokeTauri<GatewayProfiles>("save_gateway_profile", { profile, token: token ?? null });
+}
+
+export async function deleteGatewayProfile(name: string): Promise<GatewayProfiles | null> {
+  return invokeTauri<GatewayProfiles>("delete_gateway_profile", { name });
+}
+
+/** Stops the previous profile's local gateway and connects to (or spawns) the named one. */
+export async function switchGatewayProfile(name: string): Promise<GatewayStatus | null> {
+  return invokeTauri<GatewayStatus>("switch_gateway_profile", { name });
+}
+
 export async function gatewaySessionToken(): Promise<string | null> {
   return invokeTauri<string>("gateway_session_token");
 }
 
+export async function rotateGatewaySessionToken(): Promise<string | null> {
+  return invokeTauri<string>("rotate_gateway_session_token");
+}
+
 export async function getGatewayHealth(): Promise<GatewayHealth | null> {
   return invokeTauri<GatewayHealth>("gateway_health");
 }
 
 export async function listAgents(): Promise<AgentProfile[]> {
-  return (await invokeTauri<AgentProfile[]>("list_agents")) ?? [];
+  return (await invokeCached<AgentProfile[]>("list_agents")) ?? [];
 }
 
 export async function listTasks(): Promise<TaskRecord[]> {
-  return (await invokeTauri<TaskRecord[]>("list_tasks")) ?? [];
+  return (await invokeCached<TaskRecord[]>("list_tasks")) ?? [];
 }
 
 export async function createTask(payload: {
@@ -489,8 +727,8 @@ export async function createTask(payload: {
   priority?: "low" | "normal" | "high";
   assigneeAgentId?: string | null;
   parentTaskId?: string | null;
-}): Promise<TaskRecord | null> {
-  return invokeTauri<TaskRecord>("create_task", {
+}): Promise<TaskRecord | QueuedWriteResult | null> {
+  return invokeWrite<TaskRecord>("create_task", {
     payload: {
       title: payload.title,
       description: payload.description ?? null,
@@ -511,7 +749,7 @@ export async function updateTask(
     priority?: "low" | "normal" | "high";
     assigneeAgentId?: string | null;
   }
-): Promise<TaskRecord | null> {
+): Promise<TaskRecord | QueuedWriteResult | null> {
   const patchPayload: Record<string, unknown> = {};
   if ("title" in patch) {
     patchPayload.title = patch.title ?? null;
@@ -528,14 +766,14 @@ export async function updateTask(
   if ("assigneeAgentId" in patch) {
     patchPayload.assigneeAgentId = patch.assigneeAgentId ?? null;
   }
-  return invokeTauri<TaskRecord>("update_task", {
+  return invokeWrite<TaskRecord>("update_task", {
     task_id: taskId,
     patch: patchPayload
   });
 }
 
-export async function deleteTask(taskId: string): Promise<boolean> {
-  return (await invokeTauri<boolean>("delete_task", { task_id: taskId })) ?? false;
+export async function deleteTask(taskId: string): Promise<boolean | QueuedWriteResult> {
+  return (await invokeWrite<boolean>("delete_task", { task_id: taskId })) ?? false;
 }
 
 export async function createAgent(payload: AgentCreatePayload): Promise<AgentProfile | null> {
@@ -599,7 +837,7 @@ export async function getConnectedModelProviders(): Promise<ConnectedModelProvid
 }
 
 export async function chatThreads(): Promise<SwarmChatThread[]> {
-  return (await invokeTauri<SwarmChatThread[]>("chat_threads")) ?? [];
+  return (await invokeCached<SwarmChatThread[]>("chat_threads")) ?? [];
 }
 
 export async function chatSummary(): Promise<SwarmSummary | null> {
@@ -620,6 +858,42 @@ export async function chatSend(threadId: string, content: string, userId?: strin
   );
 }
 
+export type ChatStreamEvent =
+  | { event: "delta"; delta: { messageId: string; threadId: string; content: string } }
+  | { event: "message"; message: SwarmChatMessage }
+  | { event: "done"; cancelled: boolean }
+  | { event: "error"; message: string };
+
+/** Streams the reply over a Tauri channel; resolves once `done` or `error` has been delivered. */
+export async function chatSendStream(
+  streamId: string,
+  threadId: string,
+  content: string,
+  onEvent: (event: ChatStreamEvent) => void,
+  userId?: string
+): Promise<boolean> {
+  try {
+    const core = await import("@tauri-apps/api/core");
+    const channel = new core.Channel<ChatStreamEvent>();
+    channel.onmessage = onEvent;
+    await core.invoke("chat_send_stream", {
+      stream_id: streamId,
+      thread_id: threadId,
+      content,
+      user_id: userId ?? "user",
+      on_event: channel
+    });
+    return true;
+  } catch (error) {
+    emitInvokeError(error, "Command: chat_send_stream");
+    return false;
+  }
+}
+
+export async function cancelChatStream(streamId: string): Promise<boolean> {
+  return cancelRequest(streamId);
+}
+
 export async function chatQuickAction(
   threadId: string,
   action: "morning_briefing" | "status_report" | "suggest_skills" | "delegate_task"
@@ -639,19 +913,21 @@ export async function chatCheckpoints(swarmId = "swarm_main", limit = 50): Promi
 export async function chatRewind(
   threadId: string,
   checkpointId: string,
-  editPrompt?: string
+  editPrompt?: string,
+  requestId?: string
 ): Promise<SwarmChatMessage[]> {
   return (
     (await invokeTauri<SwarmChatMessage[]>("chat_rewind", {
       thread_id: threadId,
       checkpoint_id: checkpointId,
-      edit_prompt: editPrompt ?? null
+      edit_prompt: editPrompt ?? null,
+      request_id: requestId ?? null
     })) ?? []
   );
 }
 
 export async function getBudgets(): Promise<BudgetSnapshot | null> {
-  return invokeTauri<BudgetSnapshot>("get_budgets");
+  return invokeCached<BudgetSnapshot>("get_budgets");
 }
 
 export async function updateBudget(
@@ -700,13 +976,15 @@ export async function mcpInvokeTool(
   serverId: string,
   toolId: string,
   agentId: string,
-  args?: Record<string, unknown>
+  args?: Record<string, unknown>,
+  requestId?: string
 ): Promise<McpInvokeResult | null> {
   return invokeTauri<McpInvokeResult>("mcp_invoke_tool", {
     server_id: serverId,
     tool_id: toolId,
     agent_id: agentId,
-    args: args ?? {}
+    args: args ?? {},
+    request_id: requestId ?? null
   });
 }
 
@@ -719,7 +997,7 @@ export async function vaultCapacity(): Promise<VaultStorageStats | null> {
 }
 
 export async function vaultRecent(limit = 40): Promise<VaultEntry[]> {
-  return (await invokeTauri<VaultEntry[]>("vault_recent", { limit })) ?? [];
+  return (await invokeCached<VaultEntry[]>("vault_recent", { limit })) ?? [];
 }
 
 export async function vaultSearch(query: string, limit = 40): Promise<VaultEntry[]> {
@@ -735,8 +1013,8 @@ export async function vaultDeposit(input: {
   agentId: string;
   taskId?: string;
   encrypted?: boolean;
-}): Promise<VaultEntry | null> {
-  return invokeTauri<VaultEntry>("vault_deposit", {
+}): Promise<VaultEntry | QueuedWriteResult | null> {
+  return invokeWrite<VaultEntry>("vault_deposit", {
     entry_type: input.type,
     title: input.title,
     markdown_summary: input.markdownSummary,
@@ -765,8 +1043,8 @@ export async function vaultUpdateEntry(
     tags?: string[];
     encrypted?: boolean;
   }
-): Promise<VaultEntry | null> {
-  return invokeTauri<VaultEntry>("vault_update_entry", {
+): Promise<VaultEntry | QueuedWriteResult | null> {
+  return invokeWrite<VaultEntry>("vault_update_entry", {
     entry_id: entryId,
     title: patch.title ?? null,
     markdown_summary: patch.markdownSummary ?? null,
@@ -776,6 +1054,53 @@ export async function vaultUpdateEntry(
   });
 }
 
+export type QueuedWriteOp =
+  | { kind: "create_task"; payload: Record<string, unknown> }
+  | { kind: "update_task"; taskId: string; patch: Record<string, unknown> }
+  | { kind: "delete_task"; taskId: string }
+  | { kind: "vault_deposit"; payload: Record<string, unknown> }
+  | { kind: "vault_update_entry"; entryId: string; patch: Record<string, unknown> };
+
+export type QueuedWrite = {
+  id: string;
+  op: QueuedWriteOp;
+  idempotencyKey: string;
+  queuedAt: string;
+};
+
+export type WriteConflict = {
+  id: string;
+  op: QueuedWriteOp;
+  queuedAt: string;
+  failedAt: string;
+  /** HTTP status the gateway rejected it with; `null` when replay gave up for another reason. */
+  status: number | null;
+  message: string;
+};
+
+export async function getPendingWrites(): Promise<QueuedWrite[]> {
+  return (await invokeTauri<QueuedWrite[]>("write_queue_pending")) ?? [];
+}
+
+export async function getWriteConflicts(): Promise<WriteConflict[]> {
+  return (await invokeTauri<WriteConflict[]>("write_queue_conflicts")) ?? [];
+}
+
+/** Dismisses one conflict, or all of them without an id. */
+export async function dismissWriteConflict(conflictId?: string): Promise<number> {
+  return (await invokeTauri<number>("dismiss_write_conflict", { conflict_id: conflictId ?? null })) ?? 0;
+}
+
+export async function replayWriteQueue(): Promise<number> {
+  return (await invokeTauri<number>("replay_write_queue")) ?? 0;
+}
+
+export async function subscribeWriteQueue(
+  onChange: (counts: { pending: number; conflicts: number }) => void
+): Promise<() => void> {
+  return subscribeGatewayEvent("write-queue:changed", onChange);
+}
+
 export async function vaultCreateVersion(
   entryId: string,
   input: {
@@ -800,13 +1125,23 @@ export async function vaultStorageInfo(): Promise<VaultStorageInfo | null> {
   return invokeTauri<VaultStorageInfo>("vault_storage_info");
 }
 
-export async function vaultRelocateStorage(path: string, moveExisting = true): Promise<VaultStorageInfo | null> {
+export async function vaultRelocateStorage(
+  path: string,
+  moveExisting = true,
+  requestId?: string
+): Promise<VaultStorageInfo | null> {
   return invokeTauri<VaultStorageInfo>("vault_relocate_storage", {
     path,
-    move_existing: moveExisting
+    move_existing: moveExisting,
+    request_id: requestId ?? null
   });
 }
 
+/** Aborts a command started with this `requestId`; it then rejects with code `Cancelled`. */
+export async function cancelRequest(requestId: string): Promise<boolean> {
+  return (await invokeTauri<boolean>("cancel_request", { request_id: requestId })) ?? false;
+}
+
 export async function clawhubSearch(query: string, sort: ClawHubSort): Promise<ClawHubSkill[]> {
   return (await invokeTauri<ClawHubSkill[]>("clawhub_search", { query, sort })) ?? [];
 }
@@ -33,6 +33,8 @@ function mapCode(raw?: string): AppErrorCode {
       return "VaultStorageFull";
     case "NetworkError":
       return "NetworkError";
+    case "Cancelled":
+      return "Cancelled";
     default:
       return "InternalServerError";
   }
@@ -5,5 +5,8 @@ $WorkspacePath = "C:\MajorClaw\workspace"
 & $NssmPath install $ServiceName "C:\Program Files\nodejs\pnpm.cmd" "--filter @majorclaw/gateway start"
 & $NssmPath set $ServiceName AppDirectory $WorkspacePath
 & $NssmPath set $ServiceName Start SERVICE_AUTO_START
+if ($env:MAJORCLAW_GATEWAY_SESSION_TOKEN) {
+  & $NssmPath set $ServiceName AppEnvironmentExtra "MAJORCLAW_GATEWAY_SESSION_TOKEN=$env:MAJORCLAW_GATEWAY_SESSION_TOKEN"
+}
 Start-Service $ServiceName
 
@@ -37,6 +37,13 @@ export interface TaskEvent {
   createdAt: string;
 }
 
+/** Response to a create sent with an idempotency key, replayed for repeats of it until `expiresAt` (ms). */
+export interface IdempotentResponse {
+  key: string;
+  body: unknown;
+  expiresAt: number;
+}
+
 export interface DataStore {
   agents: AgentProfile[];
   apiKeys: { agentId: string; encrypted: string }[];
@@ -48,6 +55,7 @@ export interface DataStore {
   swarmMessages: SwarmChatMessage[];
   delegationPlans: { messageId: string; steps: DelegationPlanStep[] }[];
   skillSuggestions: { messageId: string; suggestion: SkillSuggestionPayload }[];
+  idempotentResponses: IdempotentResponse[];
   skills: ClawHubSkill[];
   agentSkills: { agentId: string; skillSlug: string; enabled: boolean; assignedAt: string }[];
   usageReports: UsageReport[];
@@ -72,6 +80,7 @@ export function createInMemoryStore(): DataStore {
     swarmMessages: [],
     delegationPlans: [],
     skillSuggestions: [],
+    idempotentResponses: [],
     skills: [],
     agentSkills: [],
     usageReports: [],
@@ -133,6 +142,11 @@ export class SqliteRuntimePersistence {
         message_id TEXT PRIMARY KEY,
         suggestion_json TEXT NOT NULL
       );
+      CREATE TABLE IF NOT EXISTS idempotent_responses (
+        idempotency_key TEXT PRIMARY KEY,
+        body_json TEXT NOT NULL,
+        expires_at INTEGER NOT NULL
+      );
     `);
   }
 
@@ -171,7 +185,8 @@ export class SqliteRuntimePersistence {
       return null;
     }
     try {
-      return JSON.parse(row.payload_json) as DataStore;
+      // Snapshots predating newer collections lack them; start those empty.
+      return { ...createInMemoryStore(), ...(JSON.parse(row.payload_json) as DataStore) };
     } catch {
       return null;
     }
@@ -382,6 +397,17 @@ export class SqliteRuntimePersistence {
       }));
     }
 
+    const idempotentResponses = this.db
+      .prepare(`SELECT idempotency_key,body_json,expires_at FROM idempotent_responses`)
+      .all() as Array<{ idempotency_key: string; body_json: string; expires_at: number }>;
+    if (idempotentResponses.length > 0) {
+      next.idempotentResponses = idempotentResponses.map((row) => ({
+        key: row.idempotency_key,
+        body: JSON.parse(row.body_json) as unknown,
+        expiresAt: Number(row.expires_at)
+      }));
+    }
+
     const vaultEntries = this.db
       .prepare(
         `SELECT id,type,title,markdown_summary,importance_score,tags_json,agent_id,task_id,version,blob_path,created_at,expires_at,encrypted
@@ -615,6 +641,7 @@ export class SqliteRuntimePersistence {
       this.db.exec("DELETE FROM swarm_messages;");
       this.db.exec("DELETE FROM delegation_plans;");
       this.db.exec("DELETE FROM skill_suggestions;");
+      this.db.exec("DELETE FROM idempotent_responses;");
       this.db.exec("DELETE FROM skills;");
       this.db.exec("DELETE FROM agent_skills;");
       this.db.exec("DELETE FROM cost_snapshots;");
@@ -727,6 +754,13 @@ export class SqliteRuntimePersistence {
         insertSuggestion.run(suggestion.messageId, JSON.stringify(suggestion.suggestion));
       }
 
+      const insertIdempotent = this.db.prepare(
+        `INSERT INTO idempotent_responses (idempotency_key,body_json,expires_at) VALUES (?, ?, ?)`
+      );
+      for (const response of state.idempotentResponses) {
+        insertIdempotent.run(response.key, JSON.stringify(response.body), response.expiresAt);
+      }
+
       const insertSkill = this.db.prepare(
         `INSERT INTO skills (
           slug,display_name,summary,author,downloads,stars,version,tags_json,stats_json,categories_json,permissions_json,installed,last_fetched
@@ -1109,6 +1143,22 @@ export class Repository {
     this.commit();
   }
 
+  /** The stored response for `key`, dropping any that expired before `now`. */
+  getIdempotentResponse(key: string, now = Date.now()): IdempotentResponse | undefined {
+    const live = this.db.idempotentResponses.filter((item) => item.expiresAt > now);
+    if (live.length !== this.db.idempotentResponses.length) {
+      this.db.idempotentResponses = live;
+      this.commit();
+    }
+    return live.find((item) => item.key === key);
+  }
+
+  rememberIdempotentResponse(response: IdempotentResponse): void {
+    this.db.idempotentResponses = this.db.idempotentResponses.filter((item) => item.key !== response.key);
+    this.db.idempotentResponses.push(response);
+    this.commit();
+  }
+
   getSwarmSummary(): SwarmSummary {
     const onlineAgents = this.db.agents.filter((agent) => agent.status === "online" || agent.status === "busy").length;
     const activeTasks = this.db.tasks.filter((task) => task.status !== "done" && task.status !== "failed").length;
@@ -91,3 +91,25 @@ test("persists critical runtime entities across repository restart", () => {
     cleanup();
   }
 });
+
+test("keeps idempotent responses across restart until they expire", () => {
+  const { path, cleanup } = tempDbPath();
+  try {
+    const repoA = createSqliteBackedRepository(new SqliteRuntimePersistence(path), createInMemoryStore());
+    repoA.addAuditLog({
+      id: "audit_idempotent",
+      category: "test",
+      action: "persist",
+      actor: "suite",
+      metadata: {},
+      createdAt: new Date().toISOString()
+    });
+    repoA.rememberIdempotentResponse({ key: "tasks.create:key-1", body: { task: { id: "task_1" } }, expiresAt: 2_000 });
+
+    const repoB = createSqliteBackedRepository(new SqliteRuntimePersistence(path), createInMemoryStore());
+    assert.deepEqual(repoB.getIdempotentResponse("tasks.create:key-1", 1_000)?.body, { task: { id: "task_1" } });
+    assert.equal(repoB.getIdempotentResponse("tasks.create:key-1", 2_000), undefined);
+  } finally {
+    cleanup();
+  }
+});
@@ -323,7 +323,8 @@ export type AppErrorCode =
   | "InternalServerError"
   | "NetworkError"
   | "PermissionDenied"
-  | "VaultStorageFull";
+  | "VaultStorageFull"
+  | "Cancelled";
 
 export interface ErrorResponse {
   code: AppErrorCode | string;
@@ -0,0 +1,50 @@
+// Prepares the gateway artifacts a release build ships, so the installed app runs
+// without pnpm or a source checkout:
+//   - apps/desktop-tauri/src-tauri/gateway-bundle/: the compiled gateway with its
+//     production node_modules (copied into the app resources as `gateway/`)
+//   - apps/desktop-tauri/src-tauri/binaries/node-<target triple>: the node runtime
+//     running this script (installed next to the app executable as `node`)
+import { execFileSync } from "node:child_process";
+import { chmodSync, copyFileSync, mkdirSync, rmSync } from "node:fs";
+import { dirname, join, resolve } from "node:path";
+import { fileURLToPath } from "node:url";
+
+const root = resolve(dirname(fileURLToPath(import.meta.url)), "..");
+const tauriDir = join(root, "apps", "desktop-tauri", "src-tauri");
+const bundleDir = join(tauriDir, "gateway-bundle");
+const binariesDir = join(tauriDir, "binaries");
+const pnpm = process.platform === "win32" ? "pnpm.cmd" : "pnpm";
+
+function log(line) {
+  // eslint-disable-next-line no-console
+  console.log(line);
+}
+
+function run(command, args) {
+  execFileSync(command, args, { cwd: root, stdio: "inherit", shell: process.platform === "win32" });
+}
+
+function hostTargetTriple() {
+  const output = execFileSync("rustc", ["-vV"], { encoding: "utf8" });
+  const host = output.split("\n").find((line) => line.startsWith("host:"));
+  if (!host) {
+    throw new Error("could not read the host target triple from `rustc -vV`");
+  }
+  return host.slice("host:".length).trim();
+}
+
+log("building the gateway and its workspace dependencies");
+run(pnpm, ["--filter", "@majorclaw/gateway...", "build"]);
+
+log(`deploying the gateway to ${bundleDir}`);
+rmSync(bundleDir, { recursive: true, force: true });
+run(pnpm, ["--filter", "@majorclaw/gateway", "deploy", "--prod", "--legacy", bundleDir]);
+
+const extension = process.platform === "win32" ? ".exe" : "";
+const sidecar = join(binariesDir, `node-${hostTargetTriple()}${extension}`);
+log(`copying node ${process.version} to ${sidecar}`);
+mkdirSync(binariesDir, { recursive: true });
+copyFileSync(process.execPath, sidecar);
+if (process.platform !== "win32") {
+  chmodSync(sidecar, 0o755);
+}
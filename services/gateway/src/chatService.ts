@@ -14,6 +14,12 @@ import type { VaultService } from "./vaultService.js";
 
 const DEFAULT_THREAD_ID = "thread_cso_default";
 
+/** Where `streamMessage` sends a reply's text chunks and finished messages. */
+export type ChatStreamSink = {
+  onDelta(delta: { messageId: string; threadId: string; content: string }): void;
+  onMessage(message: SwarmChatMessage): void;
+};
+
 function nowIso(): string {
   return new Date().toISOString();
 }
@@ -34,6 +40,16 @@ function memoryContextBlock(content: string, recalled: { title: string; markdown
   return lines.join("\n");
 }
 
+/** Splits text into runs of a few words (keeping their whitespace) for streaming. */
+function textChunks(text: string, wordsPerChunk = 4): string[] {
+  const words = text.match(/\S+\s*|\s+/g) ?? [];
+  const chunks: string[] = [];
+  for (let index = 0; index < words.length; index += wordsPerChunk) {
+    chunks.push(words.slice(index, index + wordsPerChunk).join(""));
+  }
+  return chunks;
+}
+
 function maybeSuggestion(input: string): SkillSuggestionPayload | null {
   const lowered = input.toLowerCase();
   if (lowered.includes("research") || lowered.includes("market") || lowered.includes("latest")) {
@@ -100,7 +116,46 @@ export class ChatService {
   }
 
   sendMessage(threadId: string, content: string, userId: string): SwarmChatMessage[] {
-    const emitted: SwarmChatMessage[] = [];
+    return [...this.produceMessages(threadId, content, userId)];
+  }
+
+  /**
+   * Streams a send as it is produced: each reply's text goes to `onDelta` a few words
+   * at a time, yielding to the event loop in between, then the finished message goes
+   * to `onMessage`. A message is stored only once it has been handed on whole, so when
+   * `signal` aborts, the reply in progress and everything after it are dropped.
+   * Returns how many messages were sent.
+   */
+  async streamMessage(
+    threadId: string,
+    content: string,
+    userId: string,
+    signal: AbortSignal,
+    sink: ChatStreamSink
+  ): Promise<number> {
+    let count = 0;
+    for (const message of this.produceMessages(threadId, content, userId)) {
+      if (message.type !== "user") {
+        for (const chunk of textChunks(message.content)) {
+          if (signal.aborted) {
+            return count;
+          }
+          sink.onDelta({ messageId: message.id, threadId: message.threadId, content: chunk });
+          await new Promise((resolve) => setImmediate(resolve));
+        }
+      }
+      if (signal.aborted) {
+        return count;
+      }
+      sink.onMessage(message);
+      count += 1;
+    }
+    return count;
+  }
+
+  // Yields each message before storing it, so a consumer that stops iterating
+  // (an aborted stream) leaves the message it was handed unsaved.
+  private *produceMessages(threadId: string, content: string, userId: string): Generator<SwarmChatMessage> {
     const budgetCheck = this.budgets.canRun("agent_cso");
     if (!budgetCheck.allowed) {
       const blocked: SwarmChatMessage = {
@@ -111,8 +166,9 @@ export class ChatService {
         content: `Execution blocked: ${budgetCheck.reason ?? "budget limit reached."}`,
         createdAt: nowIso()
       };
+      yield blocked;
       this.repository.appendSwarmMessage(blocked);
-      return [blocked];
+      return;
     }
     const userMessage: SwarmChatMessage = {
       id: randomUUID(),
@@ -122,8 +178,8 @@ export class ChatService {
       content,
       createdAt: nowIso()
     };
+    yield userMessage;
     this.repository.appendSwarmMessage(userMessage);
-    emitted.push(userMessage);
     const roughPromptTokens = Math.max(12, Math.ceil(content.length / 4));
     const roughCompletionTokens = Math.max(24, Math.ceil(content.length / 3));
     this.budgets.registerUsage({
@@ -156,9 +212,9 @@ export class ChatService {
         parentMessageId: userMessage.id,
         metadata: { recalled: recalled.map((entry) => ({ id: entry.id, title: entry.title, importanceScore: entry.importanceScore })) }
       };
+      yield message;
       this.repository.appendSwarmMessage(message);
-      emitted.push(message);
-      return emitted;
+      return;
     }
 
     const needsDelegation = /(plan|build|ship|research|analy|debug|implement|campaign|strategy|delegate)/i.test(content);
@@ -176,13 +232,13 @@ export class ChatService {
         parentMessageId: userMessage.id,
         metadata: { recalled: recalled.map((entry) => ({ id: entry.id, title: entry.title, importanceScore: entry.importanceScore })) }
       };
+      yield direct;
       this.repository.appendSwarmMessage(direct);
-      emitted.push(direct);
       const suggestion = maybeSuggestion(content);
       if (suggestion) {
-        emitted.push(this.pushSuggestion(threadId, userMessage.id, suggestion));
+        yield* this.pushSuggestion(threadId, userMessage.id, suggestion);
       }
-      return emitted;
+      return;
     }
 
     const steps: DelegationPlanStep[] = [
@@ -204,9 +260,9 @@ export class ChatService {
         recalled: recalled.map((entry) => ({ id: entry.id, title: entry.title, importanceScore: entry.importanceScore }))
       }
     };
+    yield csoPlanMessage;
     this.repository.appendSwarmMessage(csoPlanMessage);
     this.repository.upsertDelegationPlan(csoPlanMessage.id, steps);
-    emitted.push(csoPlanMessage);
 
     for (const step of steps) {
       const intent: TaskIntent = {
@@ -230,13 +286,13 @@ export class ChatService {
         parentMessageId: csoPlanMessage.id,
         metadata: { status: "in_progress" }
       };
+      yield update;
       this.repository.appendSwarmMessage(update);
-      emitted.push(update);
     }
 
     const suggestion = maybeSuggestion(content);
     if (suggestion) {
-      emitted.push(this.pushSuggestion(threadId, userMessage.id, suggestion));
+      yield* this.pushSuggestion(threadId, userMessage.id, suggestion);
     }
 
     const summary: SwarmChatMessage = {
@@ -249,9 +305,8 @@ export class ChatService {
       parentMessageId: userMessage.id,
       metadata: { recalled: recalled.map((entry) => ({ id: entry.id, title: entry.title, importanceScore: entry.importanceScore })) }
     };
+    yield summary;
     this.repository.appendSwarmMessage(summary);
-    emitted.push(summary);
-    return emitted;
   }
 
   listCheckpoints(swarmId = "swarm_main", limit = 50) {
@@ -287,7 +342,7 @@ export class ChatService {
     return this.sendMessage(threadId, templates[action], "user");
   }
 
-  private pushSuggestion(threadId: string, parentMessageId: string, suggestion: SkillSuggestionPayload): SwarmChatMessage {
+  private *pushSuggestion(threadId: string, parentMessageId: string, suggestion: SkillSuggestionPayload): Generator<SwarmChatMessage> {
     const message: SwarmChatMessage = {
       id: randomUUID(),
       threadId,
@@ -298,9 +353,9 @@ export class ChatService {
       parentMessageId,
       metadata: { ...suggestion }
     };
+    yield message;
     this.repository.appendSwarmMessage(message);
     this.repository.upsertSkillSuggestion(message.id, suggestion);
-    return message;
   }
 
   private maybePulse(threadId: string): void {
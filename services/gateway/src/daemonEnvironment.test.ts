@@ -0,0 +1,20 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { mkdtempSync, writeFileSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+import { loadDaemonEnvironment, parseDaemonEnvironment } from "./daemonEnvironment.js";
+
+test("parseDaemonEnvironment unescapes quoted values", () => {
+  const parsed = parseDaemonEnvironment('MAJORCLAW_GATEWAY_SESSION_TOKEN="a\\"b\\\\c\\$d\\`e"\nnot a line\n');
+  assert.deepEqual(parsed, { MAJORCLAW_GATEWAY_SESSION_TOKEN: 'a"b\\c$d`e' });
+});
+
+test("loadDaemonEnvironment fills unset variables from the file", () => {
+  const path = join(mkdtempSync(join(tmpdir(), "majorclaw-env-")), "gateway-daemon.env");
+  writeFileSync(path, 'MAJORCLAW_GATEWAY_SESSION_TOKEN="from-file"\nMAJORCLAW_GATEWAY_PORT="1"\n');
+  const env: NodeJS.ProcessEnv = { MAJORCLAW_GATEWAY_ENV_FILE: path, MAJORCLAW_GATEWAY_PORT: "4455" };
+  loadDaemonEnvironment(env);
+  assert.equal(env.MAJORCLAW_GATEWAY_SESSION_TOKEN, "from-file");
+  assert.equal(env.MAJORCLAW_GATEWAY_PORT, "4455");
+});
@@ -0,0 +1,30 @@
+import { readFileSync } from "node:fs";
+
+/** Parses the `KEY="value"` lines the desktop host writes into a daemon environment file. */
+export function parseDaemonEnvironment(contents: string): Record<string, string> {
+  const values: Record<string, string> = {};
+  for (const line of contents.split("\n")) {
+    const match = /^([A-Za-z_][A-Za-z0-9_]*)="((?:[^"\\]|\\.)*)"$/.exec(line.trim());
+    if (match) {
+      values[match[1]] = match[2].replace(/\\(.)/g, "$1");
+    }
+  }
+  return values;
+}
+
+/**
+ * Loads the owner-only environment file named by `MAJORCLAW_GATEWAY_ENV_FILE` (the
+ * launch agent keeps the session token there instead of in its plist). Variables
+ * already set in the environment win.
+ */
+export function loadDaemonEnvironment(env: NodeJS.ProcessEnv = process.env): void {
+  const path = env.MAJORCLAW_GATEWAY_ENV_FILE?.trim();
+  if (!path) {
+    return;
+  }
+  for (const [key, value] of Object.entries(parseDaemonEnvironment(readFileSync(path, "utf8")))) {
+    if (env[key] === undefined) {
+      env[key] = value;
+    }
+  }
+}
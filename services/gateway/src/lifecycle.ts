@@ -85,7 +85,9 @@ export class GatewayLifecycle {
 
   private async performShutdown(reason: string, actor: string): Promise<void> {
     // eslint-disable-next-line no-console
-    console.info(JSON.stringify({ level: "info", event: "gateway_shutdown_begin", reason, actor }));
+    console.info(
+      JSON.stringify({ level: "info", timestamp: new Date().toISOString(), event: "gateway_shutdown_begin", reason, actor })
+    );
     const waitDeadline = Date.now() + 10_000;
     while (this.inFlightRequests > 0 && Date.now() < waitDeadline) {
       await sleep(100);
@@ -97,6 +99,7 @@ export class GatewayLifecycle {
       console.error(
         JSON.stringify({
           level: "error",
+          timestamp: new Date().toISOString(),
           event: "gateway_shutdown_flush_failed",
           message: error instanceof Error ? error.message : "unknown"
         })
@@ -107,7 +110,9 @@ export class GatewayLifecycle {
     });
     this.releasePidLock();
     // eslint-disable-next-line no-console
-    console.info(JSON.stringify({ level: "info", event: "gateway_shutdown_complete", reason, actor }));
+    console.info(
+      JSON.stringify({ level: "info", timestamp: new Date().toISOString(), event: "gateway_shutdown_complete", reason, actor })
+    );
   }
 }
 
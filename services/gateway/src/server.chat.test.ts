@@ -0,0 +1,84 @@
+import test from "node:test";
+import assert from "node:assert/strict";
+import { spawn } from "node:child_process";
+import { mkdtempSync, rmSync } from "node:fs";
+import { join } from "node:path";
+import { tmpdir } from "node:os";
+
+function sleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+async function waitReady(baseUrl: string, attempts = 40): Promise<void> {
+  for (let i = 0; i < attempts; i += 1) {
+    try {
+      const res = await fetch(`${baseUrl}/ready`);
+      if (res.ok) {
+        return;
+      }
+    } catch {
+      // retry
+    }
+    await sleep(150);
+  }
+  throw new Error("gateway did not become ready in time");
+}
+
+test("chat send streams reply text as delta events before each message", async (t) => {
+  const port = 5250 + Math.floor(Math.random() * 300);
+  const token = `test-token-${Date.now()}`;
+  const tempDir = mkdtempSync(join(tmpdir(), "majorclaw-gateway-chat-"));
+  const baseUrl = `http://127.0.0.1:${port}`;
+
+  const child = spawn("node", ["--import", "tsx", "src/server.ts"], {
+    cwd: process.cwd(),
+    env: {
+      ...process.env,
+      MAJORCLAW_GATEWAY_PORT: String(port),
+      MAJORCLAW_GATEWAY_SESSION_TOKEN: token,
+      MAJORCLAW_DB_PATH: join(tempDir, "gateway-chat.db"),
+      MAJORCLAW_GATEWAY_PID_FILE: join(tempDir, "gateway.pid")
+    },
+    stdio: ["ignore", "pipe", "pipe"]
+  });
+  let stderr = "";
+  child.stderr.on("data", (chunk) => {
+    stderr += chunk.toString();
+  });
+
+  try {
+    try {
+      await waitReady(baseUrl);
+    } catch (error) {
+      if (stderr.includes("EPERM: operation not permitted")) {
+        t.skip("sandbox environment does not allow listening sockets");
+        return;
+      }
+      throw error;
+    }
+
+    const response = await fetch(`${baseUrl}/chat/send`, {
+      method: "POST",
+      headers: {
+        accept: "text/event-stream",
+        "content-type": "application/json",
+        "x-session-token": token,
+        "x-request-id": "stream-test"
+      },
+      body: JSON.stringify({ thread_id: "thread_cso_default", content: "hello there", user_id: "user" })
+    });
+    assert.equal(response.status, 200);
+    assert.match(response.headers.get("content-type") ?? "", /^text\/event-stream/);
+    const events = (await response.text())
+      .split("\n\n")
+      .map((block) => /^event: (\w+)/.exec(block)?.[1])
+      .filter(Boolean);
+    assert.equal(events.at(-1), "done");
+    const firstDelta = events.indexOf("delta");
+    assert.ok(firstDelta > 0, "the user message goes out whole, then the reply streams");
+    assert.ok(events.indexOf("message", firstDelta) > firstDelta);
+  } finally {
+    child.kill("SIGTERM");
+    rmSync(tempDir, { recursive: true, force: true });
+  }
+});
@@ -1,6 +1,7 @@
 import { createServer, type IncomingMessage, ServerResponse, type ServerResponse as ServerResponseType } from "node:http";
 import type { AgentQuickAction, AppErrorCode, TaskRecord } from "@majorclaw/shared-types";
 import type { ZodType } from "zod";
+import { loadDaemonEnvironment } from "./daemonEnvironment.js";
 import { bootGatewayFromRegistry } from "./index.js";
 import { GatewayLifecycle } from "./lifecycle.js";
 import { redactSensitiveString } from "./securityRedaction.js";
@@ -25,6 +26,7 @@ import {
   permissionsRequestSchema,
   redPhoneSchema,
   reorderAgentsSchema,
+  requestCancelSchema,
   RequestValidationError,
   shutdownSchema,
   taskCreateSchema,
@@ -36,9 +38,13 @@ import {
   vaultVersionSchema
 } from "./validation.js";
 
+loadDaemonEnvironment();
 const port = Number(process.env.MAJORCLAW_GATEWAY_PORT ?? "4455");
+const host = process.env.MAJORCLAW_GATEWAY_HOST ?? "127.0.0.1";
 const runtime = bootGatewayFromRegistry(process.env.MAJORCLAW_INSTANCE_CONFIG);
 const startedAt = new Date().toISOString();
+/** Who launched this process: `app` (a desktop host), `daemon` (launchd/systemd/NSSM) or `external` (by hand). */
+const gatewayOwner = process.env.MAJORCLAW_GATEWAY_OWNER?.trim() || "external";
 const gatewaySessionToken = process.env.MAJORCLAW_GATEWAY_SESSION_TOKEN ?? "";
 const maxBodyBytes = Number(process.env.MAJORCLAW_MAX_BODY_BYTES ?? "1048576");
 const rateLimitWindowMs = Number(process.env.MAJORCLAW_RATE_LIMIT_WINDOW_MS ?? "60000");
@@ -68,6 +74,59 @@ type RateBucket = {
 
 const rateBuckets = new Map<string, RateBucket>();
 
+// Kept in the gateway DB for a day, so a create the host replays from its write
+// queue after a gateway restart or a long outage still finds the first response.
+const idempotencyTtlMs = 24 * 60 * 60_000;
+
+function idempotencyKey(req: IncomingMessage, route: string): string | null {
+  const header = req.headers["idempotency-key"];
+  if (typeof header !== "string" || !header.trim()) {
+    return null;
+  }
+  return `${route}:${header.trim()}`;
+}
+
+function replayIdempotent(req: IncomingMessage, res: ServerResponseType, key: string | null): boolean {
+  if (!key) {
+    return false;
+  }
+  const existing = runtime.repository.getIdempotentResponse(key);
+  if (!existing) {
+    return false;
+  }
+  res.setHeader("idempotent-replayed", "true");
+  sendJson(req, res, existing.body);
+  return true;
+}
+
+function rememberIdempotent(key: string | null, body: unknown) {
+  if (key) {
+    runtime.repository.rememberIdempotentResponse({ key, body, expiresAt: Date.now() + idempotencyTtlMs });
+  }
+}
+
+const inflightRequests = new Map<string, AbortController>();
+
+// Long-running routes register the caller's `x-request-id` so `/requests/cancel`
+// (or the caller hanging up) can abort them before they commit.
+function trackAbort(req: IncomingMessage, res: ServerResponseType): AbortSignal {
+  const controller = new AbortController();
+  const header = req.headers["x-request-id"];
+  const id = typeof header === "string" && header.trim() ? header.trim() : null;
+  if (id) {
+    inflightRequests.set(id, controller);
+  }
+  res.on("close", () => {
+    if (id && inflightRequests.get(id) === controller) {
+      inflightRequests.delete(id);
+    }
+    if (!res.writableFinished) {
+      controller.abort(new Error("request closed by client"));
+    }
+  });
+  return controller.signal;
+}
+
 function requestId(): string {
   return `${Date.now().toString(36)}-${Math.random().toString(36).slice(2, 8)}`;
 }
@@ -95,7 +154,7 @@ function applyCors(req: IncomingMessage, res: ServerResponse) {
     res.setHeader("vary", "Origin");
   }
   res.setHeader("access-control-allow-methods", "GET,POST,PATCH,DELETE,OPTIONS");
-  res.setHeader("access-control-allow-headers", "content-type,x-session-token");
+  res.setHeader("access-control-allow-headers", "content-type,x-session-token,idempotency-key,x-request-id");
 }
 
 function sendJson(
@@ -276,6 +335,7 @@ const server = createServer(async (req, res) => {
     console.info(
       JSON.stringify({
         level: "info",
+        timestamp: new Date().toISOString(),
         event: "http_request",
         requestId: reqId,
         method: req.method,
@@ -293,6 +353,8 @@ const server = createServer(async (req, res) => {
     sendJson(req, res, {
       status: "ok",
       startedAt,
+      owner: gatewayOwner,
+      pid: process.pid,
       instanceCount: instances.length,
       instances: instances.map((item) => ({ id: item.id, name: item.name, wsUrl: item.wsUrl }))
     });
@@ -373,6 +435,29 @@ const server = createServer(async (req, res) => {
     const threadId = payload.thread_id;
     const content = payload.content;
     const userId = payload.user_id;
+    const accept = typeof req.headers.accept === "string" ? req.headers.accept : "";
+    if (accept.includes("text/event-stream")) {
+      const signal = trackAbort(req, res);
+      applyCors(req, res);
+      res.statusCode = 200;
+      res.setHeader("content-type", "text/event-stream");
+      res.setHeader("cache-control", "no-cache, no-transform");
+      res.setHeader("x-accel-buffering", "no");
+      res.flushHeaders();
+      const writeEvent = (event: string, data: unknown) => {
+        res.write(`event: ${event}\n`);
+        res.write(`data: ${JSON.stringify(data)}\n\n`);
+      };
+      const count = await runtime.chatService.streamMessage(threadId, content, userId, signal, {
+        onDelta: (delta) => writeEvent("delta", delta),
+        onMessage: (message) => writeEvent("message", message)
+      });
+      if (!signal.aborted) {
+        writeEvent("done", { count });
+      }
+      res.end();
+      return;
+    }
     const emitted = runtime.chatService.sendMessage(threadId, content, userId);
     sendJson(res, { emitted });
     return;
@@ -396,8 +481,20 @@ const server = createServer(async (req, res) => {
     return;
   }
 
+  if (req.method === "POST" && req.url.startsWith("/requests/cancel")) {
+    const payload = await readValidatedBody(req, requestCancelSchema, "requests.cancel");
+    const controller = inflightRequests.get(payload.request_id);
+    controller?.abort(new Error("request cancelled"));
+    sendJson(res, { cancelled: Boolean(controller) });
+    return;
+  }
+
   if (req.method === "POST" && req.url.startsWith("/chat/rewind")) {
+    const signal = trackAbort(req, res);
     const payload = await readValidatedBody(req, chatRewindSchema, "chat.rewind");
+    if (signal.aborted) {
+      return;
+    }
     const threadId = payload.thread_id;
     const checkpointId = payload.checkpoint_id;
     const editPrompt = payload.edit_prompt;
@@ -474,7 +571,11 @@ const server = createServer(async (req, res) => {
     const url = new URL(req.url, `http://127.0.0.1:${port}`);
     const parts = url.pathname.split("/");
     const serverId = parseRouteId(parts[3] ?? "", "mcp.server_id");
+    const signal = trackAbort(req, res);
     const payload = await readValidatedBody(req, mcpInvokeSchema, "mcp.invoke");
+    if (signal.aborted) {
+      return;
+    }
     const toolId = payload.tool_id;
     const agentId = payload.agent_id;
     const args = payload.args;
@@ -499,6 +600,10 @@ const server = createServer(async (req, res) => {
   }
 
   if (req.method === "POST" && req.url.startsWith("/tasks/create")) {
+    const replayKey = idempotencyKey(req, "tasks.create");
+    if (replayIdempotent(req, res, replayKey)) {
+      return;
+    }
     const payload = await readValidatedBody(req, taskCreateSchema, "tasks.create");
     const now = new Date().toISOString();
     const description = typeof payload.description === "string" && payload.description.trim() ? payload.description.trim() : null;
@@ -516,6 +621,7 @@ const server = createServer(async (req, res) => {
       task.description = description;
     }
     runtime.repository.upsertTask(task);
+    rememberIdempotent(replayKey, { task });
     sendJson(res, { task });
     return;
   }
@@ -775,6 +881,70 @@ const server = createServer(async (req, res) => {
     return;
   }
 
+  if (req.method === "GET" && req.url.startsWith("/events/stream")) {
+    applyCors(req, res);
+    res.statusCode = 200;
+    res.setHeader("content-type", "text/event-stream");
+    res.setHeader("cache-control", "no-cache, no-transform");
+    res.setHeader("connection", "keep-alive");
+    res.setHeader("x-accel-buffering", "no");
+    res.flushHeaders();
+
+    const writeEvent = (event: string, payload: unknown) => {
+      res.write(`event: ${event}\n`);
+      res.write(`data: ${JSON.stringify(payload)}\n\n`);
+    };
+
+    const messageCounts = new Map<string, number>();
+    for (const thread of runtime.chatService.listThreads()) {
+      messageCounts.set(thread.id, runtime.chatService.listMessages(thread.id).length);
+    }
+    const seenPending = new Set(runtime.safetyWorkflow.listPending().map((grant) => grant.id));
+
+    const chatTimer = setInterval(() => {
+      for (const thread of runtime.chatService.listThreads()) {
+        const messages = runtime.chatService.listMessages(thread.id);
+        const previous = messageCounts.get(thread.id) ?? 0;
+        for (const message of messages.slice(previous)) {
+          writeEvent("chat.message", { threadId: thread.id, message });
+        }
+        messageCounts.set(thread.id, messages.length);
+      }
+    }, 1000);
+    const permissionTimer = setInterval(() => {
+      const pending = runtime.safetyWorkflow.listPending();
+      for (const grant of pending) {
+        if (!seenPending.has(grant.id)) {
+          writeEvent("permission.pending", grant);
+        }
+      }
+      seenPending.clear();
+      for (const grant of pending) {
+        seenPending.add(grant.id);
+      }
+    }, 2000);
+    const unsubscribeHeartbeat = runtime.events.on("instance.heartbeat", (event) => {
+      writeEvent("agent.heartbeat", { instanceId: event.instanceId, timestamp: event.timestamp, health: event.payload });
+    });
+    const unsubscribeTelemetry = runtime.telemetry.subscribe((event) => {
+      if (event.severity !== "info") {
+        writeEvent("health.alert", event);
+      }
+    });
+    const pingTimer = setInterval(() => {
+      writeEvent("ping", { at: new Date().toISOString() });
+    }, 15000);
+
+    req.on("close", () => {
+      clearInterval(chatTimer);
+      clearInterval(permissionTimer);
+      clearInterval(pingTimer);
+      unsubscribeHeartbeat();
+      unsubscribeTelemetry();
+    });
+    return;
+  }
+
   if (req.method === "GET" && req.url.startsWith("/telemetry/export")) {
     const url = new URL(req.url, `http://127.0.0.1:${port}`);
     const format = (url.searchParams.get("format") ?? "json") as "json" | "csv";
@@ -1001,6 +1171,10 @@ const server = createServer(async (req, res) => {
   }
 
   if (req.method === "POST" && req.url.startsWith("/vault/deposit")) {
+    const replayKey = idempotencyKey(req, "vault.deposit");
+    if (replayIdempotent(req, res, replayKey)) {
+      return;
+    }
     const payload = await readValidatedBody(req, vaultDepositSchema, "vault.deposit");
     const depositInput: {
       type: "archive" | "file" | "kb";
@@ -1030,6 +1204,7 @@ const server = createServer(async (req, res) => {
       message: "Vault entry deposited",
       metadata: { entryId: entry.id, type: entry.type, importanceScore: entry.importanceScore }
     });
+    rememberIdempotent(replayKey, { entry });
     sendJson(res, { entry });
     return;
   }
@@ -1055,9 +1230,10 @@ const server = createServer(async (req, res) => {
   }
 
   if (req.method === "POST" && req.url.startsWith("/vault/storage/relocate")) {
+    const signal = trackAbort(req, res);
     const payload = await readValidatedBody(req, vaultRelocateSchema, "vault.storage.relocate");
     try {
-      const info = await runtime.vault.relocate(payload.path, payload.move_existing);
+      const info = await runtime.vault.relocate(payload.path, payload.move_existing, signal);
       runtime.telemetry.record({
         category: "vault",
         source: "gateway.vault.relocate",
@@ -1172,6 +1348,7 @@ const server = createServer(async (req, res) => {
         console.error(
           JSON.stringify({
             level: "error",
+            timestamp: new Date().toISOString(),
             event: "http_request_error",
             requestId: reqId,
             method: req.method,
@@ -1193,6 +1370,7 @@ try {
   console.error(
     JSON.stringify({
       level: "error",
+      timestamp: new Date().toISOString(),
       event: "gateway_pid_lock_failed",
       message: error instanceof Error ? error.message : "unknown error"
     })
@@ -1218,13 +1396,13 @@ process.on("exit", () => {
   lifecycle.releasePidLock();
 });
 
-server.listen(port, "127.0.0.1", () => {
+server.listen(port, host, () => {
   runtime.telemetry.record({
     category: "lifecycle",
     source: "gateway.boot",
     message: "Gateway listening",
-    metadata: { port, startedAt }
+    metadata: { host, port, startedAt }
   });
   // eslint-disable-next-line no-console
-  console.log(`[majorclaw-gateway] listening on http://127.0.0.1:${port}`);
+  console.log(`[majorclaw-gateway] listening on http://${host}:${port}`);
 });
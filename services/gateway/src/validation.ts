@@ -156,6 +156,10 @@ export const vaultPruneSchema = z.object({
   max_importance: z.number().int().min(1).max(10).optional().default(3)
 });
 
+export const requestCancelSchema = z.object({
+  request_id: idString
+});
+
 export const vaultRelocateSchema = z.object({
   path: safeString(3, 4096),
   move_existing: z.boolean().optional().default(true)
@@ -98,12 +98,14 @@ export class VaultService {
     };
   }
 
-  async relocate(nextPath: string, moveExisting: boolean): Promise<VaultStorageInfo> {
+  async relocate(nextPath: string, moveExisting: boolean, signal?: AbortSignal): Promise<VaultStorageInfo> {
     const target = nextPath.trim();
     if (!target) {
       throw new Error("vault path is required");
     }
     await this.ensurePath(target);
+    // Last point where a cancelled relocation leaves the vault where it was.
+    signal?.throwIfAborted();
     const previous = this.rootPath;
     this.rootPath = target;
     this.repository.addAuditLog({